@@ -0,0 +1,33 @@
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use rand::rngs::SmallRng;
+
+use rustfall_engine::sandbox::Sandbox;
+use rustfall_engine::scenario::{Scenario, ScenarioReport};
+
+const TICKS: u64 = 100;
+
+/// Times one phase of the ticks of every demo, so an optimization shows up where it belongs.
+fn bench_phase(c: &mut Criterion, phase: &str, time: fn(&ScenarioReport) -> Duration) {
+    let mut group = c.benchmark_group(phase);
+    for scenario in Scenario::demos() {
+        group.bench_function(&scenario.name, |b| {
+            b.iter_custom(|iters| {
+                (0..iters)
+                    .map(|_| time(&Sandbox::<SmallRng>::run_scenario(scenario.clone(), TICKS)))
+                    .sum()
+            })
+        });
+    }
+    group.finish();
+}
+
+fn scenarios(c: &mut Criterion) {
+    bench_phase(c, "movement", |report| report.movement);
+    bench_phase(c, "interaction", |report| report.interaction);
+    bench_phase(c, "bookkeeping", |report| report.bookkeeping);
+}
+
+criterion_group!(benches, scenarios);
+criterion_main!(benches);
@@ -0,0 +1,149 @@
+#![no_main]
+
+use arbitrary::Arbitrary;
+use libfuzzer_sys::fuzz_target;
+use rand::rngs::SmallRng;
+use rustfall_engine::catalog;
+use rustfall_engine::command::Command;
+use rustfall_engine::event::EventKind;
+use rustfall_engine::sandbox::Sandbox;
+
+/// Longest side of the sandbox, small ones fill up quickly and run many ticks per second
+const MAX_SIDE: u8 = 48;
+/// Longest side of a brush
+const MAX_BRUSH: u8 = 8;
+
+/// What the player could do between two ticks.
+#[derive(Debug, Arbitrary)]
+enum Op {
+    Place {
+        element: u8,
+        x: u8,
+        y: u8,
+        width: u8,
+        height: u8,
+        force: bool,
+    },
+    Flick {
+        x: u8,
+        y: u8,
+        width: u8,
+        height: u8,
+        dx: i8,
+        dy: i8,
+    },
+    Erase {
+        x: u8,
+        y: u8,
+        width: u8,
+        height: u8,
+    },
+    Resize {
+        width: u8,
+        height: u8,
+    },
+    Tick {
+        ticks: u8,
+    },
+}
+
+#[derive(Debug, Arbitrary)]
+struct Input {
+    seed: u64,
+    width: u8,
+    height: u8,
+    ops: Vec<Op>,
+}
+
+fn side(n: u8) -> usize {
+    (n % MAX_SIDE) as usize + 1
+}
+
+fn brush(n: u8) -> usize {
+    (n % MAX_BRUSH) as usize + 1
+}
+
+/// Panics on any invariant the last checked tick found broken.
+fn check(sandbox: &Sandbox<SmallRng>) {
+    if let Some(event) = sandbox
+        .events()
+        .iter()
+        .find(|event| matches!(event.kind, EventKind::InvariantViolation(_)))
+    {
+        panic!("{:?} on tick {}", event, sandbox.tick_count());
+    }
+    assert_eq!(sandbox.pixels.len(), sandbox.width * sandbox.height);
+}
+
+// Plays random placements, throws, erasures, resizes and ticks on a seeded sandbox, so any crash found
+// replays the same way. Coordinates may fall past the border on purpose.
+fuzz_target!(|input: Input| {
+    let elements = catalog::catalog()
+        .iter()
+        .filter(|element| element.placeable)
+        .collect::<Vec<_>>();
+    let mut sandbox = Sandbox::<SmallRng>::new_with_seed(
+        side(input.width),
+        side(input.height),
+        input.seed,
+    );
+
+    for op in input.ops {
+        match op {
+            Op::Place {
+                element,
+                x,
+                y,
+                width,
+                height,
+                force,
+            } => sandbox.queue_command(Command::PlaceBrush {
+                pixel: elements[element as usize % elements.len()].pixel(),
+                x: x as usize,
+                y: y as usize,
+                width: brush(width),
+                height: brush(height),
+                force,
+            }),
+            Op::Flick {
+                x,
+                y,
+                width,
+                height,
+                dx,
+                dy,
+            } => sandbox.queue_command(Command::Flick {
+                x: x as usize,
+                y: y as usize,
+                width: brush(width),
+                height: brush(height),
+                dx,
+                dy,
+            }),
+            Op::Erase {
+                x,
+                y,
+                width,
+                height,
+            } => sandbox.queue_command(Command::Erase {
+                x: x as usize,
+                y: y as usize,
+                width: brush(width),
+                height: brush(height),
+            }),
+            Op::Resize { width, height } => sandbox.queue_command(Command::Resize {
+                width: side(width),
+                height: side(height),
+            }),
+            Op::Tick { ticks } => {
+                for _ in 0..=ticks % 16 {
+                    sandbox.tick_checked();
+                    check(&sandbox);
+                }
+            }
+        }
+    }
+    // applies the commands queued after the last tick
+    sandbox.tick_checked();
+    check(&sandbox);
+});
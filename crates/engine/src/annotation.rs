@@ -0,0 +1,33 @@
+/// Rule of the automaton that moved a pixel, see [`Sandbox::annotate`].
+///
+/// [`Sandbox::annotate`]: crate::sandbox::Sandbox::annotate
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, strum_macros::EnumIter)]
+pub enum MoveRule {
+    /// Fell straight down into an empty cell, or rose straight up for a gas
+    Gravity,
+    /// Traded places with a lighter pixel it sank through, or a heavier one it floated past
+    DensitySwap,
+    /// Slid sideways or diagonally into an empty cell, which is how piles and puddles spread
+    Dispersion,
+    /// Flew along the velocity it was thrown with
+    Thrown,
+}
+
+impl MoveRule {
+    pub fn name(&self) -> &'static str {
+        match self {
+            MoveRule::Gravity => "gravity",
+            MoveRule::DensitySwap => "density swap",
+            MoveRule::Dispersion => "dispersion",
+            MoveRule::Thrown => "thrown",
+        }
+    }
+}
+
+/// Move of a pixel during the last tick, and why it happened.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Annotation {
+    pub from: (usize, usize),
+    pub to: (usize, usize),
+    pub rule: MoveRule,
+}
@@ -0,0 +1,282 @@
+use std::fmt;
+use std::sync::OnceLock;
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::palette::Rgb;
+use crate::pixel::{Pixel, PixelFundamental, PixelKind};
+use crate::registry;
+
+/// Broad group an element belongs to, for grouping it in menus.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Ord, PartialOrd, Hash)]
+pub enum Category {
+    Powder,
+    Liquid,
+    Gas,
+    Solid,
+    Life,
+    Fire,
+    Electronics,
+    Special,
+}
+
+/// Two keys typed one after the other, selecting an element once the single keys run out.
+///
+/// Written as a string of the two keys, e.g. `"gp"`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Chord(pub char, pub char);
+
+impl TryFrom<String> for Chord {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        let mut keys = s.chars();
+        match (keys.next(), keys.next(), keys.next()) {
+            (Some(first), Some(second), None)
+                if !first.is_whitespace() && !second.is_whitespace() =>
+            {
+                Ok(Self(first, second))
+            }
+            _ => Err(format!("a chord is two keys, not {:?}", s)),
+        }
+    }
+}
+
+impl From<Chord> for String {
+    fn from(chord: Chord) -> Self {
+        chord.to_string()
+    }
+}
+
+impl fmt::Display for Chord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.0, self.1)
+    }
+}
+
+/// Everything a frontend needs to offer an element to the player.
+#[derive(Debug, Copy, Clone)]
+pub struct Element {
+    pub kind: PixelKind,
+    pub name: &'static str,
+    pub category: Category,
+    /// Key selecting the element, unique among the elements. Registered elements only reached
+    /// through their chord get a private use character instead, which still tells them apart
+    /// in snapshots
+    pub hotkey: char,
+    pub chord: Option<Chord>,
+    /// Colour of the element in its default state
+    pub color: Rgb,
+    /// Whether the element can be painted as is, rather than through a tool like the eraser
+    pub placeable: bool,
+    /// Registered elements all share a kind, so they are told apart by their default pixel
+    pixel: Pixel,
+}
+
+impl Element {
+    /// The element in its default state.
+    pub fn pixel(&self) -> Pixel {
+        self.pixel
+    }
+
+    /// Keys to type for the element, its hotkey unless it only has a chord.
+    pub fn keys(&self) -> String {
+        match self.chord {
+            Some(chord) if is_stand_in(self.hotkey) => chord.to_string(),
+            _ => self.hotkey.to_string(),
+        }
+    }
+
+    fn new(kind: PixelKind) -> Self {
+        use Category::*;
+
+        let (category, hotkey, color, placeable) = match kind {
+            PixelKind::Sand => (Powder, '1', Rgb(0xff, 0xaf, 0x00), true),
+            PixelKind::Rock => (Solid, '2', Rgb(0xe4, 0xe4, 0xe4), true),
+            PixelKind::Water => (Liquid, '3', Rgb(0x00, 0x37, 0xda), true),
+            PixelKind::Steam => (Gas, '4', Rgb(0x5f, 0x87, 0xff), true),
+            PixelKind::Ice => (Solid, '5', Rgb(0xd7, 0xff, 0xff), true),
+            PixelKind::Fire => (Fire, '6', Rgb(0xd7, 0x00, 0x00), true),
+            PixelKind::EternalFire => (Fire, '7', Rgb(0x5f, 0x00, 0x00), true),
+            PixelKind::Wood => (Solid, '8', Rgb(0xd7, 0xd7, 0x00), true),
+            PixelKind::Lava => (Liquid, '9', Rgb(0xd7, 0x5f, 0x00), true),
+            PixelKind::Obsidian => (Solid, 'o', Rgb(0x5f, 0x00, 0x87), true),
+            PixelKind::Gravel => (Powder, 'g', Rgb(0x80, 0x80, 0x80), true),
+            PixelKind::WetSand => (Powder, 'W', Rgb(0xd7, 0x87, 0x00), true),
+            PixelKind::Soil => (Powder, 's', Rgb(0x87, 0x5f, 0x00), true),
+            PixelKind::Grass => (Life, 'r', Rgb(0x00, 0xaf, 0x00), true),
+            PixelKind::Mud => (Solid, 'm', Rgb(0x5f, 0x5f, 0x00), true),
+            // composites, placed whole and centred on the brush
+            PixelKind::Boulder => (Solid, 'B', Rgb(0x6c, 0x6c, 0x6c), true),
+            PixelKind::Plank => (Solid, 'P', Rgb(0xaf, 0x87, 0x5f), true),
+            PixelKind::Vine => (Life, 'i', Rgb(0x00, 0x87, 0x00), true),
+            PixelKind::Fungus => (Life, 'M', Rgb(0xaf, 0x87, 0xaf), true),
+            PixelKind::Dust => (Powder, 'k', Rgb(0xd7, 0xaf, 0x87), true),
+            PixelKind::Foam => (Liquid, 'n', Rgb(0xff, 0xff, 0xff), true),
+            PixelKind::Oil => (Liquid, 'O', Rgb(0x87, 0x87, 0x00), true),
+            PixelKind::Poison => (Liquid, 'p', Rgb(0x87, 0x00, 0xff), true),
+            PixelKind::PoisonedWater => (Liquid, 'w', Rgb(0x5f, 0xaf, 0x5f), true),
+            PixelKind::ToxicGas => (Gas, 'x', Rgb(0xaf, 0xd7, 0x00), true),
+            PixelKind::Cloud => (Gas, 'C', Rgb(0xbc, 0xbc, 0xbc), true),
+            PixelKind::Fog => (Gas, 'F', Rgb(0xd7, 0xd7, 0xd7), true),
+            PixelKind::Wire => (Electronics, 'E', Rgb(0xaf, 0x5f, 0x00), true),
+            PixelKind::Battery => (Electronics, 'b', Rgb(0xff, 0x5f, 0x5f), true),
+            PixelKind::Switch => (Electronics, 'c', Rgb(0x80, 0x80, 0x80), true),
+            PixelKind::Timer => (Electronics, 't', Rgb(0x00, 0xaf, 0xaf), true),
+            PixelKind::Turbine => (Electronics, 'T', Rgb(0x87, 0xaf, 0xd7), true),
+            // a composite too
+            PixelKind::WaterWheel => (Electronics, 'H', Rgb(0x87, 0x5f, 0x00), true),
+            // needs an element and a rate picked first
+            PixelKind::Spawner => (Special, 'z', Rgb(0xaf, 0x00, 0xaf), false),
+            PixelKind::LevitationField => (Special, 'h', Rgb(0xd7, 0xaf, 0xff), true),
+            PixelKind::Salt => (Powder, 'j', Rgb(0xda, 0xda, 0xda), true),
+            PixelKind::Rubber => (Powder, 'R', Rgb(0xd7, 0x5f, 0x87), true),
+            PixelKind::Gunpowder => (Powder, 'G', Rgb(0x4e, 0x4e, 0x4e), true),
+            PixelKind::Tnt => (Special, 'X', Rgb(0xd7, 0x00, 0x5f), true),
+            PixelKind::Pipe => (Special, 'y', Rgb(0x58, 0x58, 0x58), true),
+            PixelKind::Pump => (Special, 'q', Rgb(0x00, 0x5f, 0xaf), true),
+            PixelKind::Spout => (Special, 'U', Rgb(0x5f, 0x87, 0xaf), true),
+            PixelKind::Drain => (Special, 'D', Rgb(0x26, 0x26, 0x26), true),
+            PixelKind::Sieve => (Special, 'S', Rgb(0x8a, 0x8a, 0x8a), true),
+            // erases instead
+            PixelKind::Void => (Special, '0', Rgb(0x00, 0x00, 0x00), false),
+            // one entry for each registered element instead, see `registered`
+            PixelKind::Custom => (Special, '\0', Rgb(0x00, 0x00, 0x00), false),
+        };
+
+        Self {
+            kind,
+            name: kind.pixel().name(),
+            category,
+            hotkey,
+            chord: None,
+            color,
+            placeable,
+            pixel: kind.pixel(),
+        }
+    }
+
+    fn registered(pixel: Pixel) -> Option<Self> {
+        let Pixel::Custom(custom) = pixel else {
+            return None;
+        };
+        let descriptor = custom.descriptor()?;
+        Some(Self {
+            kind: PixelKind::Custom,
+            name: descriptor.name.as_str(),
+            category: descriptor.category(),
+            hotkey: descriptor.hotkey.unwrap_or_else(|| stand_in(custom.id())),
+            chord: descriptor.chord,
+            color: descriptor.color,
+            placeable: true,
+            pixel,
+        })
+    }
+}
+
+/// First character of the private use area, where the stand-in hotkeys start.
+const STAND_INS: u32 = 0xe000;
+
+/// Hotkey of registered element `id` when it only has a chord.
+fn stand_in(id: u16) -> char {
+    char::from_u32(STAND_INS + id as u32).expect("the private use area is valid")
+}
+
+fn is_stand_in(hotkey: char) -> bool {
+    (STAND_INS..=STAND_INS + u16::MAX as u32).contains(&(hotkey as u32))
+}
+
+/// The elements built into the engine, in no particular order.
+///
+/// Unlike [`catalog`], doesn't freeze the [`registry::PixelRegistry`].
+pub(crate) fn builtin() -> Vec<Element> {
+    PixelKind::iter()
+        .filter(|&kind| kind != PixelKind::Custom)
+        .map(Element::new)
+        .collect()
+}
+
+/// Every element, registered ones included, sorted by hotkey as the palette lists them.
+pub fn catalog() -> &'static [Element] {
+    static CATALOG: OnceLock<Vec<Element>> = OnceLock::new();
+    CATALOG.get_or_init(|| {
+        let mut elements = builtin();
+        elements.extend(
+            registry::registry()
+                .pixels()
+                .filter_map(Element::registered),
+        );
+        elements.sort_by_key(|element| element.hotkey);
+        elements
+    })
+}
+
+/// Catalog entry of `pixel`'s element.
+pub fn element(pixel: &Pixel) -> &'static Element {
+    let kind = PixelKind::from(pixel);
+    catalog()
+        .iter()
+        .find(|element| {
+            element.kind == kind && (kind != PixelKind::Custom || element.name == pixel.name())
+        })
+        .expect("every kind is in the catalog")
+}
+
+/// Element bound to `hotkey`, if any.
+pub fn by_hotkey(hotkey: char) -> Option<&'static Element> {
+    catalog().iter().find(|element| element.hotkey == hotkey)
+}
+
+/// Element bound to `chord`, if any.
+pub fn by_chord(chord: Chord) -> Option<&'static Element> {
+    catalog()
+        .iter()
+        .find(|element| element.chord == Some(chord))
+}
+
+/// Elements whose chord starts with `key`, sorted by the second key.
+pub fn completions(key: char) -> Vec<&'static Element> {
+    let mut elements = catalog()
+        .iter()
+        .filter(|element| element.chord.is_some_and(|chord| chord.0 == key))
+        .collect::<Vec<_>>();
+    elements.sort_by_key(|element| element.chord.map(|chord| chord.1));
+    elements
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::HashSet;
+
+    use strum::IntoEnumIterator;
+
+    use crate::catalog::{by_hotkey, catalog, element, Chord};
+    use crate::pixel::{Pixel, PixelFundamental};
+
+    #[test]
+    fn test_catalog() {
+        // no elements are registered in the tests
+        assert_eq!(catalog().len(), Pixel::iter().count() - 1);
+        let hotkeys = catalog().iter().map(|e| e.hotkey).collect::<HashSet<_>>();
+        assert_eq!(hotkeys.len(), catalog().len(), "hotkeys must be unique");
+
+        for pixel in Pixel::iter().filter(|p| !matches!(p, Pixel::Custom(_))) {
+            let element = element(&pixel);
+            assert_eq!(element.name, pixel.name());
+            assert_eq!(by_hotkey(element.hotkey).unwrap().kind, element.kind);
+            assert_eq!(element.keys(), element.hotkey.to_string());
+        }
+    }
+
+    #[test]
+    fn test_chord() {
+        let chord = Chord::try_from("gp".to_string()).unwrap();
+        assert_eq!(chord, Chord('g', 'p'));
+        assert_eq!(String::from(chord), "gp");
+        for s in ["", "g", "gpx", "g "] {
+            assert!(Chord::try_from(s.to_string()).is_err(), "{:?}", s);
+        }
+    }
+}
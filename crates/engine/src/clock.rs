@@ -0,0 +1,72 @@
+//! Time of day in the world, derived from the tick count of a sandbox.
+
+use std::fmt::{Display, Formatter};
+
+/// Time of day on the in-world clock, see [`SandboxConfig::day_length`].
+///
+/// [`SandboxConfig::day_length`]: crate::config::SandboxConfig::day_length
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct WorldTime {
+    /// Counted from 1
+    pub day: u64,
+    pub hour: u8,
+    pub minute: u8,
+}
+
+impl WorldTime {
+    const MINUTES_PER_DAY: u64 = 24 * 60;
+    /// The world starts in the morning, at this hour
+    const DAWN: u8 = 6;
+    const DUSK: u8 = 18;
+
+    /// Time after `ticks` ticks, with days lasting `day_length` ticks.
+    pub fn at(ticks: u64, day_length: u32) -> Self {
+        let elapsed = ticks as u128 * Self::MINUTES_PER_DAY as u128 / day_length.max(1) as u128;
+        let minutes = elapsed as u64 + Self::DAWN as u64 * 60;
+        Self {
+            day: minutes / Self::MINUTES_PER_DAY + 1,
+            hour: (minutes % Self::MINUTES_PER_DAY / 60) as u8,
+            minute: (minutes % 60) as u8,
+        }
+    }
+
+    pub fn is_night(&self) -> bool {
+        self.hour < Self::DAWN || self.hour >= Self::DUSK
+    }
+}
+
+impl Display for WorldTime {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Day {} {:02}:{:02}", self.day, self.hour, self.minute)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+
+    use crate::clock::WorldTime;
+    use crate::config::SandboxConfig;
+    use crate::sandbox::Sandbox;
+
+    #[test]
+    fn test_world_time() {
+        let dawn = WorldTime::at(0, 1440);
+        assert_eq!(dawn.to_string(), "Day 1 06:00");
+        assert!(!dawn.is_night());
+        assert!(WorldTime::at(720, 1440).is_night());
+        assert_eq!(WorldTime::at(1440 + 90, 1440).to_string(), "Day 2 07:30");
+
+        let mut sandbox = Sandbox::<SmallRng>::new(2, 2);
+        assert_eq!(sandbox.world_time(), None);
+        sandbox.set_config(SandboxConfig {
+            day_length: Some(24),
+            ..Default::default()
+        });
+        for _ in 0..3 {
+            sandbox.tick();
+        }
+        assert_eq!(sandbox.tick_count(), 3);
+        assert_eq!(sandbox.world_time().unwrap().hour, 9);
+    }
+}
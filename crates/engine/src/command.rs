@@ -0,0 +1,240 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entity::EntityKind;
+use crate::pixel::spawner::Spawner;
+use crate::pixel::spout::Spout;
+use crate::pixel::{by_name, Direction, Pixel};
+
+/// Input queued by a frontend and applied by the engine at the start of the next tick.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Command {
+    /// Place `pixel` over the rectangle starting at (`x`, `y`).
+    ///
+    /// Occupied cells are left untouched unless `force` is set.
+    PlaceBrush {
+        #[serde(with = "by_name")]
+        pixel: Pixel,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        force: bool,
+    },
+    /// Place spawners of `element` emitting every `period` ticks over the rectangle starting at
+    /// (`x`, `y`), leaving occupied cells untouched.
+    PlaceSpawner {
+        #[serde(with = "by_name")]
+        element: Pixel,
+        period: u8,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    },
+    /// Place spouts pouring `element` over the rectangle starting at (`x`, `y`), leaving occupied
+    /// cells untouched.
+    PlaceSpout {
+        #[serde(with = "by_name")]
+        element: Pixel,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    },
+    /// Let the pixel at (`x`, `y`) interact once with `source`, as if it were a neighbour.
+    TriggerEvent {
+        #[serde(with = "by_name")]
+        source: Pixel,
+        x: usize,
+        y: usize,
+    },
+    /// Throw the movable pixels over the rectangle starting at (`x`, `y`), giving them a
+    /// velocity of (`dx`, `dy`) cells per tick.
+    Flick {
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        dx: i8,
+        dy: i8,
+    },
+    /// Remove the movable pixels over the rectangle starting at (`x`, `y`), reporting them in
+    /// [`Sandbox::take_vacuumed`](crate::sandbox::Sandbox::take_vacuumed).
+    Vacuum {
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    },
+    /// Empty the cells over the rectangle starting at (`x`, `y`), see
+    /// [`Sandbox::erase_area`](crate::sandbox::Sandbox::erase_area).
+    Erase {
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    },
+    /// Activate the pixels over the rectangle starting at (`x`, `y`), as if the user clicked
+    /// them.
+    Activate {
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    },
+    /// Turn the pixels of `from` over the rectangle starting at (`x`, `y`) into `to` in place,
+    /// keeping their velocity and fall.
+    Transmute {
+        #[serde(with = "by_name")]
+        from: Pixel,
+        #[serde(with = "by_name")]
+        to: Pixel,
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    },
+    /// Freeze the cells over the rectangle starting at (`x`, `y`), or thaw them when `frozen` is
+    /// unset. Frozen cells neither move nor react, and nothing moves into them.
+    Freeze {
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+        frozen: bool,
+    },
+    /// Spawn an `entity` with its top left cell at (`x`, `y`), if it fits there.
+    SpawnEntity {
+        entity: EntityKind,
+        x: usize,
+        y: usize,
+    },
+    /// Remove `entity` from the sandbox.
+    RemoveEntity { entity: u32 },
+    /// Steer the player `entity`, see [`Sandbox::steer_entity`](crate::sandbox::Sandbox::steer_entity).
+    Steer { entity: u32, direction: Direction },
+    /// Tell the engine the rectangle starting at (`x`, `y`) is what the player is looking at,
+    /// so the far field can be updated less often, see
+    /// [`SandboxConfig::far_field_period`](crate::config::SandboxConfig::far_field_period).
+    Focus {
+        x: usize,
+        y: usize,
+        width: usize,
+        height: usize,
+    },
+    /// Resize the sandbox, keeping its content centered.
+    Resize { width: usize, height: usize },
+}
+
+impl Command {
+    /// Pixel placed by the command, and whether it overwrites occupied cells.
+    pub fn placement(&self) -> Option<(Pixel, bool)> {
+        match *self {
+            Command::PlaceBrush { pixel, force, .. } => Some((pixel, force)),
+            Command::PlaceSpawner {
+                element, period, ..
+            } => Some((Spawner::new(element.into(), period).into(), false)),
+            Command::PlaceSpout { element, .. } => Some((Spout::new(element.into()).into(), false)),
+            _ => None,
+        }
+    }
+
+    /// Cells covered by the command, which may lie outside the sandbox.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> {
+        let (x, y, width, height) = match *self {
+            // a composite is placed whole, centred on the brush and kept inside the top left
+            Command::PlaceBrush {
+                pixel,
+                x,
+                y,
+                width,
+                height,
+                ..
+            } if pixel.footprint().is_some() => {
+                let (footprint_width, footprint_height) = pixel.footprint().unwrap();
+                (
+                    (x + width / 2).saturating_sub(footprint_width / 2),
+                    (y + height / 2).saturating_sub(footprint_height / 2),
+                    footprint_width,
+                    footprint_height,
+                )
+            }
+            Command::PlaceBrush {
+                x,
+                y,
+                width,
+                height,
+                ..
+            }
+            | Command::PlaceSpawner {
+                x,
+                y,
+                width,
+                height,
+                ..
+            }
+            | Command::PlaceSpout {
+                x,
+                y,
+                width,
+                height,
+                ..
+            } => (x, y, width, height),
+            Command::TriggerEvent { x, y, .. } => (x, y, 1, 1),
+            Command::SpawnEntity { entity, x, y } => {
+                let (width, height) = entity.footprint();
+                (x, y, width, height)
+            }
+            Command::Flick {
+                x,
+                y,
+                width,
+                height,
+                ..
+            }
+            | Command::Vacuum {
+                x,
+                y,
+                width,
+                height,
+            }
+            | Command::Erase {
+                x,
+                y,
+                width,
+                height,
+            }
+            | Command::Activate {
+                x,
+                y,
+                width,
+                height,
+            }
+            | Command::Focus {
+                x,
+                y,
+                width,
+                height,
+            }
+            | Command::Freeze {
+                x,
+                y,
+                width,
+                height,
+                ..
+            }
+            | Command::Transmute {
+                x,
+                y,
+                width,
+                height,
+                ..
+            } => (x, y, width, height),
+            Command::Resize { .. } | Command::RemoveEntity { .. } | Command::Steer { .. } => {
+                (0, 0, 0, 0)
+            }
+        };
+        (x..x + width).flat_map(move |x| (y..y + height).map(move |y| (x, y)))
+    }
+}
@@ -0,0 +1,122 @@
+use std::collections::BTreeMap;
+
+use rand::rngs::SmallRng;
+use serde::Serialize;
+
+use crate::config::SandboxConfig;
+use crate::pixel::PixelKind;
+use crate::sandbox::Sandbox;
+use crate::scenario::{Scenario, ScenarioPlayer};
+
+/// How far apart the two runs of a [`Comparison`] are at a tick.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct Divergence {
+    pub tick: u64,
+    /// Cells holding a different element in each run
+    pub cells: usize,
+    /// Share of the cells that differ, from 0 to 1
+    pub ratio: f64,
+    /// Pixels that became another element in one run but not in the other, going by the counts
+    /// of every element, so pixels that merely ended up in other places don't count
+    pub census: usize,
+}
+
+/// Plays a scenario twice from the same seed, each run under its own config, to measure how
+/// much a setting changes the way things play out.
+///
+/// Both runs get the same scene and timeline and draw the same random numbers until their
+/// configs make them do different things, so any divergence comes from the configs.
+#[derive(Debug)]
+pub struct Comparison {
+    runs: [(Sandbox<SmallRng>, ScenarioPlayer); 2],
+}
+
+impl Comparison {
+    pub fn new(scenario: &Scenario, seed: u64, configs: [SandboxConfig; 2]) -> Self {
+        let runs = configs.map(|config| {
+            let mut sandbox =
+                Sandbox::<SmallRng>::new_with_seed(scenario.width, scenario.height, seed);
+            sandbox.set_config(config);
+            for placement in &scenario.scene {
+                for command in placement.commands() {
+                    sandbox.queue_command(command);
+                }
+            }
+            (sandbox, ScenarioPlayer::new(scenario.clone()))
+        });
+        Self { runs }
+    }
+
+    /// Ticks both runs, playing the steps of the timeline that are due without pausing.
+    pub fn tick(&mut self) {
+        for (sandbox, player) in &mut self.runs {
+            while player.tick(sandbox) {}
+        }
+    }
+
+    pub fn sandboxes(&self) -> [&Sandbox<SmallRng>; 2] {
+        [&self.runs[0].0, &self.runs[1].0]
+    }
+
+    pub fn divergence(&self) -> Divergence {
+        let [a, b] = self.sandboxes();
+        let cells = a
+            .pixels
+            .iter()
+            .zip(&b.pixels)
+            .filter(|(a, b)| PixelKind::from(&a.pixel()) != PixelKind::from(&b.pixel()))
+            .count();
+
+        let mut counts = BTreeMap::<&str, isize>::new();
+        for (name, count) in a.census() {
+            *counts.entry(name).or_default() += count as isize;
+        }
+        for (name, count) in b.census() {
+            *counts.entry(name).or_default() -= count as isize;
+        }
+        let census = counts
+            .values()
+            .map(|count| count.unsigned_abs())
+            .sum::<usize>();
+
+        Divergence {
+            tick: a.tick_count(),
+            cells,
+            ratio: cells as f64 / a.pixels.len().max(1) as f64,
+            // such a pixel is short from one element and extra in another
+            census: census / 2,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::compare::Comparison;
+    use crate::config::{SandboxConfig, UpdateOrder};
+    use crate::scenario::Scenario;
+
+    #[test]
+    fn test_comparison() {
+        let scenario = Scenario::demos().remove(0);
+        let same = SandboxConfig::default();
+        let mut comparison = Comparison::new(&scenario, 7, [same, same]);
+        for _ in 0..50 {
+            comparison.tick();
+            assert_eq!(comparison.divergence().cells, 0);
+        }
+        assert_eq!(comparison.divergence().tick, 50);
+
+        // visiting the cells in another order is enough to move pixels elsewhere
+        let alternating = SandboxConfig {
+            update_order: UpdateOrder::Alternating,
+            ..same
+        };
+        let mut comparison = Comparison::new(&scenario, 7, [same, alternating]);
+        for _ in 0..50 {
+            comparison.tick();
+        }
+        let divergence = comparison.divergence();
+        assert!(divergence.cells > 0);
+        assert!(divergence.ratio > 0.0 && divergence.ratio < 1.0);
+    }
+}
@@ -0,0 +1,75 @@
+use crate::pixel::boulder::Boulder;
+use crate::pixel::plank::Plank;
+use crate::pixel::water_wheel::WaterWheel;
+use crate::pixel::Pixel;
+
+/// Element spanning a fixed footprint of cells that act as one, e.g. a boulder falling whole.
+///
+/// Every cell holds a pixel of the element knowing its part, its row major position in the
+/// footprint, and the top left cell at part 0 anchors the composite. The sandbox keeps the cells
+/// together: solid composites fall as a group, and a composite missing any of its cells falls
+/// apart into debris.
+pub trait Composite: Copy + Into<Pixel> {
+    /// Width and height in cells
+    const FOOTPRINT: (usize, usize);
+
+    fn part(&self) -> u8;
+
+    /// The cell at `part` of a composite in the same state.
+    fn with_part(&self, part: u8) -> Self;
+
+    /// What the cells turn into when the composite loses one of them.
+    fn debris(&self) -> Pixel;
+}
+
+impl Pixel {
+    /// Width and height of the composite the pixel is a cell of, if it is one.
+    pub fn footprint(&self) -> Option<(usize, usize)> {
+        match self {
+            Pixel::Boulder(_) => Some(Boulder::FOOTPRINT),
+            Pixel::Plank(_) => Some(Plank::FOOTPRINT),
+            Pixel::WaterWheel(_) => Some(WaterWheel::FOOTPRINT),
+            _ => None,
+        }
+    }
+
+    /// Position of the cell in its composite, `0` for pixels filling a single cell.
+    pub fn part(&self) -> u8 {
+        match self {
+            Pixel::Boulder(p) => p.part(),
+            Pixel::Plank(p) => p.part(),
+            Pixel::WaterWheel(p) => p.part(),
+            _ => 0,
+        }
+    }
+
+    /// The cell at `part` of the same composite, or the pixel itself if it isn't one.
+    pub fn with_part(&self, part: u8) -> Pixel {
+        match self {
+            Pixel::Boulder(p) => p.with_part(part).into(),
+            Pixel::Plank(p) => p.with_part(part).into(),
+            Pixel::WaterWheel(p) => p.with_part(part).into(),
+            _ => *self,
+        }
+    }
+
+    /// What the cell turns into when its composite is broken, see [`Composite::debris`].
+    pub fn debris(&self) -> Pixel {
+        match self {
+            Pixel::Boulder(p) => p.debris(),
+            Pixel::Plank(p) => p.debris(),
+            Pixel::WaterWheel(p) => p.debris(),
+            _ => *self,
+        }
+    }
+}
+
+/// Cells of a `width` by `height` footprint with its top left cell at (`x`, `y`), in the order
+/// of their parts.
+pub fn footprint_cells(
+    x: usize,
+    y: usize,
+    (width, height): (usize, usize),
+) -> impl Iterator<Item = (usize, usize)> {
+    (y..y + height).flat_map(move |y| (x..x + width).map(move |x| (x, y)))
+}
@@ -0,0 +1,62 @@
+use serde::{Deserialize, Serialize};
+
+/// Order the movement pass visits the cells of a row in.
+///
+/// Rows are always visited bottom to top, so falling pixels leave room for the ones above them
+/// within the same tick. Whichever cell of a row moves first wins a contested spot, so a fixed
+/// order favours one direction.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateOrder {
+    /// Right to left. The cheapest, but liquids visibly drift to the right
+    #[default]
+    Fixed,
+    /// Flips direction every row and every tick. Cancels the drift out over a few ticks at no
+    /// cost, but wide flat pools can still ripple in a checkered pattern
+    Alternating,
+    /// A new seeded permutation of the columns of every row on every tick. No bias at all, at
+    /// the cost of a shuffle per row and noisier looking flows
+    Shuffled,
+}
+
+/// Percent of their speed pixels lose to the air on every tick, for each type of pixel.
+///
+/// Thrown pixels lose the fraction of a cell left over as often as it amounts to, so even slow
+/// ones are held back. Falls only lose whole cells, settling at a terminal speed of about
+/// `100 / drag` cells per tick instead of speeding up forever.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AirDrag {
+    pub solid: u8,
+    pub liquid: u8,
+    /// High, so puffs of gas lose their push within a few cells
+    pub gas: u8,
+}
+
+impl Default for AirDrag {
+    fn default() -> Self {
+        Self {
+            solid: 4,
+            liquid: 5,
+            gas: 40,
+        }
+    }
+}
+
+/// Settings of a sandbox that change how the simulation plays out.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SandboxConfig {
+    pub update_order: UpdateOrder,
+    /// Solids falling fast into a liquid throw some of it up instead of quietly sinking
+    pub splash: bool,
+    /// Degrees Celsius of the air, which everything slowly cools down or warms up to, room
+    /// temperature if unset
+    pub ambient: Option<i16>,
+    /// Ticks between two updates of the chunks away from the focus, which keeps huge worlds
+    /// affordable. Every chunk is updated on every tick if unset
+    pub far_field_period: Option<u8>,
+    /// Ticks a day of the in-world clock lasts, the world keeps no time of day if unset
+    pub day_length: Option<u32>,
+    pub drag: AirDrag,
+}
@@ -0,0 +1,111 @@
+use crate::event::{EventKind, SandboxEvent};
+
+/// Look of a visual effect.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum EffectKind {
+    /// A few grains kicked up above a hard landing
+    Puff,
+    /// A ring spreading out from a splash
+    Ring,
+}
+
+/// Short-lived decoration drawn above the grid, it never touches the pixels.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Effect {
+    pub kind: EffectKind,
+    pub x: usize,
+    pub y: usize,
+    /// Ticks since the effect appeared
+    pub age: u8,
+    /// Ticks the effect lasts for
+    pub life: u8,
+}
+
+impl Effect {
+    fn from_event(event: &SandboxEvent) -> Option<Self> {
+        let (kind, life) = match event.kind {
+            EventKind::Impact => (EffectKind::Puff, 3),
+            // harder splashes make wider rings
+            EventKind::Splash => (EffectKind::Ring, (2 + event.magnitude / 10).min(5) as u8),
+            // the ring of a blast spreads past the destroyed cells, as far as its shockwave
+            EventKind::Explosion => (EffectKind::Ring, (event.magnitude * 2).min(12) as u8),
+            _ => return None,
+        };
+        Some(Self {
+            kind,
+            x: event.x,
+            y: event.y,
+            age: 0,
+            life,
+        })
+    }
+
+    /// Offsets from (`x`, `y`) of the cells the effect covers at its current age.
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        let r = self.age as isize + 1;
+        match self.kind {
+            EffectKind::Puff => vec![(-r, -r), (0, -r - 1), (r, -r)],
+            EffectKind::Ring => (-r..=r)
+                .flat_map(|dx| (-r..=r).map(move |dy| (dx, dy)))
+                .filter(|(dx, dy)| (dx * dx + dy * dy - r * r).abs() <= r)
+                .collect(),
+        }
+    }
+}
+
+/// Visual effects spawned by the events of the sandbox.
+#[derive(Debug, Default)]
+pub struct Effects {
+    effects: Vec<Effect>,
+}
+
+impl Effects {
+    /// Ages the current effects and spawns the ones for the `events` of the last tick.
+    pub fn tick(&mut self, events: &[SandboxEvent]) {
+        self.effects.iter_mut().for_each(|e| e.age += 1);
+        self.effects.retain(|e| e.age < e.life);
+        self.effects
+            .extend(events.iter().filter_map(Effect::from_event));
+    }
+
+    /// Cells covered by every effect, which may lie past the border of the sandbox.
+    pub fn cells(&self) -> impl Iterator<Item = (isize, isize, EffectKind)> + '_ {
+        self.effects.iter().flat_map(|e| {
+            e.offsets()
+                .into_iter()
+                .map(move |(dx, dy)| (e.x as isize + dx, e.y as isize + dy, e.kind))
+        })
+    }
+
+    pub fn clear(&mut self) {
+        self.effects.clear();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::effect::Effects;
+    use crate::event::{EventKind, SandboxEvent};
+
+    #[test]
+    fn test_effects_fade() {
+        let mut effects = Effects::default();
+        effects.tick(&[SandboxEvent {
+            kind: EventKind::Splash,
+            x: 5,
+            y: 5,
+            magnitude: 20,
+        }]);
+        let first = effects.cells().count();
+        assert!(first > 0);
+
+        effects.tick(&[]);
+        // the ring spreads out
+        assert!(effects.cells().count() > first);
+
+        for _ in 0..4 {
+            effects.tick(&[]);
+        }
+        assert_eq!(effects.cells().count(), 0);
+    }
+}
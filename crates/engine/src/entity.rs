@@ -0,0 +1,185 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Pixel, PixelFundamental, PixelType};
+use crate::sandbox::Sandbox;
+
+/// What an entity is, which decides its footprint and how it moves.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    /// Falls, sinks through liquids and bounces off solids
+    Ball,
+    /// Falls and floats on liquids
+    Boat,
+    /// Walks, jumps, swims and digs as told with [`Command::Steer`](crate::command::Command::Steer),
+    /// and gets hurt by hot and toxic pixels
+    Player,
+}
+
+impl EntityKind {
+    /// Width and height in cells.
+    pub fn footprint(self) -> (usize, usize) {
+        match self {
+            EntityKind::Ball => (2, 2),
+            EntityKind::Boat => (6, 2),
+            EntityKind::Player => (2, 4),
+        }
+    }
+}
+
+/// Object moving over the grid rather than being part of it.
+///
+/// Entities are blocked by solids and walls, and move through empty cells, liquids and gases
+/// without displacing them. Pixels don't see entities at all.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Entity {
+    pub id: u32,
+    pub kind: EntityKind,
+    /// Top left cell of the footprint
+    pub x: usize,
+    pub y: usize,
+    /// Cells travelled per tick along each axis
+    pub velocity: (i8, i8),
+    /// Only players get hurt, the entity is removed when it runs out
+    pub health: u8,
+}
+
+impl Entity {
+    /// Fastest an entity falls through the air, in cells per tick
+    const MAX_FALL: i8 = 4;
+    /// Slowest a ball lands at and still bounces
+    const BOUNCE_SPEED: i8 = 2;
+    pub const MAX_HEALTH: u8 = 100;
+    /// Speed a player jumps off the ground at
+    const JUMP_SPEED: i8 = -3;
+
+    pub(crate) fn new(id: u32, kind: EntityKind, x: usize, y: usize) -> Self {
+        Self {
+            id,
+            kind,
+            x,
+            y,
+            velocity: (0, 0),
+            health: Self::MAX_HEALTH,
+        }
+    }
+
+    pub fn is_alive(&self) -> bool {
+        self.health > 0
+    }
+
+    pub fn footprint(&self) -> (usize, usize) {
+        self.kind.footprint()
+    }
+
+    /// Whether the cell at (`x`, `y`) is under the entity.
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        let (width, height) = self.footprint();
+        (self.x..self.x + width).contains(&x) && (self.y..self.y + height).contains(&y)
+    }
+
+    /// Cells under the entity, which may lie outside the sandbox.
+    pub fn cells(&self) -> impl Iterator<Item = (usize, usize)> {
+        let (x, y) = (self.x, self.y);
+        let (width, height) = self.footprint();
+        (y..y + height).flat_map(move |y| (x..x + width).map(move |x| (x, y)))
+    }
+
+    /// Whether the footprint fits with its top left cell at (`x`, `y`).
+    pub(crate) fn fits<R: Rng>(&self, sandbox: &Sandbox<R>, x: isize, y: isize) -> bool {
+        let (width, height) = self.footprint();
+        x >= 0
+            && y >= 0
+            && (y as usize..y as usize + height).all(|y| {
+                (x as usize..x as usize + width).all(|x| {
+                    sandbox.is_coordinate_in_bound(x, y)
+                        && !matches!(
+                            sandbox.pixels[sandbox.coordinates_to_index(x, y)]
+                                .pixel()
+                                .pixel_type(),
+                            PixelType::Solid(_) | PixelType::Wall
+                        )
+                })
+            })
+    }
+
+    /// Number of cells under the entity holding a pixel matching `f`.
+    fn count_pixels<R: Rng>(&self, sandbox: &Sandbox<R>, f: impl Fn(Pixel) -> bool) -> usize {
+        self.cells()
+            .filter(|&(x, y)| {
+                sandbox.is_coordinate_in_bound(x, y)
+                    && f(sandbox.pixels[sandbox.coordinates_to_index(x, y)].pixel())
+            })
+            .count()
+    }
+
+    /// Whether the entity stands on something or swims, and so can jump.
+    pub(crate) fn can_jump<R: Rng>(&self, sandbox: &Sandbox<R>) -> bool {
+        !self.fits(sandbox, self.x as isize, self.y as isize + 1)
+            || self.count_pixels(sandbox, |p| matches!(p.pixel_type(), PixelType::Liquid(_))) > 0
+    }
+
+    /// Jumps, or swims a stroke up in a liquid.
+    pub(crate) fn jump<R: Rng>(&mut self, sandbox: &Sandbox<R>) {
+        if self.can_jump(sandbox) {
+            self.velocity.1 = Self::JUMP_SPEED;
+        }
+    }
+
+    /// Applies gravity and buoyancy, then moves the entity along its velocity.
+    pub(crate) fn tick<R: Rng>(&mut self, sandbox: &Sandbox<R>) {
+        if self.kind == EntityKind::Player {
+            let burns = self.count_pixels(sandbox, |p| p.is_hot());
+            let toxins = self.count_pixels(sandbox, |p| {
+                matches!(
+                    p,
+                    Pixel::Poison(_) | Pixel::PoisonedWater(_) | Pixel::ToxicGas(_)
+                )
+            });
+            let damage = (burns * 2 + toxins).min(u8::MAX as usize) as u8;
+            self.health = self.health.saturating_sub(damage);
+        }
+
+        let (width, height) = self.footprint();
+        let submerged =
+            self.count_pixels(sandbox, |p| matches!(p.pixel_type(), PixelType::Liquid(_)));
+
+        let (mut dx, mut dy) = self.velocity;
+        dy = match (self.kind, submerged) {
+            (_, 0) => (dy + 1).min(Self::MAX_FALL),
+            // the hull rides with half of it under the surface
+            (EntityKind::Boat, submerged) if submerged * 2 > width * height => -1,
+            (EntityKind::Boat, _) => 0,
+            // liquids slow a sinking ball down, and let a player swim up slowly
+            (EntityKind::Ball, _) => (dy + 1).min(1),
+            (EntityKind::Player, _) => (dy + 1).clamp(-1, 1),
+        };
+        if submerged > 0 {
+            dx -= dx.signum();
+        }
+
+        for _ in 0..dy.unsigned_abs() {
+            let y = self.y as isize + dy.signum() as isize;
+            if !self.fits(sandbox, self.x as isize, y) {
+                dy = match self.kind {
+                    EntityKind::Ball if dy >= Self::BOUNCE_SPEED => -dy / 2,
+                    _ => 0,
+                };
+                // rolling on the ground slows it down
+                dx -= dx.signum();
+                break;
+            }
+            self.y = y as usize;
+        }
+        for _ in 0..dx.unsigned_abs() {
+            let x = self.x as isize + dx.signum() as isize;
+            if !self.fits(sandbox, x, self.y as isize) {
+                dx = 0;
+                break;
+            }
+            self.x = x as usize;
+        }
+        self.velocity = (dx, dy);
+    }
+}
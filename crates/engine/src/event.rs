@@ -0,0 +1,102 @@
+use crate::pixel::{Pixel, PixelFundamental, PixelType};
+
+/// Kind of a notable happening inside the sandbox.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum EventKind {
+    /// Something caught fire
+    Ignition,
+    /// Water turned into ice
+    Freeze,
+    /// A solid fell into a liquid
+    Splash,
+    /// A falling pixel landed hard
+    Impact,
+    /// Lightning struck from a cloud
+    Thunder,
+    /// An explosive went off, the magnitude is the radius of the blast
+    Explosion,
+    /// A water wheel turned by a step, the magnitude is the flow that drove it this tick
+    Torque,
+    /// A bug in the engine broke an invariant, only reported by checked ticks
+    InvariantViolation(Invariant),
+}
+
+/// Rule the engine must uphold on every tick.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum Invariant {
+    /// Moving pixels around neither creates nor destroys any, the magnitude is the difference
+    Conservation,
+    /// A pixel was still marked as moved when the movement pass started
+    MovedLeak,
+    /// A wall pixel was moved or replaced while moving pixels around
+    WallMoved,
+}
+
+/// Event emitted by the engine during a tick, for frontends to observe.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct SandboxEvent {
+    pub kind: EventKind,
+    pub x: usize,
+    pub y: usize,
+    /// How strong the happening was, higher is louder/bigger
+    pub magnitude: u32,
+}
+
+impl SandboxEvent {
+    /// Landing speed from which an impact is notable
+    const HARD_LANDING: u8 = 4;
+
+    /// Detects an event from a pixel changing state in place.
+    pub(crate) fn from_transition(before: Pixel, after: Pixel, x: usize, y: usize) -> Option<Self> {
+        let kind = match (before, after) {
+            (Pixel::Wood(before), Pixel::Wood(after))
+                if !before.is_burning() && after.is_burning() =>
+            {
+                EventKind::Ignition
+            }
+            (Pixel::Vine(before), Pixel::Vine(after))
+                if !before.is_burning() && after.is_burning() =>
+            {
+                EventKind::Ignition
+            }
+            (Pixel::Oil(before), Pixel::Oil(after))
+                if !before.is_burning() && after.is_burning() =>
+            {
+                EventKind::Ignition
+            }
+            (Pixel::Void(_), Pixel::Fire(_)) => EventKind::Ignition,
+            (Pixel::Water(_), Pixel::Ice(_)) => EventKind::Freeze,
+            _ => return None,
+        };
+
+        Some(Self {
+            kind,
+            x,
+            y,
+            magnitude: 1,
+        })
+    }
+
+    /// Detects an event from a pixel landing at `speed` on (`x`, `y`).
+    pub(crate) fn from_impact(speed: u8, x: usize, y: usize) -> Option<Self> {
+        (speed >= Self::HARD_LANDING).then_some(Self {
+            kind: EventKind::Impact,
+            x,
+            y,
+            magnitude: speed as u32,
+        })
+    }
+
+    /// Detects an event from `moving` swapping places with `target` at (`x`, `y`).
+    pub(crate) fn from_swap(moving: Pixel, target: Pixel, x: usize, y: usize) -> Option<Self> {
+        match (moving.pixel_type(), target.pixel_type()) {
+            (PixelType::Solid(solid), PixelType::Liquid(liquid)) => Some(Self {
+                kind: EventKind::Splash,
+                x,
+                y,
+                magnitude: solid.abs_diff(liquid) as u32,
+            }),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,210 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use rand::rngs::SmallRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::command::Command;
+use crate::config::SandboxConfig;
+use crate::rng::RngStrategy;
+use crate::sandbox::Sandbox;
+use crate::versioned::Versioned;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecordedCommand {
+    /// Ticks that had passed when the command was applied
+    pub tick: u64,
+    pub command: Command,
+}
+
+/// A seeded run with its inputs and the hash of the grid it ended with.
+///
+/// Replaying a fixture must always end on the same grid, which makes fixtures a cheap
+/// regression check for changes to the physics. The hash needs recording again when a change
+/// alters how the elements of the run move, heat or what state they keep, not when it adds
+/// elements the run doesn't use, see [`Sandbox::state_hash`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Fixture {
+    pub version: u32,
+    pub seed: u64,
+    /// Whether the run was in deterministic mode, see [`Sandbox::new_deterministic`]
+    #[serde(default)]
+    pub deterministic: bool,
+    #[serde(default)]
+    pub config: SandboxConfig,
+    pub width: usize,
+    pub height: usize,
+    pub ticks: u64,
+    /// Hex encoded, as TOML integers can't hold every u64
+    pub hash: String,
+    pub inputs: Vec<RecordedCommand>,
+}
+
+impl Versioned for Fixture {
+    const KIND: &'static str = "fixture";
+    const VERSION: u32 = 1;
+}
+
+impl Fixture {
+    /// Records the run `sandbox` went through so far.
+    ///
+    /// Fails for sandboxes not created from a seed, as those can't be replayed.
+    pub fn record<R: Rng>(sandbox: &Sandbox<R>) -> anyhow::Result<Self> {
+        let seed = sandbox
+            .seed()
+            .ok_or_else(|| anyhow!("the sandbox was not created from a seed"))?;
+
+        let inputs = sandbox.input_log().ok_or_else(|| {
+            anyhow!("the run took too many inputs or changed outside of them, e.g. by undoing")
+        })?;
+
+        let (width, height) = sandbox.initial_size();
+        Ok(Self {
+            version: Self::VERSION,
+            seed,
+            deterministic: sandbox.is_deterministic(),
+            config: sandbox.config(),
+            width,
+            height,
+            ticks: sandbox.tick_count(),
+            hash: format!("{:016x}", sandbox.state_hash()),
+            inputs: inputs
+                .iter()
+                .map(|&(tick, command)| RecordedCommand { tick, command })
+                .collect(),
+        })
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read fixture {}", path.display()))?;
+        Self::from_versioned_toml(&s)
+            .with_context(|| format!("failed to parse fixture {}", path.display()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("failed to write fixture {}", path.display()))
+    }
+
+    /// Runs the recorded inputs on a fresh sandbox.
+    pub fn replay(&self) -> Sandbox<SmallRng> {
+        let strategy = match self.deterministic {
+            true => RngStrategy::Deterministic(self.seed),
+            false => RngStrategy::Seeded(self.seed),
+        };
+        let mut sandbox = Sandbox::<SmallRng>::with_rng_strategy(self.width, self.height, strategy);
+        sandbox.set_config(self.config);
+        let mut inputs = self.inputs.iter().peekable();
+        for tick in 0..=self.ticks {
+            while let Some(input) = inputs.next_if(|input| input.tick == tick) {
+                sandbox.queue_command(input.command);
+            }
+            match tick < self.ticks {
+                true => sandbox.tick(),
+                // inputs applied while paused after the last tick
+                false => sandbox.apply_commands(),
+            }
+        }
+        sandbox
+    }
+
+    /// Replays the fixture and checks it ends on the recorded grid.
+    pub fn verify(&self) -> anyhow::Result<()> {
+        let hash = format!("{:016x}", self.replay().state_hash());
+        match hash == self.hash {
+            true => Ok(()),
+            false => Err(anyhow!(
+                "replay ended on grid {} instead of {}",
+                hash,
+                self.hash
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+
+    use crate::command::Command;
+    use crate::config::{SandboxConfig, UpdateOrder};
+    use crate::fixture::Fixture;
+    use crate::pixel::sand::Sand;
+    use crate::pixel::water::Water;
+    use crate::sandbox::Sandbox;
+
+    fn run(mut sandbox: Sandbox<SmallRng>) -> Sandbox<SmallRng> {
+        for tick in 0..40 {
+            if tick % 10 == 0 {
+                sandbox.queue_command(Command::PlaceBrush {
+                    pixel: match tick % 20 {
+                        0 => Sand.into(),
+                        _ => Water::default().into(),
+                    },
+                    x: 3,
+                    y: 0,
+                    width: 2,
+                    height: 2,
+                    force: false,
+                });
+            }
+            if tick == 20 {
+                sandbox.queue_command(Command::Resize {
+                    width: 10,
+                    height: 6,
+                });
+            }
+            sandbox.tick();
+        }
+        sandbox
+    }
+
+    #[test]
+    fn test_fixture_roundtrip() {
+        let sandbox = run(Sandbox::<SmallRng>::new_with_seed(8, 8, 42));
+        let fixture = Fixture::record(&sandbox).unwrap();
+        let fixture: Fixture = toml::from_str(&toml::to_string(&fixture).unwrap()).unwrap();
+        fixture.verify().unwrap();
+    }
+
+    #[test]
+    fn test_fixture_after_undo() {
+        let mut sandbox = Sandbox::<SmallRng>::new_with_seed(8, 8, 42);
+        sandbox.record_patch();
+        let mut sandbox = run(sandbox);
+        let undo = sandbox.take_patch().unwrap();
+        sandbox.apply_patch(&undo).unwrap();
+        // the undone cells are in no command, a replay wouldn't end on the same grid
+        assert!(Fixture::record(&sandbox).is_err());
+    }
+
+    #[test]
+    fn test_fixture_deterministic() {
+        let sandbox = run(Sandbox::<SmallRng>::new_deterministic(8, 8, 42));
+        let fixture = Fixture::record(&sandbox).unwrap();
+        assert!(fixture.deterministic);
+        let fixture: Fixture = toml::from_str(&toml::to_string(&fixture).unwrap()).unwrap();
+        fixture.verify().unwrap();
+    }
+
+    #[test]
+    fn test_fixture_update_order() {
+        for update_order in [UpdateOrder::Alternating, UpdateOrder::Shuffled] {
+            let mut sandbox = Sandbox::<SmallRng>::new_deterministic(8, 8, 42);
+            sandbox.set_config(SandboxConfig {
+                update_order,
+                ..Default::default()
+            });
+            let sandbox = run(sandbox);
+
+            let fixture = Fixture::record(&sandbox).unwrap();
+            let fixture: Fixture = toml::from_str(&toml::to_string(&fixture).unwrap()).unwrap();
+            assert_eq!(fixture.config.update_order, update_order);
+            fixture.verify().unwrap();
+        }
+    }
+}
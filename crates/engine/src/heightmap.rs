@@ -0,0 +1,118 @@
+use std::collections::BTreeMap;
+
+use rand::Rng;
+use serde::Serialize;
+
+use crate::pixel::{Pixel, PixelFundamental, PixelType};
+use crate::sandbox::Sandbox;
+
+/// Surface and contents of one column of the sandbox.
+#[derive(Debug, Clone, Serialize)]
+pub struct Column {
+    pub x: usize,
+    /// Cells from the bottom up to the topmost pixel that isn't empty or a gas
+    pub height: usize,
+    /// Pixels of every element in the column, gases included
+    pub composition: BTreeMap<&'static str, usize>,
+}
+
+/// Per-column surface heights and material, for measuring e.g. how fast a dam erodes.
+#[derive(Debug, Clone, Serialize)]
+pub struct Heightmap {
+    pub tick: u64,
+    pub columns: Vec<Column>,
+}
+
+impl Heightmap {
+    pub fn new<R: Rng>(sandbox: &Sandbox<R>) -> Self {
+        let columns = (0..sandbox.width)
+            .map(|x| {
+                let mut height = 0;
+                let mut composition = BTreeMap::new();
+                for y in 0..sandbox.height {
+                    let pixel = sandbox.pixels[sandbox.coordinates_to_index(x, y)].pixel();
+                    match pixel.pixel_type() {
+                        PixelType::Void => continue,
+                        PixelType::Gas(_) => {}
+                        _ => height = height.max(sandbox.height - y),
+                    }
+                    *composition.entry(pixel.name()).or_insert(0) += 1;
+                }
+                Column {
+                    x,
+                    height,
+                    composition,
+                }
+            })
+            .collect();
+
+        Self {
+            tick: sandbox.tick_count(),
+            columns,
+        }
+    }
+
+    /// One row per column, with a count for every element.
+    pub fn to_csv(&self) -> String {
+        let names = Pixel::all()
+            .filter(|p| !matches!(p, Pixel::Void(_)))
+            .map(|p| p.name())
+            .collect::<Vec<_>>();
+
+        let mut csv = format!("tick,x,height,{}\n", names.join(","));
+        for column in &self.columns {
+            let counts = names
+                .iter()
+                .map(|name| {
+                    column
+                        .composition
+                        .get(name)
+                        .copied()
+                        .unwrap_or(0)
+                        .to_string()
+                })
+                .collect::<Vec<_>>();
+            csv += &format!(
+                "{},{},{},{}\n",
+                self.tick,
+                column.x,
+                column.height,
+                counts.join(",")
+            );
+        }
+        csv
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+
+    use crate::heightmap::Heightmap;
+    use crate::pixel::rock::Rock;
+    use crate::pixel::sand::Sand;
+    use crate::pixel::steam::Steam;
+    use crate::sandbox::Sandbox;
+
+    #[test]
+    fn test_heightmap() {
+        let mut sandbox = Sandbox::<SmallRng>::new_with_seed(3, 4, 1);
+        sandbox.place_pixel_force(Rock.into(), 0, 3);
+        sandbox.place_pixel_force(Sand.into(), 0, 2);
+        sandbox.place_pixel_force(Steam::default().into(), 1, 0);
+
+        let heightmap = Heightmap::new(&sandbox);
+        let heights = heightmap
+            .columns
+            .iter()
+            .map(|c| c.height)
+            .collect::<Vec<_>>();
+        // gases don't count towards the surface
+        assert_eq!(heights, vec![2, 0, 0]);
+        assert_eq!(heightmap.columns[1].composition.get("Steam"), Some(&1));
+
+        let csv = heightmap.to_csv();
+        assert_eq!(csv.lines().count(), 4);
+        assert!(csv.lines().nth(1).unwrap().starts_with("0,0,2,"));
+    }
+}
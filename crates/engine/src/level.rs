@@ -0,0 +1,378 @@
+use std::collections::HashMap;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use anyhow::{anyhow, Context};
+use rand::rngs::SmallRng;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::command::Command;
+use crate::pixel::by_name;
+use crate::pixel::{Pixel, PixelFundamental};
+use crate::sandbox::Sandbox;
+use crate::scene::{self, Placement, Region};
+
+/// An element the player is allowed to place, optionally limited to a number of pixels.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Allowance {
+    #[serde(with = "by_name")]
+    pub pixel: Pixel,
+    pub budget: Option<u32>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Goal {
+    /// Get `count` pixels of `pixel` into `region`
+    Fill {
+        #[serde(with = "by_name")]
+        pixel: Pixel,
+        region: Region,
+        count: usize,
+        within_ticks: Option<u64>,
+    },
+    /// Put out every fire and burning pixel
+    Extinguish { within_ticks: Option<u64> },
+}
+
+impl Goal {
+    fn within_ticks(&self) -> Option<u64> {
+        match self {
+            Goal::Fill { within_ticks, .. } | Goal::Extinguish { within_ticks } => *within_ticks,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct Level {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub width: usize,
+    pub height: usize,
+    #[serde(default)]
+    pub scene: Vec<Placement>,
+    /// Elements the player may place, every element is allowed when empty
+    #[serde(default)]
+    pub elements: Vec<Allowance>,
+    pub goal: Goal,
+}
+
+impl Level {
+    pub fn from_toml(s: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(s)?)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read level {}", path.display()))?;
+        Self::from_toml(&s).with_context(|| format!("failed to parse level {}", path.display()))
+    }
+
+    /// Levels shipped with the engine.
+    pub fn builtin() -> Vec<Level> {
+        [
+            include_str!("../levels/basin.toml"),
+            include_str!("../levels/wildfire.toml"),
+        ]
+        .into_iter()
+        .map(|s| Self::from_toml(s).expect("builtin level must be valid"))
+        .collect()
+    }
+
+    fn allowance(&self, pixel: Pixel) -> Option<&Allowance> {
+        self.elements
+            .iter()
+            .find(|a| a.pixel.name() == pixel.name())
+    }
+
+    pub fn is_allowed(&self, pixel: Pixel) -> bool {
+        self.elements.is_empty() || self.allowance(pixel).is_some()
+    }
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum LevelStatus {
+    InProgress,
+    Won,
+    Lost,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct Progress {
+    pub status: LevelStatus,
+    pub current: usize,
+    pub target: usize,
+    /// Ticks left before the level is lost, if it has a time limit
+    pub ticks_left: Option<u64>,
+}
+
+/// A level being played, evaluating its goal after every tick.
+#[derive(Debug)]
+pub struct LevelSession {
+    level: Level,
+    ticks: u64,
+    started: Instant,
+    /// Ticks and real time it took to decide the outcome
+    finished: Option<(u64, Duration)>,
+    spent: HashMap<&'static str, u32>,
+    peak_count: usize,
+    progress: Progress,
+}
+
+impl LevelSession {
+    pub fn new(level: Level) -> Self {
+        Self {
+            level,
+            ticks: 0,
+            started: Instant::now(),
+            finished: None,
+            spent: HashMap::new(),
+            peak_count: 0,
+            progress: Progress {
+                status: LevelStatus::InProgress,
+                current: 0,
+                target: 0,
+                ticks_left: None,
+            },
+        }
+    }
+
+    pub fn level(&self) -> &Level {
+        &self.level
+    }
+
+    pub fn progress(&self) -> Progress {
+        self.progress
+    }
+
+    /// Ticks and real time spent on the level so far, frozen once it is won or lost.
+    pub fn timer(&self) -> (u64, Duration) {
+        self.finished
+            .unwrap_or_else(|| (self.ticks, self.started.elapsed()))
+    }
+
+    /// Creates a sandbox holding the level's starting scene.
+    pub fn create_sandbox(&mut self) -> Sandbox<SmallRng> {
+        let mut sandbox =
+            scene::build_sandbox(self.level.width, self.level.height, &self.level.scene);
+        if !self.level.elements.is_empty() {
+            sandbox.set_allowed_elements(Some(
+                self.level
+                    .elements
+                    .iter()
+                    .map(|allowance| (&allowance.pixel).into())
+                    .collect(),
+            ));
+        }
+        self.evaluate(&sandbox);
+        sandbox
+    }
+
+    /// Remaining pixels of `pixel` the player may place, `None` if unlimited.
+    pub fn remaining_budget(&self, pixel: Pixel) -> Option<u32> {
+        let budget = self.level.allowance(pixel)?.budget?;
+        Some(budget.saturating_sub(self.spent.get(pixel.name()).copied().unwrap_or(0)))
+    }
+
+    /// Queues `command` on `sandbox` if the level's rules allow it.
+    ///
+    /// Brush placements are charged for every empty cell they cover, erasing is always free.
+    pub fn queue_command<R: Rng>(
+        &mut self,
+        sandbox: &mut Sandbox<R>,
+        command: Command,
+    ) -> anyhow::Result<()> {
+        if let Some((pixel, _)) = command.placement() {
+            if !matches!(pixel, Pixel::Void(_)) {
+                if !self.level.is_allowed(pixel) {
+                    return Err(anyhow!("{} is not allowed in this level", pixel.name()));
+                }
+
+                let cost = sandbox.placement_count(&command) as u32;
+                if let Some(remaining) = self.remaining_budget(pixel) {
+                    if cost > remaining {
+                        return Err(anyhow!("not enough {} left", pixel.name()));
+                    }
+                }
+                *self.spent.entry(pixel.name()).or_insert(0) += cost;
+            }
+        }
+
+        sandbox.queue_command(command);
+        Ok(())
+    }
+
+    /// Ticks `sandbox` and evaluates the goal against the result.
+    pub fn tick<R: Rng>(&mut self, sandbox: &mut Sandbox<R>) -> Progress {
+        sandbox.tick();
+        self.ticks += 1;
+        self.evaluate(sandbox)
+    }
+
+    fn evaluate<R: Rng>(&mut self, sandbox: &Sandbox<R>) -> Progress {
+        // the outcome sticks once decided
+        if self.progress.status != LevelStatus::InProgress {
+            return self.progress;
+        }
+
+        let (current, target) = match self.level.goal {
+            Goal::Fill {
+                pixel,
+                region,
+                count: target,
+                ..
+            } => {
+                let count = sandbox
+                    .pixels
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, p)| p.pixel().name() == pixel.name())
+                    .filter(|(idx, _)| {
+                        let (x, y) = sandbox.index_to_coordinates(*idx);
+                        region.contains(x, y)
+                    })
+                    .count();
+                (count.min(target), target)
+            }
+            Goal::Extinguish { .. } => {
+                let burning = sandbox
+                    .pixels
+                    .iter()
+                    .filter(|p| match p.pixel() {
+                        Pixel::Fire(_) => true,
+                        Pixel::Wood(wood) => wood.is_burning(),
+                        Pixel::Oil(oil) => oil.is_burning(),
+                        _ => false,
+                    })
+                    .count();
+                // fire spreads, so measure against the worst it has been
+                self.peak_count = self.peak_count.max(burning);
+                (self.peak_count - burning, self.peak_count)
+            }
+        };
+
+        let ticks_left = self
+            .level
+            .goal
+            .within_ticks()
+            .map(|limit| limit.saturating_sub(self.ticks));
+
+        let status = match (current >= target, ticks_left) {
+            (true, _) => LevelStatus::Won,
+            (false, Some(0)) => LevelStatus::Lost,
+            _ => LevelStatus::InProgress,
+        };
+
+        if status != LevelStatus::InProgress {
+            self.finished = Some((self.ticks, self.started.elapsed()));
+        }
+        self.progress = Progress {
+            status,
+            current,
+            target,
+            ticks_left,
+        };
+        self.progress
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::command::Command;
+    use crate::level::{Level, LevelSession, LevelStatus};
+    use crate::pixel::sand::Sand;
+    use crate::pixel::water::Water;
+
+    const LEVEL: &str = r#"
+        name = "test"
+        width = 3
+        height = 3
+
+        [[scene]]
+        pixel = "Rock"
+        x = 0
+        y = 2
+        width = 3
+        height = 1
+
+        [[elements]]
+        pixel = "Sand"
+        budget = 2
+
+        [goal]
+        kind = "fill"
+        pixel = "Sand"
+        region = { x = 0, y = 1, width = 3, height = 1 }
+        count = 2
+        within_ticks = 10
+    "#;
+
+    #[test]
+    fn test_builtin_levels() {
+        assert!(!Level::builtin().is_empty());
+    }
+
+    #[test]
+    fn test_builtin_levels_are_not_won_on_start() {
+        for level in Level::builtin() {
+            let mut session = LevelSession::new(level);
+            session.create_sandbox();
+            assert_eq!(session.progress().status, LevelStatus::InProgress);
+        }
+    }
+
+    #[test]
+    fn test_level_budget() {
+        let mut session = LevelSession::new(Level::from_toml(LEVEL).unwrap());
+        let mut sandbox = session.create_sandbox();
+
+        let brush = |pixel, width| Command::PlaceBrush {
+            pixel,
+            x: 0,
+            y: 0,
+            width,
+            height: 1,
+            force: false,
+        };
+        assert!(session
+            .queue_command(&mut sandbox, brush(Water::default().into(), 1))
+            .is_err());
+        assert!(session
+            .queue_command(&mut sandbox, brush(Sand.into(), 3))
+            .is_err());
+        assert!(session
+            .queue_command(&mut sandbox, brush(Sand.into(), 2))
+            .is_ok());
+        assert_eq!(session.remaining_budget(Sand.into()), Some(0));
+    }
+
+    #[test]
+    fn test_level_goal() {
+        let mut session = LevelSession::new(Level::from_toml(LEVEL).unwrap());
+        let mut sandbox = session.create_sandbox();
+        assert_eq!(session.progress().status, LevelStatus::InProgress);
+
+        sandbox.place_pixel(Sand.into(), 0, 0);
+        sandbox.place_pixel(Sand.into(), 2, 0);
+        let progress = session.tick(&mut sandbox);
+        assert_eq!(progress.status, LevelStatus::Won, "{:?}", progress);
+        assert_eq!(progress.current, 2);
+
+        // the timer stops on completion
+        session.tick(&mut sandbox);
+        assert_eq!(session.timer().0, 1);
+    }
+
+    #[test]
+    fn test_level_timeout() {
+        let mut session = LevelSession::new(Level::from_toml(LEVEL).unwrap());
+        let mut sandbox = session.create_sandbox();
+        for _ in 0..10 {
+            session.tick(&mut sandbox);
+        }
+        assert_eq!(session.progress().status, LevelStatus::Lost);
+    }
+}
@@ -1,3 +1,29 @@
+pub mod annotation;
+pub mod catalog;
+pub mod clock;
+pub mod command;
+pub mod compare;
+pub mod composite;
+pub mod config;
+pub mod effect;
+pub mod entity;
+pub mod event;
+pub mod fixture;
 pub mod fps_tracker;
+pub mod hash;
+pub mod heightmap;
+pub mod level;
+pub mod palette;
+pub mod patch;
 pub mod pixel;
+pub mod reaction;
+pub mod registry;
+pub mod rng;
 pub mod sandbox;
+pub mod scenario;
+pub mod scene;
+pub mod snapshot;
+pub mod stats;
+pub mod sync;
+pub mod temperature;
+pub mod versioned;
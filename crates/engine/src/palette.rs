@@ -0,0 +1,118 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::pixel::{by_name, Pixel, PixelFundamental};
+
+/// A colour as red, green and blue.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Rgb(pub u8, pub u8, pub u8);
+
+impl Rgb {
+    /// Parses `#RRGGBB`.
+    pub fn from_hex(s: &str) -> anyhow::Result<Self> {
+        let hex = s
+            .strip_prefix('#')
+            .filter(|hex| hex.len() == 6)
+            .ok_or_else(|| anyhow!("expected a colour like #RRGGBB, got {}", s))?;
+        let channel = |i: usize| {
+            u8::from_str_radix(&hex[i..i + 2], 16).with_context(|| format!("invalid colour {}", s))
+        };
+        Ok(Self(channel(0)?, channel(2)?, channel(4)?))
+    }
+
+    fn distance(&self, other: &Rgb) -> u32 {
+        let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2) as u32;
+        d(self.0, other.0) + d(self.1, other.1) + d(self.2, other.2)
+    }
+}
+
+impl Serialize for Rgb {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("#{:02x}{:02x}{:02x}", self.0, self.1, self.2))
+    }
+}
+
+impl<'de> Deserialize<'de> for Rgb {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Rgb::from_hex(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct PaletteEntry {
+    pub color: Rgb,
+    #[serde(with = "by_name")]
+    pub pixel: Pixel,
+}
+
+/// Maps colours to elements and back, for importing images and theming.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Palette {
+    pub name: String,
+    pub colors: Vec<PaletteEntry>,
+}
+
+impl Palette {
+    pub fn from_toml(s: &str) -> anyhow::Result<Self> {
+        let palette: Self = toml::from_str(s)?;
+        if palette.colors.is_empty() {
+            return Err(anyhow!("palette {} has no colours", palette.name));
+        }
+        Ok(palette)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read palette {}", path.display()))?;
+        Self::from_toml(&s).with_context(|| format!("failed to parse palette {}", path.display()))
+    }
+
+    /// The shipped default, close to the colours of The Powder Toy.
+    pub fn powder_toy() -> Self {
+        Self::from_toml(include_str!("../palettes/powder_toy.toml"))
+            .expect("builtin palette must be valid")
+    }
+
+    /// Element whose colour is the closest to `color`.
+    pub fn pixel(&self, color: Rgb) -> Pixel {
+        self.colors
+            .iter()
+            .min_by_key(|entry| entry.color.distance(&color))
+            .map(|entry| entry.pixel)
+            .unwrap_or_default()
+    }
+
+    /// Colour of the element of `pixel`, if the palette has one.
+    pub fn color(&self, pixel: Pixel) -> Option<Rgb> {
+        self.colors
+            .iter()
+            .find(|entry| entry.pixel.name() == pixel.name())
+            .map(|entry| entry.color)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::palette::{Palette, Rgb};
+    use crate::pixel::sand::Sand;
+    use crate::pixel::water::Water;
+    use crate::pixel::{Pixel, PixelFundamental};
+
+    #[test]
+    fn test_powder_toy_palette() {
+        let palette = Palette::powder_toy();
+        assert_eq!(palette.pixel(Rgb(0xFF, 0xD0, 0x90)), Sand.into());
+        // slightly off colours still land on the closest element
+        assert_eq!(palette.pixel(Rgb(0x25, 0x35, 0xC8)).name(), "Water");
+        assert_eq!(palette.pixel(Rgb(3, 2, 1)), Pixel::default());
+        assert_eq!(
+            palette.color(Water::default().into()),
+            Some(Rgb(0x20, 0x30, 0xD0))
+        );
+        assert!(Rgb::from_hex("#12345").is_err());
+    }
+}
@@ -0,0 +1,48 @@
+use std::collections::BTreeMap;
+
+use crate::pixel::PixelKind;
+use crate::sandbox::PixelContainer;
+
+/// Earlier contents of the cells of a sandbox some placements changed, which put them back when
+/// applied, see [`Sandbox::record_patch`].
+///
+/// Only the cells a placement overwrote are kept, so undoing a stroke costs as much as the
+/// stroke rather than a copy of the grid. Pixels that moved out of those cells since stay where
+/// they went, and the cells other elements moved into are left alone.
+///
+/// [`Sandbox::record_patch`]: crate::sandbox::Sandbox::record_patch
+#[derive(Debug, Clone, Default)]
+pub struct Patch {
+    /// Size of the sandbox the cells are in, the patch doesn't apply to any other
+    pub(crate) size: (usize, usize),
+    /// Content of every changed cell before its first change, by index
+    pub(crate) cells: BTreeMap<usize, PixelContainer>,
+    /// Element the placements left in every changed cell
+    pub(crate) placed: BTreeMap<usize, PixelKind>,
+}
+
+impl Patch {
+    pub(crate) fn new(width: usize, height: usize) -> Self {
+        Self {
+            size: (width, height),
+            cells: BTreeMap::new(),
+            placed: BTreeMap::new(),
+        }
+    }
+
+    /// Keeps `container` as what the cell at `idx` held, unless an earlier change already did,
+    /// and `placed` as the element it holds now.
+    pub(crate) fn record(&mut self, idx: usize, container: PixelContainer, placed: PixelKind) {
+        self.cells.entry(idx).or_insert(container);
+        self.placed.insert(idx, placed);
+    }
+
+    /// Number of cells the patch puts back.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+}
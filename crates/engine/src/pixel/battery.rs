@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{PixelFundamental, PixelInteract, PixelType};
+
+/// Keeps sparking into the wires next to it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Battery;
+
+impl PixelFundamental for Battery {
+    fn name(&self) -> &'static str {
+        "Battery"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+}
+
+impl PixelInteract for Battery {}
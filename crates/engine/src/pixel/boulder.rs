@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+use crate::composite::Composite;
+use crate::pixel::gravel::Gravel;
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Cell of a 2x2 rock that falls whole and sinks in liquids, crumbling into gravel when it
+/// loses a cell.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Boulder {
+    part: u8,
+}
+
+impl Composite for Boulder {
+    const FOOTPRINT: (usize, usize) = (2, 2);
+
+    fn part(&self) -> u8 {
+        self.part
+    }
+
+    fn with_part(&self, part: u8) -> Self {
+        Self { part }
+    }
+
+    fn debris(&self) -> Pixel {
+        Gravel.into()
+    }
+}
+
+impl PixelFundamental for Boulder {
+    fn name(&self) -> &'static str {
+        "Boulder"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Solid(80)
+    }
+}
+
+impl PixelInteract for Boulder {}
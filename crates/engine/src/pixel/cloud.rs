@@ -0,0 +1,41 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Gas lighter than any other, it builds up charge rubbing against clouds and steam until
+/// it strikes lightning down.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Cloud {
+    charge: u8,
+}
+
+impl Cloud {
+    const CHARGED: u8 = 200;
+
+    /// Whether the cloud may strike lightning, the sandbox decides when.
+    pub fn is_charged(&self) -> bool {
+        self.charge >= Self::CHARGED
+    }
+
+    pub fn discharge(&mut self) {
+        self.charge = 0;
+    }
+}
+
+impl PixelFundamental for Cloud {
+    fn name(&self) -> &'static str {
+        "Cloud"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Gas(-20)
+    }
+}
+
+impl PixelInteract for Cloud {
+    fn interact(&mut self, target: Pixel) {
+        if let Pixel::Cloud(_) | Pixel::Steam(_) = target {
+            self.charge = self.charge.saturating_add(1);
+        }
+    }
+}
@@ -0,0 +1,87 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+use crate::registry::{self, Descriptor};
+
+/// Pixel of an element added through the [`crate::registry::PixelRegistry`], behaving as its
+/// descriptor says.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+#[serde(try_from = "Registered")]
+pub struct Custom {
+    /// Position of the element in the registry
+    id: u16,
+    /// Reaction of the descriptor set off this tick
+    reaction: Option<u8>,
+}
+
+/// A [`Custom`] as stored, checked against the registry when loaded.
+#[derive(Deserialize)]
+struct Registered {
+    id: u16,
+    reaction: Option<u8>,
+}
+
+impl TryFrom<Registered> for Custom {
+    type Error = String;
+
+    fn try_from(Registered { id, reaction }: Registered) -> Result<Self, Self::Error> {
+        match registry::descriptor(id) {
+            Some(_) => Ok(Self { id, reaction }),
+            None => Err(format!("custom element #{} isn't registered", id)),
+        }
+    }
+}
+
+impl Custom {
+    pub(crate) fn new(id: u16) -> Self {
+        Self { id, reaction: None }
+    }
+
+    /// Position of the element in the registry.
+    pub(crate) fn id(&self) -> u16 {
+        self.id
+    }
+
+    pub fn descriptor(&self) -> Option<&'static Descriptor> {
+        registry::descriptor(self.id)
+    }
+}
+
+impl PixelFundamental for Custom {
+    fn name(&self) -> &'static str {
+        self.descriptor().map_or("Custom", |d| d.name.as_str())
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        // only left unregistered as the placeholder variant iterating the pixels yields
+        self.descriptor()
+            .map_or(PixelType::Wall, Descriptor::pixel_type)
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        let reaction = self
+            .descriptor()?
+            .reactions
+            .get(self.reaction.take()? as usize)?;
+        Pixel::from_name(&reaction.becomes)
+    }
+
+    fn viscosity(&self) -> u8 {
+        self.descriptor().map_or(0, |d| d.viscosity)
+    }
+}
+
+impl PixelInteract for Custom {
+    fn interact(&mut self, target: Pixel) {
+        let Some(descriptor) = self.descriptor() else {
+            return;
+        };
+        if self.reaction.is_none() {
+            self.reaction = descriptor
+                .reactions
+                .iter()
+                .position(|r| r.touching.eq_ignore_ascii_case(target.name()))
+                .map(|idx| idx as u8);
+        }
+    }
+}
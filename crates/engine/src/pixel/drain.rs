@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{PixelFundamental, PixelInteract, PixelType};
+
+/// Swallows the movable pixels touching it, the sandbox empties their cells.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Drain;
+
+impl PixelFundamental for Drain {
+    fn name(&self) -> &'static str {
+        "Drain"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+}
+
+impl PixelInteract for Drain {}
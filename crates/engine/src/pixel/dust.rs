@@ -0,0 +1,19 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{PixelFundamental, PixelInteract, PixelType};
+
+/// What is left of fungus once it runs out of wood.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Dust;
+
+impl PixelFundamental for Dust {
+    fn name(&self) -> &'static str {
+        "Dust"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Solid(20)
+    }
+}
+
+impl PixelInteract for Dust {}
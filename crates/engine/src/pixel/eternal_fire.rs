@@ -1,6 +1,9 @@
-use crate::pixel::{PixelFundamental, PixelInteract, PixelType};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+use crate::temperature;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
 pub struct EternalFire;
 
 impl PixelFundamental for EternalFire {
@@ -11,6 +14,15 @@ impl PixelFundamental for EternalFire {
     fn pixel_type(&self) -> PixelType {
         PixelType::Wall
     }
+
+    fn initial_temperature(&self) -> u8 {
+        temperature::FLAME
+    }
+
+    fn update(&mut self, temp: &mut u8) -> Option<Pixel> {
+        *temp = temperature::FLAME;
+        None
+    }
 }
 
 impl PixelInteract for EternalFire {}
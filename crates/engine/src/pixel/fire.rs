@@ -1,7 +1,10 @@
+use serde::{Deserialize, Serialize};
+
 use crate::pixel::void::Void;
 use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+use crate::temperature;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Fire {
     life: u8,
 }
@@ -20,7 +23,12 @@ impl PixelFundamental for Fire {
         PixelType::Gas(-1)
     }
 
-    fn update(&mut self) -> Option<Pixel> {
+    fn initial_temperature(&self) -> u8 {
+        temperature::FLAME
+    }
+
+    fn update(&mut self, temp: &mut u8) -> Option<Pixel> {
+        *temp = temperature::FLAME;
         self.life -= 1;
 
         if self.life == 0 {
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::void::Void;
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Froth whipped up by water crashing into things, it floats for a while then pops.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Foam {
+    life: u8,
+}
+
+impl Default for Foam {
+    fn default() -> Self {
+        Self { life: 40 }
+    }
+}
+
+impl PixelFundamental for Foam {
+    fn name(&self) -> &'static str {
+        "Foam"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Liquid(5)
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        self.life -= 1;
+
+        if self.life == 0 {
+            Some(Void::default().into())
+        } else {
+            None
+        }
+    }
+}
+
+impl PixelInteract for Foam {}
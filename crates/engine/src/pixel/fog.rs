@@ -0,0 +1,45 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::water::Water;
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Steam that cooled down in the open air, it hangs around and drifts slowly until enough of it
+/// gathers to rain out as droplets.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Fog {
+    density: u8,
+}
+
+impl Fog {
+    const RAINS: u8 = 120;
+}
+
+impl PixelFundamental for Fog {
+    fn name(&self) -> &'static str {
+        "Fog"
+    }
+
+    /// Heavier than steam, so the steam still rising goes through it.
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Gas(-2)
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        (self.density >= Self::RAINS).then(|| Water::default().into())
+    }
+
+    fn viscosity(&self) -> u8 {
+        75
+    }
+}
+
+impl PixelInteract for Fog {
+    fn interact(&mut self, target: Pixel) {
+        match target {
+            Pixel::Fog(_) | Pixel::Cloud(_) => self.density = self.density.saturating_add(1),
+            // cold surfaces gather the droplets faster
+            Pixel::Ice(_) => self.density = self.density.saturating_add(4),
+            _ => {}
+        }
+    }
+}
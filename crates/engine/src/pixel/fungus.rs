@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::dust::Dust;
+use crate::pixel::fire::Fire;
+use crate::pixel::{Neighbours, Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Slowly eats through wood, turning it into more fungus.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Fungus {
+    /// Ticks since the fungus last touched wood
+    hunger: u8,
+    burnt: bool,
+    poisoned: bool,
+}
+
+impl Fungus {
+    const STARVATION: u8 = 120;
+}
+
+impl PixelFundamental for Fungus {
+    fn name(&self) -> &'static str {
+        "Fungus"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        if self.burnt {
+            Some(Fire::default().into())
+        } else if self.poisoned || self.hunger >= Self::STARVATION {
+            Some(Dust.into())
+        } else {
+            None
+        }
+    }
+}
+
+impl PixelInteract for Fungus {
+    fn interact(&mut self, target: Pixel) {
+        match target {
+            Pixel::Fire(_) | Pixel::EternalFire(_) | Pixel::Lava(_) => self.burnt = true,
+            Pixel::Wood(wood) if wood.is_burning() => self.burnt = true,
+            // salt kills it as surely as poison
+            Pixel::Salt(_) => self.poisoned = true,
+            target if target.is_toxic() => self.poisoned = true,
+            _ => {}
+        }
+    }
+
+    fn react(&mut self, neighbours: &Neighbours) {
+        self.hunger = match neighbours.iter().any(|p| matches!(p, Pixel::Wood(_))) {
+            true => 0,
+            false => self.hunger.saturating_add(1),
+        };
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::soil::Soil;
+use crate::pixel::{Neighbours, Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Top layer of wet soil under open sky, it dies back to soil when covered, burnt or poisoned.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Grass {
+    withered: bool,
+}
+
+impl PixelFundamental for Grass {
+    fn name(&self) -> &'static str {
+        "Grass"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Solid(50)
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        match self.withered {
+            true => Some(Soil::default().into()),
+            false => None,
+        }
+    }
+}
+
+impl PixelInteract for Grass {
+    fn interact(&mut self, target: Pixel) {
+        if target.is_hot() || target.is_toxic() {
+            self.withered = true;
+        }
+    }
+
+    fn react(&mut self, neighbours: &Neighbours) {
+        if neighbours
+            .up
+            .is_some_and(|up| !matches!(up, Pixel::Void(_)))
+        {
+            self.withered = true;
+        }
+    }
+}
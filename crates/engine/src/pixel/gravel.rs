@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{PixelFundamental, PixelInteract, PixelType};
+
+/// Chunky powder heavier than sand, it only slides off steep slopes.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Gravel;
+
+impl PixelFundamental for Gravel {
+    fn name(&self) -> &'static str {
+        "Gravel"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Solid(70)
+    }
+
+    fn topple_height(&self) -> usize {
+        2
+    }
+}
+
+impl PixelInteract for Gravel {}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+use crate::temperature;
+
+/// Black powder going off in a small blast once a flame licks it or its cell gets hot enough to
+/// ignite, see [`Pixel::blast_radius`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Gunpowder {
+    lit: bool,
+}
+
+impl Gunpowder {
+    pub const BLAST_RADIUS: usize = 3;
+
+    pub fn is_lit(&self) -> bool {
+        self.lit
+    }
+
+    pub fn light(&mut self) {
+        self.lit = true;
+    }
+}
+
+impl PixelFundamental for Gunpowder {
+    fn name(&self) -> &'static str {
+        "Gunpowder"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Solid(45)
+    }
+
+    fn update(&mut self, temp: &mut u8) -> Option<Pixel> {
+        self.lit |= *temp >= temperature::IGNITION;
+        None
+    }
+}
+
+impl PixelInteract for Gunpowder {
+    fn interact(&mut self, target: Pixel) {
+        self.lit |= target.is_hot();
+    }
+}
@@ -1,15 +1,19 @@
+use serde::{Deserialize, Serialize};
+
 use crate::pixel::water::Water;
 use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+use crate::temperature;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+/// Frozen water, melting once its cell took in enough heat above freezing.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
 pub struct Ice {
-    pub temp: u8,
+    /// Heat taken in towards melting, the cell itself staying at freezing meanwhile
+    thaw: u8,
 }
 
 impl Ice {
-    pub fn is_burning(&self) -> bool {
-        self.temp >= 99
-    }
+    /// Heat it takes to melt
+    const LATENT_HEAT: u8 = 80;
 }
 
 impl PixelFundamental for Ice {
@@ -21,34 +25,17 @@ impl PixelFundamental for Ice {
         PixelType::Wall
     }
 
-    fn update(&mut self) -> Option<Pixel> {
-        if self.is_burning() {
-            Some(Water::default().into())
-        } else {
-            None
-        }
+    fn initial_temperature(&self) -> u8 {
+        temperature::ICE
     }
-}
 
-impl PixelInteract for Ice {
-    fn interact(&mut self, target: Pixel) {
-        match target {
-            Pixel::Fire(_) | Pixel::EternalFire(_) => {
-                if !self.is_burning() {
-                    self.temp += 20;
-                }
-            }
-            Pixel::Water(_) => {
-                if !self.is_burning() {
-                    self.temp += 10;
-                }
-            }
-            Pixel::Steam(_) => {
-                if !self.is_burning() {
-                    self.temp += 15;
-                }
-            }
-            _ => {}
+    fn update(&mut self, temp: &mut u8) -> Option<Pixel> {
+        if *temp > temperature::FREEZING {
+            self.thaw = self.thaw.saturating_add(*temp - temperature::FREEZING);
+            *temp = temperature::FREEZING;
         }
+        (self.thaw >= Self::LATENT_HEAT).then(|| Water::default().into())
     }
 }
+
+impl PixelInteract for Ice {}
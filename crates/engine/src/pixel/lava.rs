@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::obsidian::Obsidian;
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+use crate::temperature;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Lava {
+    /// Builds up while surrounded by lava, the pool sparks when it overflows
+    pool: u8,
+    sparking: bool,
+    quenched: bool,
+}
+
+impl Lava {
+    /// Whether the lava throws a spark into the empty cells around it this tick.
+    pub fn is_sparking(&self) -> bool {
+        self.sparking
+    }
+}
+
+impl PixelFundamental for Lava {
+    fn name(&self) -> &'static str {
+        "Lava"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Liquid(40)
+    }
+
+    fn viscosity(&self) -> u8 {
+        75
+    }
+
+    fn initial_temperature(&self) -> u8 {
+        temperature::MOLTEN
+    }
+
+    fn update(&mut self, temp: &mut u8) -> Option<Pixel> {
+        *temp = temperature::MOLTEN;
+        if self.quenched {
+            return Some(Obsidian.into());
+        }
+
+        self.sparking = self.pool == u8::MAX;
+        if self.sparking {
+            self.pool = 0;
+        }
+        None
+    }
+}
+
+impl PixelInteract for Lava {
+    fn interact(&mut self, target: Pixel) {
+        match target {
+            Pixel::Water(_) | Pixel::Ice(_) => self.quenched = true,
+            Pixel::Lava(_) => self.pool = self.pool.saturating_add(1),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,48 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Gravity, Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Overrides gravity for the movable pixels in a half disc above it, clicking it switches
+/// between inverting and nullifying gravity.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct LevitationField {
+    inverted: bool,
+}
+
+impl Default for LevitationField {
+    fn default() -> Self {
+        Self { inverted: true }
+    }
+}
+
+impl LevitationField {
+    /// Reach of the field in cells.
+    pub const RADIUS: usize = 8;
+
+    pub fn gravity(&self) -> Gravity {
+        match self.inverted {
+            true => Gravity::Inverted,
+            false => Gravity::Nullified,
+        }
+    }
+}
+
+impl PixelFundamental for LevitationField {
+    fn name(&self) -> &'static str {
+        "Levitation field"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        None
+    }
+}
+
+impl PixelInteract for LevitationField {
+    fn activate(&mut self) {
+        self.inverted = !self.inverted;
+    }
+}
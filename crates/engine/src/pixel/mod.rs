@@ -1,30 +1,110 @@
+pub mod battery;
+pub mod boulder;
+pub mod cloud;
+pub mod custom;
+pub mod drain;
+pub mod dust;
 pub mod eternal_fire;
 pub mod fire;
+pub mod foam;
+pub mod fog;
+pub mod fungus;
+pub mod grass;
+pub mod gravel;
+pub mod gunpowder;
 pub mod ice;
+pub mod lava;
+pub mod levitation_field;
+pub mod mud;
+pub mod obsidian;
+pub mod oil;
+pub mod pipe;
+pub mod plank;
+pub mod poison;
+pub mod poisoned_water;
+pub mod pump;
 pub mod rock;
+pub mod rubber;
+pub mod salt;
 pub mod sand;
+pub mod sieve;
+pub mod soil;
+pub mod solution;
+pub mod spawner;
+pub mod spout;
 pub mod steam;
+pub mod switch;
+pub mod timer;
+pub mod tnt;
+pub mod toxic_gas;
+pub mod turbine;
+pub mod vine;
 pub mod void;
 pub mod water;
+pub mod water_wheel;
+pub mod wet_sand;
+pub mod wire;
 pub mod wood;
 
+use crate::pixel::battery::Battery;
+use crate::pixel::boulder::Boulder;
+use crate::pixel::cloud::Cloud;
+use crate::pixel::custom::Custom;
+use crate::pixel::drain::Drain;
+use crate::pixel::dust::Dust;
 use crate::pixel::eternal_fire::EternalFire;
 use crate::pixel::fire::Fire;
+use crate::pixel::foam::Foam;
+use crate::pixel::fog::Fog;
+use crate::pixel::fungus::Fungus;
+use crate::pixel::grass::Grass;
+use crate::pixel::gravel::Gravel;
+use crate::pixel::gunpowder::Gunpowder;
 use crate::pixel::ice::Ice;
+use crate::pixel::lava::Lava;
+use crate::pixel::levitation_field::LevitationField;
+use crate::pixel::mud::Mud;
+use crate::pixel::obsidian::Obsidian;
+use crate::pixel::oil::Oil;
+use crate::pixel::pipe::Pipe;
+use crate::pixel::plank::Plank;
+use crate::pixel::poison::Poison;
+use crate::pixel::poisoned_water::PoisonedWater;
+use crate::pixel::pump::Pump;
 use crate::pixel::rock::Rock;
+use crate::pixel::rubber::Rubber;
+use crate::pixel::salt::Salt;
 use crate::pixel::sand::Sand;
+use crate::pixel::sieve::Sieve;
+use crate::pixel::soil::Soil;
+use crate::pixel::spawner::Spawner;
+use crate::pixel::spout::Spout;
 use crate::pixel::steam::Steam;
+use crate::pixel::switch::Switch;
+use crate::pixel::timer::Timer;
+use crate::pixel::tnt::Tnt;
+use crate::pixel::toxic_gas::ToxicGas;
+use crate::pixel::turbine::Turbine;
+use crate::pixel::vine::Vine;
 use crate::pixel::void::Void;
 use crate::pixel::water::Water;
+use crate::pixel::water_wheel::WaterWheel;
+use crate::pixel::wet_sand::WetSand;
+use crate::pixel::wire::Wire;
 use crate::pixel::wood::Wood;
+use crate::registry;
 use crate::sandbox::Sandbox;
+use crate::temperature;
 use enum_dispatch::enum_dispatch;
 use itertools::Itertools;
 use rand::distributions::Distribution;
 use rand::distributions::Uniform;
 use rand::Rng;
+use serde::{Deserialize, Serialize};
 use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
 use std::sync::OnceLock;
+use strum::IntoEnumIterator;
 
 /// Holds the type and density of a pixel
 #[derive(Debug, Eq, PartialEq)]
@@ -42,7 +122,60 @@ pub enum PixelType {
     Void,
 }
 
-#[derive(Debug, Clone, Copy)]
+impl PixelType {
+    /// Whether pixels of this type can be moved around, by the simulation or by tools.
+    pub fn is_movable(&self) -> bool {
+        matches!(
+            self,
+            PixelType::Gas(_) | PixelType::Liquid(_) | PixelType::Solid(_)
+        )
+    }
+}
+
+/// How gravity pulls on the movable pixels of a cell.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Ord, PartialOrd)]
+pub enum Gravity {
+    #[default]
+    Normal,
+    /// Nothing falls or rises, pixels hover in place
+    Nullified,
+    /// Solids and liquids rise, gases sink
+    Inverted,
+}
+
+/// How readily heat passes through a pixel, see [`PixelFundamental::insulates`].
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Insulation {
+    /// Passes heat along faster, like metal
+    Conductor,
+    #[default]
+    Neutral,
+    /// Holds heat back
+    Insulator,
+}
+
+impl Insulation {
+    /// Degrees per ambient period passing through a side made of the material.
+    pub(crate) fn conductance(self) -> u8 {
+        match self {
+            Insulation::Conductor => 2,
+            Insulation::Neutral => 1,
+            Insulation::Insulator => 0,
+        }
+    }
+
+    /// 256ths of the difference in temperature flowing every tick through a side made of the
+    /// material, see [`crate::sandbox::Sandbox::temperature_at`].
+    pub(crate) fn conduction(self) -> u16 {
+        match self {
+            Insulation::Conductor => 64,
+            Insulation::Neutral => 32,
+            Insulation::Insulator => 0,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
 #[repr(u8)]
 pub enum Direction {
     Up,
@@ -56,7 +189,34 @@ pub enum Direction {
 }
 
 impl Direction {
-    pub fn gas_directions<R: Rng>(rng: &mut R) -> &'static [Direction] {
+    /// Points the other way.
+    pub fn opposite(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::Left => Direction::Right,
+            Direction::Right => Direction::Left,
+            Direction::UpLeft => Direction::DownRight,
+            Direction::UpRight => Direction::DownLeft,
+            Direction::DownLeft => Direction::UpRight,
+            Direction::DownRight => Direction::UpLeft,
+        }
+    }
+
+    /// Mirrors the direction upside down.
+    pub fn flipped(self) -> Direction {
+        match self {
+            Direction::Up => Direction::Down,
+            Direction::Down => Direction::Up,
+            Direction::UpLeft => Direction::DownLeft,
+            Direction::UpRight => Direction::DownRight,
+            Direction::DownLeft => Direction::UpLeft,
+            Direction::DownRight => Direction::UpRight,
+            Direction::Left | Direction::Right => self,
+        }
+    }
+
+    pub fn gas_directions<R: Rng + ?Sized>(rng: &mut R) -> &'static [Direction] {
         static DIRECTIONS: OnceLock<Vec<[Direction; 5]>> = OnceLock::new();
         let v = DIRECTIONS.get_or_init(|| {
             let v = vec![
@@ -84,14 +244,14 @@ impl Direction {
         let idx = between.sample(rng);
         v[idx].as_ref()
     }
-    pub fn liquid_directions<R: Rng>(rng: &mut R) -> &'static [Direction] {
+    pub fn liquid_directions<R: Rng + ?Sized>(rng: &mut R) -> &'static [Direction] {
         static DIRECTIONS: OnceLock<Vec<[Direction; 5]>> = OnceLock::new();
         let v = DIRECTIONS.get_or_init(|| {
             let v1 = vec![
                 [Direction::DownLeft, Direction::DownRight],
                 [Direction::DownRight, Direction::DownLeft],
             ];
-            let v2 = vec![
+            let v2 = [
                 [Direction::Left, Direction::Right],
                 [Direction::Right, Direction::Left],
             ];
@@ -111,7 +271,7 @@ impl Direction {
         let idx = between.sample(rng);
         v[idx].as_ref()
     }
-    pub fn solid_directions<R: Rng>(rng: &mut R) -> &'static [Direction] {
+    pub fn solid_directions<R: Rng + ?Sized>(rng: &mut R) -> &'static [Direction] {
         static DIRECTIONS: OnceLock<Vec<[Direction; 3]>> = OnceLock::new();
         let v = DIRECTIONS.get_or_init(|| {
             let v = vec![
@@ -138,10 +298,59 @@ pub trait PixelFundamental {
 
     fn pixel_type(&self) -> PixelType;
 
-    fn update(&mut self) -> Option<Pixel> {
+    /// Updates the pixel at the end of its tick, returns what it turns into.
+    ///
+    /// `temp` is the temperature of its cell, see [`crate::temperature`], which the pixel may
+    /// change, e.g. to keep a flame hot. The cell keeps it when the pixel turns into another.
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        None
+    }
+
+    /// Temperature of the cell a new pixel of the element is placed in.
+    fn initial_temperature(&self) -> u8 {
+        temperature::ROOM
+    }
+
+    /// Called when the pixel hits something while travelling at `speed` cells per tick, e.g. at
+    /// the end of a fall, returns what the pixel turns into.
+    fn impact<R: Rng + ?Sized>(&self, _speed: u8, _rng: &mut R) -> Option<Pixel> {
         None
     }
 
+    /// Percentage of its speed the pixel keeps when it bounces off what it hits, `0` for the
+    /// ones that just thud to a stop.
+    fn restitution(&self) -> u8 {
+        0
+    }
+
+    /// Percentage of their speed pixels sinking through the liquid lose every tick.
+    ///
+    /// `0` lets them through as fast as they fall through the air. A viscous gas stays put for
+    /// as many percent of the ticks instead, so it drifts slowly.
+    fn viscosity(&self) -> u8 {
+        0
+    }
+
+    /// How the pixel slows down or speeds up heat passing through it, which decides how fast
+    /// what it encloses follows the ambient temperature.
+    fn insulates(&self) -> Insulation {
+        Insulation::Neutral
+    }
+
+    /// Whether a wall lets a pixel of type `moving` through when it moves in `dir`, the pixel
+    /// then lands in the first cell past the wall.
+    fn lets_through(&self, _moving: &PixelType, _dir: Direction) -> bool {
+        false
+    }
+
+    /// How far a solid has to be able to drop next to it before it topples diagonally.
+    ///
+    /// `1` lets it slide off any slope, higher values make steeper piles: sand piles up at 45°,
+    /// gravel at twice the slope and wet sand at three times.
+    fn topple_height(&self) -> usize {
+        1
+    }
+
     fn tick_move<R: Rng>(
         &self,
         x: usize,
@@ -149,12 +358,21 @@ pub trait PixelFundamental {
         sandbox: &mut Sandbox<R>,
     ) -> Option<(usize, usize)> {
         let check_density = |sandbox: &Sandbox<R>, density, dir: Direction, reverse: bool| {
-            sandbox
-                .get_neighbour_pixel(x, y, dir)
-                .and_then(|(x, y, p)| match p.is_moved() {
-                    true => None,
-                    false => Some((x, y, p.pixel().pixel_type())),
-                })
+            let mut neighbour = sandbox.get_neighbour_pixel(x, y, dir);
+            while let Some((x, y, p)) = neighbour {
+                if !p.pixel().lets_through(&self.pixel_type(), dir) {
+                    break;
+                }
+                neighbour = sandbox.get_neighbour_pixel(x, y, dir);
+            }
+            neighbour
+                // composites only move as a whole, nothing swaps with a single cell of them
+                .and_then(
+                    |(x, y, p)| match p.is_moved() || p.pixel().footprint().is_some() {
+                        true => None,
+                        false => Some((x, y, p.pixel().pixel_type())),
+                    },
+                )
                 .and_then(|(x, y, p)| match p {
                     PixelType::Solid(td) | PixelType::Gas(td) | PixelType::Liquid(td) => {
                         match (density == td, density > td, reverse) {
@@ -169,27 +387,108 @@ pub trait PixelFundamental {
                 })
         };
 
+        let gravity = sandbox.gravity_at(x, y);
+        if gravity == Gravity::Nullified {
+            return None;
+        }
+        let orient = |dir: &Direction| match gravity {
+            Gravity::Inverted => dir.flipped(),
+            Gravity::Normal | Gravity::Nullified => *dir,
+        };
+
+        // the diagonal cell and the ones under it need to be open for the solid to topple
+        let can_topple = |sandbox: &Sandbox<R>, dir: Direction| {
+            let step = match dir {
+                Direction::DownLeft | Direction::DownRight => 1,
+                Direction::UpLeft | Direction::UpRight => -1,
+                _ => return true,
+            };
+            sandbox
+                .get_neighbour_coordinates(x, y, dir)
+                .is_some_and(|(x, y)| {
+                    (1..self.topple_height() as isize).all(|dy| {
+                        let y = y as isize + dy * step;
+                        y >= 0
+                            && sandbox.is_coordinate_in_bound(x, y as usize)
+                            && !matches!(
+                                sandbox.pixels[sandbox.coordinates_to_index(x, y as usize)]
+                                    .pixel()
+                                    .pixel_type(),
+                                PixelType::Solid(_) | PixelType::Wall
+                            )
+                    })
+                })
+        };
+
         match self.pixel_type() {
             PixelType::Gas(density) => Direction::gas_directions(sandbox.rng())
                 .iter()
-                .find_map(|dir| check_density(sandbox, density, *dir, true)),
+                .map(orient)
+                .find_map(|dir| check_density(sandbox, density, dir, true)),
             PixelType::Liquid(density) => Direction::liquid_directions(sandbox.rng())
                 .iter()
-                .find_map(|dir| check_density(sandbox, density, *dir, false)),
+                .map(orient)
+                .find_map(|dir| check_density(sandbox, density, dir, false)),
             PixelType::Solid(density) => Direction::solid_directions(sandbox.rng())
                 .iter()
-                .find_map(|dir| check_density(sandbox, density, *dir, false)),
+                .map(orient)
+                .filter(|dir| can_topple(sandbox, *dir))
+                .find_map(|dir| check_density(sandbox, density, dir, false)),
             PixelType::Wall | PixelType::Void => None,
         }
     }
 }
 
+/// The pixels next to a pixel, `None` past the border of the sandbox.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Neighbours {
+    pub up: Option<Pixel>,
+    pub down: Option<Pixel>,
+    pub left: Option<Pixel>,
+    pub right: Option<Pixel>,
+}
+
+impl Neighbours {
+    pub fn iter(&self) -> impl Iterator<Item = Pixel> {
+        [self.up, self.down, self.left, self.right]
+            .into_iter()
+            .flatten()
+    }
+}
+
 #[enum_dispatch]
 pub trait PixelInteract {
     fn interact(&mut self, _target: Pixel) {}
+
+    /// Runs once per tick after [`PixelInteract::interact`], for reactions that depend on
+    /// where the neighbours are.
+    fn react(&mut self, _neighbours: &Neighbours) {}
+
+    /// Called when the user clicks the pixel, e.g. to flip a switch.
+    fn activate(&mut self) {}
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, strum_macros::EnumIter)]
+#[derive(
+    Debug,
+    Copy,
+    Clone,
+    Eq,
+    PartialEq,
+    strum_macros::EnumIter,
+    strum_macros::EnumDiscriminants,
+    Serialize,
+    Deserialize,
+)]
+// `PixelKind` is the element of a pixel without its state
+#[strum_discriminants(
+    name(PixelKind),
+    derive(
+        strum_macros::EnumIter,
+        strum_macros::IntoStaticStr,
+        Serialize,
+        Deserialize
+    )
+)]
 #[repr(u8)]
 #[enum_dispatch(PixelInteract, PixelFundamental)]
 pub enum Pixel {
@@ -201,6 +500,43 @@ pub enum Pixel {
     Fire(Fire),
     EternalFire(EternalFire),
     Wood(Wood),
+    Lava(Lava),
+    Obsidian(Obsidian),
+    Gravel(Gravel),
+    Soil(Soil),
+    Grass(Grass),
+    Mud(Mud),
+    Vine(Vine),
+    Fungus(Fungus),
+    Dust(Dust),
+    Foam(Foam),
+    Poison(Poison),
+    PoisonedWater(PoisonedWater),
+    ToxicGas(ToxicGas),
+    Wire(Wire),
+    Battery(Battery),
+    Switch(Switch),
+    Timer(Timer),
+    Spawner(Spawner),
+    LevitationField(LevitationField),
+    Salt(Salt),
+    Pipe(Pipe),
+    Pump(Pump),
+    Turbine(Turbine),
+    WaterWheel(WaterWheel),
+    Sieve(Sieve),
+    Cloud(Cloud),
+    WetSand(WetSand),
+    Fog(Fog),
+    Boulder(Boulder),
+    Plank(Plank),
+    Oil(Oil),
+    Gunpowder(Gunpowder),
+    Tnt(Tnt),
+    Spout(Spout),
+    Drain(Drain),
+    Rubber(Rubber),
+    Custom(Custom),
     Void(Void),
 }
 
@@ -210,6 +546,184 @@ impl Default for Pixel {
     }
 }
 
+/// Hashes the element by name rather than by variant, so adding elements doesn't change the
+/// hash of existing ones.
+impl Hash for Pixel {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.name().hash(state);
+        match self {
+            Pixel::Steam(p) => p.hash(state),
+            Pixel::Sand(p) => p.hash(state),
+            Pixel::Rock(p) => p.hash(state),
+            Pixel::Water(p) => p.hash(state),
+            Pixel::Ice(p) => p.hash(state),
+            Pixel::Fire(p) => p.hash(state),
+            Pixel::EternalFire(p) => p.hash(state),
+            Pixel::Wood(p) => p.hash(state),
+            Pixel::Lava(p) => p.hash(state),
+            Pixel::Obsidian(p) => p.hash(state),
+            Pixel::Gravel(p) => p.hash(state),
+            Pixel::Soil(p) => p.hash(state),
+            Pixel::Grass(p) => p.hash(state),
+            Pixel::Mud(p) => p.hash(state),
+            Pixel::Vine(p) => p.hash(state),
+            Pixel::Fungus(p) => p.hash(state),
+            Pixel::Dust(p) => p.hash(state),
+            Pixel::Foam(p) => p.hash(state),
+            Pixel::Poison(p) => p.hash(state),
+            Pixel::PoisonedWater(p) => p.hash(state),
+            Pixel::ToxicGas(p) => p.hash(state),
+            Pixel::Wire(p) => p.hash(state),
+            Pixel::Battery(p) => p.hash(state),
+            Pixel::Switch(p) => p.hash(state),
+            Pixel::Timer(p) => p.hash(state),
+            Pixel::Spawner(p) => p.hash(state),
+            Pixel::LevitationField(p) => p.hash(state),
+            Pixel::Salt(p) => p.hash(state),
+            Pixel::Pipe(p) => p.hash(state),
+            Pixel::Pump(p) => p.hash(state),
+            Pixel::Turbine(p) => p.hash(state),
+            Pixel::WaterWheel(p) => p.hash(state),
+            Pixel::Sieve(p) => p.hash(state),
+            Pixel::Cloud(p) => p.hash(state),
+            Pixel::WetSand(p) => p.hash(state),
+            Pixel::Fog(p) => p.hash(state),
+            Pixel::Boulder(p) => p.hash(state),
+            Pixel::Plank(p) => p.hash(state),
+            Pixel::Oil(p) => p.hash(state),
+            Pixel::Gunpowder(p) => p.hash(state),
+            Pixel::Tnt(p) => p.hash(state),
+            Pixel::Spout(p) => p.hash(state),
+            Pixel::Drain(p) => p.hash(state),
+            Pixel::Rubber(p) => p.hash(state),
+            Pixel::Custom(p) => p.hash(state),
+            Pixel::Void(p) => p.hash(state),
+        }
+    }
+}
+
+impl Pixel {
+    /// Finds the default pixel with the given [`PixelFundamental::name`], registered elements
+    /// included.
+    pub fn from_name(name: &str) -> Option<Pixel> {
+        Pixel::all().find(|p| p.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Default pixel of every element, the builtin ones in declaration order followed by the
+    /// registered ones.
+    pub fn all() -> impl Iterator<Item = Pixel> {
+        Pixel::iter()
+            .filter(|p| !matches!(p, Pixel::Custom(_)))
+            .chain(registry::registry().pixels())
+    }
+
+    /// Whether the pixel is hot enough to boil or burn what it touches.
+    pub fn is_hot(&self) -> bool {
+        match self {
+            Pixel::Fire(_) | Pixel::EternalFire(_) | Pixel::Lava(_) => true,
+            Pixel::Wood(wood) => wood.is_burning(),
+            Pixel::Vine(vine) => vine.is_burning(),
+            Pixel::Oil(oil) => oil.is_burning(),
+            _ => false,
+        }
+    }
+
+    /// Radius of the blast the pixel sets off this tick, if it is a lit explosive.
+    pub fn blast_radius(&self) -> Option<usize> {
+        match self {
+            Pixel::Gunpowder(p) if p.is_lit() => Some(Gunpowder::BLAST_RADIUS),
+            Pixel::Tnt(p) if p.is_lit() => Some(Tnt::BLAST_RADIUS),
+            _ => None,
+        }
+    }
+
+    /// Lights the pixel if it is an explosive, setting it off. Returns whether it is one.
+    pub fn light(&mut self) -> bool {
+        match self {
+            Pixel::Gunpowder(p) => p.light(),
+            Pixel::Tnt(p) => p.light(),
+            _ => return false,
+        }
+        true
+    }
+
+    /// Whether the pixel passes a spark to the conductors next to it this tick.
+    pub fn is_sparking(&self) -> bool {
+        match self {
+            Pixel::Battery(_) => true,
+            Pixel::Wire(wire) => wire.is_sparking(),
+            Pixel::Switch(switch) => switch.is_sparking(),
+            Pixel::Timer(timer) => timer.is_sparking(),
+            Pixel::Turbine(turbine) => turbine.is_sparking(),
+            Pixel::WaterWheel(wheel) => wheel.is_sparking(),
+            _ => false,
+        }
+    }
+
+    /// Whether clicking the pixel does anything, see [`PixelInteract::activate`].
+    pub fn is_activatable(&self) -> bool {
+        matches!(
+            self,
+            Pixel::Switch(_) | Pixel::Timer(_) | Pixel::LevitationField(_) | Pixel::Pump(_)
+        )
+    }
+
+    /// Whether the pixel is poisonous to anything living it touches.
+    pub fn is_toxic(&self) -> bool {
+        matches!(
+            self,
+            Pixel::Poison(_) | Pixel::PoisonedWater(_) | Pixel::ToxicGas(_)
+        )
+    }
+}
+
+/// Hashes the kind by variant name rather than by discriminant, so adding elements doesn't
+/// change the hash of the state holding existing ones.
+impl Hash for PixelKind {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        <&'static str>::from(self).hash(state);
+    }
+}
+
+impl PixelKind {
+    /// The element in its default state.
+    pub fn pixel(self) -> Pixel {
+        Pixel::iter()
+            .find(|p| PixelKind::from(p) == self)
+            .expect("every kind has a pixel")
+    }
+}
+
+/// Serializes a pixel as its [`PixelFundamental::name`], dropping its state.
+pub(crate) mod by_name {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use crate::pixel::{Pixel, PixelFundamental};
+
+    pub fn serialize<S: Serializer>(pixel: &Pixel, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(pixel.name())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Pixel, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        Pixel::from_name(&name)
+            .ok_or_else(|| serde::de::Error::custom(format!("unknown pixel {name}")))
+    }
+
+    /// Deserializes a list of names, for fields that aren't a single pixel.
+    pub fn deserialize_all<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Pixel>, D::Error> {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|name| {
+                Pixel::from_name(name)
+                    .ok_or_else(|| serde::de::Error::custom(format!("unknown pixel {name}")))
+            })
+            .collect()
+    }
+}
+
 impl Display for Pixel {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.name())
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::soil::Soil;
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Soil soaked with water, heat dries it back out.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Mud {
+    heated: bool,
+}
+
+impl PixelFundamental for Mud {
+    fn name(&self) -> &'static str {
+        "Mud"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Solid(55)
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        match self.heated {
+            true => Some(Soil::default().into()),
+            false => None,
+        }
+    }
+}
+
+impl PixelInteract for Mud {
+    fn interact(&mut self, target: Pixel) {
+        if let Pixel::Fire(_) | Pixel::EternalFire(_) | Pixel::Lava(_) = target {
+            self.heated = true;
+        }
+    }
+}
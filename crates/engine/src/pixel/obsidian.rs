@@ -0,0 +1,24 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Insulation, PixelFundamental, PixelInteract, PixelType};
+
+/// Lava cooled down by water, it doesn't burn, melt or move.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Obsidian;
+
+impl PixelFundamental for Obsidian {
+    fn name(&self) -> &'static str {
+        "Obsidian"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+
+    // volcanic glass
+    fn insulates(&self) -> Insulation {
+        Insulation::Insulator
+    }
+}
+
+impl PixelInteract for Obsidian {}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::fire::Fire;
+use crate::pixel::{Insulation, Pixel, PixelFundamental, PixelInteract, PixelType};
+use crate::temperature;
+
+/// Liquid lighter than water, so it floats on it, that catches fire once its cell gets hot and
+/// burns down into fire.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Oil {
+    /// Whether its cell was hot enough to set the oil on fire at the last update
+    burning: bool,
+    /// Ticks left to burn
+    pub life: u8,
+}
+
+impl Default for Oil {
+    fn default() -> Self {
+        Self {
+            burning: false,
+            life: 90,
+        }
+    }
+}
+
+impl Oil {
+    pub fn is_burning(&self) -> bool {
+        self.burning
+    }
+}
+
+impl PixelFundamental for Oil {
+    fn name(&self) -> &'static str {
+        "Oil"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Liquid(7)
+    }
+
+    /// Heat spreads through a pool only as it burns.
+    fn insulates(&self) -> Insulation {
+        Insulation::Insulator
+    }
+
+    fn update(&mut self, temp: &mut u8) -> Option<Pixel> {
+        self.burning = *temp >= temperature::IGNITION;
+        if self.burning {
+            *temp = temperature::FLAME;
+            self.life = self.life.saturating_sub(1);
+        }
+        (self.life == 0).then(|| Fire::default().into())
+    }
+}
+
+impl PixelInteract for Oil {}
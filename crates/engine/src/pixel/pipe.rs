@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Insulation, Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Wall segment that carries what a [`Pump`](crate::pixel::pump::Pump) pushes into it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Pipe;
+
+impl PixelFundamental for Pipe {
+    fn name(&self) -> &'static str {
+        "Pipe"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+
+    fn insulates(&self) -> Insulation {
+        Insulation::Conductor
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        None
+    }
+}
+
+impl PixelInteract for Pipe {}
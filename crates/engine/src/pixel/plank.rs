@@ -0,0 +1,40 @@
+use serde::{Deserialize, Serialize};
+
+use crate::composite::Composite;
+use crate::pixel::wood::Wood;
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Cell of a 3x1 board that falls whole and floats on water, splintering into wood when it
+/// loses a cell.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Plank {
+    part: u8,
+}
+
+impl Composite for Plank {
+    const FOOTPRINT: (usize, usize) = (3, 1);
+
+    fn part(&self) -> u8 {
+        self.part
+    }
+
+    fn with_part(&self, part: u8) -> Self {
+        Self { part }
+    }
+
+    fn debris(&self) -> Pixel {
+        Wood::default().into()
+    }
+}
+
+impl PixelFundamental for Plank {
+    fn name(&self) -> &'static str {
+        "Plank"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Solid(8)
+    }
+}
+
+impl PixelInteract for Plank {}
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::toxic_gas::ToxicGas;
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Heavy liquid slowly contaminating the water it touches.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Poison {
+    heated: bool,
+}
+
+impl PixelFundamental for Poison {
+    fn name(&self) -> &'static str {
+        "Poison"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Liquid(12)
+    }
+
+    fn viscosity(&self) -> u8 {
+        25
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        match self.heated {
+            true => Some(ToxicGas::default().into()),
+            false => None,
+        }
+    }
+}
+
+impl PixelInteract for Poison {
+    fn interact(&mut self, target: Pixel) {
+        if target.is_hot() {
+            self.heated = true;
+        }
+    }
+}
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::toxic_gas::ToxicGas;
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Water contaminated by poison, it spreads the contamination further.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct PoisonedWater {
+    heated: bool,
+}
+
+impl PixelFundamental for PoisonedWater {
+    fn name(&self) -> &'static str {
+        "Poisoned water"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Liquid(10)
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        match self.heated {
+            true => Some(ToxicGas::default().into()),
+            false => None,
+        }
+    }
+}
+
+impl PixelInteract for PoisonedWater {
+    fn interact(&mut self, target: Pixel) {
+        if target.is_hot() {
+            self.heated = true;
+        }
+    }
+}
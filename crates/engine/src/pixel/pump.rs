@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Direction, Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Moves liquid from the cell behind it to the far end of the pipes in front of it, whatever
+/// gravity says. Clicking it turns it clockwise.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Pump {
+    facing: Direction,
+}
+
+impl Default for Pump {
+    fn default() -> Self {
+        Self {
+            facing: Direction::Right,
+        }
+    }
+}
+
+impl Pump {
+    pub fn new(facing: Direction) -> Self {
+        Self { facing }
+    }
+
+    /// Side the liquid leaves through.
+    pub fn outlet(&self) -> Direction {
+        self.facing
+    }
+
+    /// Side the liquid is taken from.
+    pub fn inlet(&self) -> Direction {
+        self.facing.opposite()
+    }
+}
+
+impl PixelFundamental for Pump {
+    fn name(&self) -> &'static str {
+        "Pump"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        None
+    }
+}
+
+impl PixelInteract for Pump {
+    fn activate(&mut self) {
+        self.facing = match self.facing {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            _ => Direction::Up,
+        };
+    }
+}
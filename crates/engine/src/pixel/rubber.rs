@@ -0,0 +1,23 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{PixelFundamental, PixelInteract, PixelType};
+
+/// Bouncy pellets, thrown or dropped they spring back off what they hit.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Rubber;
+
+impl PixelFundamental for Rubber {
+    fn name(&self) -> &'static str {
+        "Rubber"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Solid(12)
+    }
+
+    fn restitution(&self) -> u8 {
+        75
+    }
+}
+
+impl PixelInteract for Rubber {}
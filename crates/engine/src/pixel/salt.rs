@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::void::Void;
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelKind, PixelType};
+
+/// Dissolves in the water around it, and crystallizes back out when salty water evaporates.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Salt {
+    /// Ticks spent dissolving into water
+    wetness: u8,
+}
+
+impl Salt {
+    const DISSOLVED: u8 = 20;
+}
+
+impl PixelFundamental for Salt {
+    fn name(&self) -> &'static str {
+        "Salt"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Solid(30)
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        (self.wetness >= Self::DISSOLVED).then(|| Void::default().into())
+    }
+}
+
+impl PixelInteract for Salt {
+    fn interact(&mut self, target: Pixel) {
+        if let Pixel::Water(water) = target {
+            if water.solution().can_dissolve(PixelKind::Salt) {
+                self.wetness += 1;
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
+use serde::{Deserialize, Serialize};
+
 use crate::pixel::{PixelFundamental, PixelInteract, PixelType};
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
 pub struct Sand;
 
 impl PixelFundamental for Sand {
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Direction, PixelFundamental, PixelInteract, PixelType};
+
+/// Wall with holes too small for powders and solids, liquids and gases pass straight through
+/// it up or down.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Sieve;
+
+impl PixelFundamental for Sieve {
+    fn name(&self) -> &'static str {
+        "Sieve"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+
+    fn lets_through(&self, moving: &PixelType, dir: Direction) -> bool {
+        matches!(moving, PixelType::Liquid(_) | PixelType::Gas(_))
+            && matches!(dir, Direction::Up | Direction::Down)
+    }
+}
+
+impl PixelInteract for Sieve {}
@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::grass::Grass;
+use crate::pixel::mud::Mud;
+use crate::pixel::{Neighbours, Pixel, PixelFundamental, PixelInteract, PixelType};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Soil {
+    pub moisture: u8,
+    /// Ticks spent wet under open sky
+    growth: u8,
+}
+
+impl Soil {
+    const WET: u8 = 10;
+    const SATURATED: u8 = 100;
+    const GROWTH: u8 = 120;
+
+    /// Dries the soil by `amount`, e.g. in a hot climate.
+    pub fn dry(&mut self, amount: u8) {
+        self.moisture = self.moisture.saturating_sub(amount);
+    }
+
+    /// Whether the soil still soaks up water next to it.
+    pub fn is_thirsty(&self) -> bool {
+        self.moisture < Self::SATURATED
+    }
+}
+
+impl PixelFundamental for Soil {
+    fn name(&self) -> &'static str {
+        "Soil"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Solid(50)
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        if !self.is_thirsty() {
+            Some(Mud::default().into())
+        } else if self.growth >= Self::GROWTH {
+            Some(Grass::default().into())
+        } else {
+            None
+        }
+    }
+}
+
+impl PixelInteract for Soil {
+    fn interact(&mut self, target: Pixel) {
+        match target {
+            Pixel::Water(_) => self.moisture = self.moisture.saturating_add(10),
+            // grass stops growing on soil dried out by fire
+            target if target.is_hot() => self.dry(Self::WET / 2),
+            _ => {}
+        }
+    }
+
+    fn react(&mut self, neighbours: &Neighbours) {
+        // there is no light yet, the open sky counts as lit
+        let is_lit = matches!(neighbours.up, Some(Pixel::Void(_)));
+        self.growth = match is_lit && self.moisture >= Self::WET {
+            true => self.growth.saturating_add(1),
+            false => 0,
+        };
+    }
+}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Pixel, PixelKind};
+
+/// Solid dissolved in a liquid, spreading to the liquid around it until the liquid evaporates
+/// and leaves the solid behind.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Solution {
+    solute: Option<PixelKind>,
+    concentration: u8,
+}
+
+impl Solution {
+    pub const SATURATED: u8 = 100;
+    /// Taken in per tick from a soluble neighbour
+    const DISSOLVING: u8 = 5;
+
+    pub fn solute(&self) -> Option<PixelKind> {
+        self.solute
+    }
+
+    pub fn concentration(&self) -> u8 {
+        self.concentration
+    }
+
+    pub fn can_dissolve(&self, solute: PixelKind) -> bool {
+        self.concentration < Self::SATURATED && self.solute.is_none_or(|s| s == solute)
+    }
+
+    pub fn dissolve(&mut self, solute: PixelKind) {
+        if self.can_dissolve(solute) {
+            self.solute = Some(solute);
+            self.concentration = (self.concentration + Self::DISSOLVING).min(Self::SATURATED);
+        }
+    }
+
+    /// Evens out the concentration with the solution of a neighbouring cell, one unit at a time.
+    pub fn diffuse(&mut self, other: Solution) {
+        if other.solute.is_some() && self.solute.is_some_and(|s| Some(s) != other.solute) {
+            return;
+        }
+        if other.concentration > self.concentration + 1 {
+            self.solute = other.solute;
+            self.concentration += 1;
+        } else if other.concentration + 1 < self.concentration {
+            self.concentration -= 1;
+            if self.concentration == 0 {
+                self.solute = None;
+            }
+        }
+    }
+
+    /// What crystallizes out once the liquid is gone, if it held enough of it.
+    pub fn precipitate(&self) -> Option<Pixel> {
+        self.solute
+            .filter(|_| self.concentration >= Self::SATURATED / 2)
+            .map(PixelKind::pixel)
+    }
+}
@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelKind, PixelType};
+
+/// Fills the empty cells next to it with an element once every period.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Spawner {
+    element: PixelKind,
+    period: u8,
+    ticks: u8,
+}
+
+impl Default for Spawner {
+    fn default() -> Self {
+        Self::new(PixelKind::Sand, 10)
+    }
+}
+
+impl Spawner {
+    /// Creates a spawner emitting `element` every `period` ticks.
+    pub fn new(element: PixelKind, period: u8) -> Self {
+        Self {
+            element,
+            period: period.max(1),
+            ticks: 0,
+        }
+    }
+
+    pub fn element(&self) -> PixelKind {
+        self.element
+    }
+
+    pub fn period(&self) -> u8 {
+        self.period
+    }
+
+    pub fn is_spawning(&self) -> bool {
+        self.ticks == 0
+    }
+}
+
+impl PixelFundamental for Spawner {
+    fn name(&self) -> &'static str {
+        "Spawner"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        self.ticks = (self.ticks + 1) % self.period;
+        None
+    }
+}
+
+impl PixelInteract for Spawner {}
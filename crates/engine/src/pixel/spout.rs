@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{PixelFundamental, PixelInteract, PixelKind, PixelType};
+
+/// Pours an element into the empty cell below it on every tick.
+///
+/// The spout doesn't place anything itself, the empty cell below claims the element when it
+/// reacts to its neighbours, the way cells next to a [`Spawner`](super::spawner::Spawner) do.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Spout {
+    element: PixelKind,
+}
+
+impl Default for Spout {
+    fn default() -> Self {
+        Self::new(PixelKind::Water)
+    }
+}
+
+impl Spout {
+    /// Elements the frontends cycle through
+    pub const ELEMENTS: [PixelKind; 3] = [PixelKind::Water, PixelKind::Sand, PixelKind::Lava];
+
+    /// Creates a spout pouring `element`.
+    pub fn new(element: PixelKind) -> Self {
+        Self { element }
+    }
+
+    pub fn element(&self) -> PixelKind {
+        self.element
+    }
+
+    /// The spout pouring the element following its own in [`Spout::ELEMENTS`].
+    pub fn cycle(&self) -> Self {
+        let next = Self::ELEMENTS
+            .iter()
+            .position(|&element| element == self.element)
+            .map_or(0, |idx| (idx + 1) % Self::ELEMENTS.len());
+        Self::new(Self::ELEMENTS[next])
+    }
+}
+
+impl PixelFundamental for Spout {
+    fn name(&self) -> &'static str {
+        "Spout"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+}
+
+impl PixelInteract for Spout {}
@@ -1,49 +1,60 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::fog::Fog;
 use crate::pixel::water::Water;
-use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+use crate::pixel::{Neighbours, Pixel, PixelFundamental, PixelInteract, PixelType};
+use crate::temperature;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Steam {
-    temp: u8,
+    /// Degrees above condensing of its cell as of the last update, up to [`Steam::MAX_HEAT`]
+    heat: u8,
+    /// Whether the steam touches empty cells, where it condenses into fog rather than water
+    #[serde(default)]
+    in_open_air: bool,
 }
+
 impl Default for Steam {
     fn default() -> Self {
-        Self { temp: 200 }
+        Self {
+            heat: Self::MAX_HEAT,
+            in_open_air: false,
+        }
     }
 }
 
+impl Steam {
+    const MAX_HEAT: u8 = temperature::STEAM - temperature::CONDENSING;
+}
+
 impl PixelFundamental for Steam {
     fn name(&self) -> &'static str {
         "Steam"
     }
 
+    /// Cooling steam gets denser and sinks below the hotter steam rising past it.
     fn pixel_type(&self) -> PixelType {
-        PixelType::Gas(-10)
+        PixelType::Gas(-5 - (self.heat / 5) as i8)
     }
-    fn update(&mut self) -> Option<Pixel> {
-        if self.temp < 10 {
-            Some(Water::default().into())
-        } else {
-            None
+
+    fn initial_temperature(&self) -> u8 {
+        temperature::STEAM
+    }
+
+    fn update(&mut self, temp: &mut u8) -> Option<Pixel> {
+        self.heat = temp
+            .saturating_sub(temperature::CONDENSING)
+            .min(Self::MAX_HEAT);
+        match (*temp < temperature::CONDENSING, self.in_open_air) {
+            (true, true) => Some(Fog::default().into()),
+            (true, false) => Some(Water::default().into()),
+            (false, _) => None,
         }
     }
 }
 
 impl PixelInteract for Steam {
-    fn interact(&mut self, target: Pixel) {
-        match target {
-            Pixel::Water(_) | Pixel::Steam(_) => {
-                if self.temp > 0 {
-                    self.temp -= 1;
-                }
-            }
-            Pixel::Ice(_) => {
-                if self.temp > 1 {
-                    self.temp -= 2;
-                } else {
-                    self.temp = 0;
-                }
-            }
-            _ => {}
-        }
+    fn react(&mut self, neighbours: &Neighbours) {
+        self.in_open_air = neighbours.iter().any(|n| matches!(n, Pixel::Void(_)));
     }
 }
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::wire::Charge;
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Conducts sparks like a wire while it is on, the user flips it by clicking it.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Switch {
+    pub on: bool,
+    charge: Charge,
+}
+
+impl Switch {
+    pub fn is_sparking(&self) -> bool {
+        self.on && self.charge.is_sparking()
+    }
+}
+
+impl PixelFundamental for Switch {
+    fn name(&self) -> &'static str {
+        "Switch"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        self.charge.update();
+        None
+    }
+}
+
+impl PixelInteract for Switch {
+    fn interact(&mut self, target: Pixel) {
+        if self.on {
+            self.charge.interact(target);
+        }
+    }
+
+    fn activate(&mut self) {
+        self.on = !self.on;
+    }
+}
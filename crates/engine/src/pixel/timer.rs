@@ -0,0 +1,61 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Sparks into the wires next to it once every period, clicking it picks the next period.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct Timer {
+    period: u8,
+    ticks: u8,
+}
+
+impl Default for Timer {
+    fn default() -> Self {
+        Self::new(30)
+    }
+}
+
+impl Timer {
+    pub const PERIODS: [u8; 5] = [15, 30, 60, 120, 240];
+
+    /// Creates a timer sparking every `period` ticks.
+    pub fn new(period: u8) -> Self {
+        Self {
+            period: period.max(1),
+            ticks: 0,
+        }
+    }
+
+    pub fn period(&self) -> u8 {
+        self.period
+    }
+
+    pub fn is_sparking(&self) -> bool {
+        self.ticks == 0
+    }
+}
+
+impl PixelFundamental for Timer {
+    fn name(&self) -> &'static str {
+        "Timer"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        self.ticks = (self.ticks + 1) % self.period;
+        None
+    }
+}
+
+impl PixelInteract for Timer {
+    fn activate(&mut self) {
+        let next = Self::PERIODS
+            .iter()
+            .position(|&period| period == self.period)
+            .map_or(0, |idx| (idx + 1) % Self::PERIODS.len());
+        *self = Self::new(Self::PERIODS[next]);
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+use crate::temperature;
+
+/// Block of explosive going off in a large blast when a flame or a spark reaches it, so it can
+/// be set off from afar through a wire, see [`Pixel::blast_radius`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Tnt {
+    lit: bool,
+}
+
+impl Tnt {
+    pub const BLAST_RADIUS: usize = 6;
+
+    pub fn is_lit(&self) -> bool {
+        self.lit
+    }
+
+    pub fn light(&mut self) {
+        self.lit = true;
+    }
+}
+
+impl PixelFundamental for Tnt {
+    fn name(&self) -> &'static str {
+        "TNT"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+
+    fn update(&mut self, temp: &mut u8) -> Option<Pixel> {
+        self.lit |= *temp >= temperature::IGNITION;
+        None
+    }
+}
+
+impl PixelInteract for Tnt {
+    fn interact(&mut self, target: Pixel) {
+        self.lit |= target.is_hot() || target.is_sparking();
+    }
+}
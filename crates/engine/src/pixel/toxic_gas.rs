@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::void::Void;
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Fumes of boiled poison, deadly to anything living until they thin out.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct ToxicGas {
+    life: u8,
+}
+
+impl Default for ToxicGas {
+    fn default() -> Self {
+        Self { life: 120 }
+    }
+}
+
+impl PixelFundamental for ToxicGas {
+    fn name(&self) -> &'static str {
+        "Toxic gas"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Gas(-5)
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        self.life -= 1;
+
+        if self.life == 0 {
+            Some(Void::default().into())
+        } else {
+            None
+        }
+    }
+}
+
+impl PixelInteract for ToxicGas {}
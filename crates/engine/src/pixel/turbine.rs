@@ -0,0 +1,54 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Sparks into the wires next to it while liquids or gases keep flowing past it.
+///
+/// The sandbox reports how many fluid pixels moved into the cells around the turbine every
+/// tick, at the end of each window the turbine sparks once if enough of them did.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Turbine {
+    /// Pixels that flowed past during the current window
+    flow: u8,
+    ticks: u8,
+    sparking: bool,
+}
+
+impl Turbine {
+    /// Ticks over which the flow is counted
+    pub const WINDOW: u8 = 10;
+    /// Pixels that need to flow past in a window for the turbine to spark
+    pub const MIN_FLOW: u8 = 4;
+
+    pub fn is_sparking(&self) -> bool {
+        self.sparking
+    }
+
+    /// Counts `passes` more pixels flowing past during the current window.
+    pub fn record_flow(&mut self, passes: u8) {
+        self.flow = self.flow.saturating_add(passes);
+    }
+}
+
+impl PixelFundamental for Turbine {
+    fn name(&self) -> &'static str {
+        "Turbine"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        self.ticks += 1;
+        self.sparking = false;
+        if self.ticks == Self::WINDOW {
+            self.sparking = self.flow >= Self::MIN_FLOW;
+            self.flow = 0;
+            self.ticks = 0;
+        }
+        None
+    }
+}
+
+impl PixelInteract for Turbine {}
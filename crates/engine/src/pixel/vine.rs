@@ -0,0 +1,84 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::dust::Dust;
+use crate::pixel::fire::Fire;
+use crate::pixel::{Neighbours, Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Climbing plant growing up along rock and wood.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Vine {
+    /// Whether an unbroken stem connects the vine to the ground
+    rooted: bool,
+    growth: u8,
+    /// Ticks since the vine caught fire, `0` while it isn't burning
+    burning: u8,
+    poisoned: bool,
+}
+
+impl Vine {
+    const GROWTH: u8 = 90;
+    const BURN_TICKS: u8 = 10;
+
+    pub fn is_burning(&self) -> bool {
+        self.burning > 0
+    }
+
+    /// Whether the vine grows into the empty cell above it this tick.
+    pub fn is_budding(&self) -> bool {
+        self.growth == Self::GROWTH
+    }
+}
+
+impl PixelFundamental for Vine {
+    fn name(&self) -> &'static str {
+        "Vine"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        if self.poisoned {
+            return Some(Dust.into());
+        }
+        if self.is_burning() {
+            self.burning += 1;
+            self.growth = 0;
+            return (self.burning >= Self::BURN_TICKS).then(|| Fire::default().into());
+        }
+
+        self.growth = match self.rooted && self.growth < Self::GROWTH {
+            true => self.growth + 1,
+            false => 0,
+        };
+        None
+    }
+}
+
+impl PixelInteract for Vine {
+    fn interact(&mut self, target: Pixel) {
+        let ignites = match target {
+            Pixel::Fire(_) | Pixel::EternalFire(_) | Pixel::Lava(_) => true,
+            Pixel::Wood(wood) => wood.is_burning(),
+            Pixel::Vine(vine) => vine.is_burning(),
+            Pixel::Oil(oil) => oil.is_burning(),
+            _ => false,
+        };
+        if ignites && !self.is_burning() {
+            self.burning = 1;
+        }
+        if target.is_toxic() {
+            self.poisoned = true;
+        }
+    }
+
+    fn react(&mut self, neighbours: &Neighbours) {
+        // cutting the stem stops everything above the cut from growing
+        self.rooted = match neighbours.down {
+            Some(Pixel::Vine(vine)) => vine.rooted,
+            Some(Pixel::Void(_)) | None => false,
+            Some(_) => true,
+        };
+    }
+}
@@ -1,9 +1,11 @@
-use crate::pixel::fire::Fire;
-use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq, Default)]
+use crate::pixel::{Neighbours, Pixel, PixelFundamental, PixelInteract, PixelKind, PixelType};
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
 pub struct Void {
-    burn: bool,
+    /// What fills the cell on the next update, the first neighbour to claim it wins
+    becomes: Option<PixelKind>,
 }
 
 impl PixelFundamental for Void {
@@ -15,20 +17,35 @@ impl PixelFundamental for Void {
         PixelType::Void
     }
 
-    fn update(&mut self) -> Option<Pixel> {
-        if self.burn {
-            Some(Fire::default().into())
-        } else {
-            None
-        }
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        self.becomes.map(PixelKind::pixel)
     }
 }
 
 impl PixelInteract for Void {
     fn interact(&mut self, target: Pixel) {
         match target {
-            Pixel::EternalFire(_) => self.burn = true,
+            Pixel::EternalFire(_) => {
+                self.becomes.get_or_insert(PixelKind::Fire);
+            }
+            Pixel::Lava(lava) if lava.is_sparking() => {
+                self.becomes.get_or_insert(PixelKind::Fire);
+            }
+            Pixel::Spawner(spawner) if spawner.is_spawning() => {
+                self.becomes.get_or_insert(spawner.element());
+            }
             _ => {}
         }
     }
+
+    fn react(&mut self, neighbours: &Neighbours) {
+        if let Some(Pixel::Spout(spout)) = neighbours.up {
+            self.becomes.get_or_insert(spout.element());
+        }
+        let is_surface = |p: Option<Pixel>| matches!(p, Some(Pixel::Rock(_) | Pixel::Wood(_)));
+        let is_budding = matches!(neighbours.down, Some(Pixel::Vine(vine)) if vine.is_budding());
+        if is_budding && (is_surface(neighbours.left) || is_surface(neighbours.right)) {
+            self.becomes.get_or_insert(PixelKind::Vine);
+        }
+    }
 }
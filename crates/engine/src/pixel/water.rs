@@ -1,24 +1,38 @@
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::foam::Foam;
 use crate::pixel::ice::Ice;
+use crate::pixel::poisoned_water::PoisonedWater;
+use crate::pixel::solution::Solution;
 use crate::pixel::steam::Steam;
-use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+use crate::pixel::void::Void;
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelKind, PixelType};
+use crate::temperature;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
 pub struct Water {
-    pub temp: u8,
-}
-
-impl Default for Water {
-    fn default() -> Self {
-        Self { temp: 20 }
-    }
+    /// How much lighter the water is for the temperature of its cell, as of the last update
+    buoyancy: i8,
+    /// Soaked up by soil, the water is gone on the next update
+    absorbed: bool,
+    /// How much poison the water took in from its neighbours
+    toxin: u8,
+    solution: Solution,
 }
 
 impl Water {
-    pub fn is_burning(&self) -> bool {
-        self.temp >= 30
+    const FOAMING_SPEED: u8 = 6;
+    const POISONED: u8 = 30;
+
+    pub fn solution(&self) -> Solution {
+        self.solution
     }
-    pub fn is_frozen(&self) -> bool {
-        self.temp <= 10
+
+    /// Warm water is a little lighter than cold water, so it rises through it, while salty
+    /// water sinks below fresh water.
+    fn density(&self) -> i8 {
+        10 - self.buoyancy + (self.solution.concentration() >= Solution::SATURATED / 2) as i8
     }
 }
 
@@ -28,13 +42,31 @@ impl PixelFundamental for Water {
     }
 
     fn pixel_type(&self) -> PixelType {
-        PixelType::Liquid(10)
+        PixelType::Liquid(self.density())
+    }
+
+    fn impact<R: Rng + ?Sized>(&self, speed: u8, rng: &mut R) -> Option<Pixel> {
+        // a hard landing whips some of the water into foam
+        (speed >= Self::FOAMING_SPEED && rng.gen_ratio(1, 4)).then(|| Foam::default().into())
     }
 
-    fn update(&mut self) -> Option<Pixel> {
-        if self.is_burning() {
-            Some(Steam::default().into())
-        } else if self.is_frozen() {
+    fn update(&mut self, temp: &mut u8) -> Option<Pixel> {
+        self.buoyancy = match *temp {
+            25.. => 1,
+            ..=15 => -1,
+            _ => 0,
+        };
+        if self.absorbed {
+            Some(Void::default().into())
+        } else if self.toxin >= Self::POISONED {
+            Some(PoisonedWater::default().into())
+        } else if *temp >= temperature::BOILING {
+            Some(
+                self.solution
+                    .precipitate()
+                    .unwrap_or_else(|| Steam::default().into()),
+            )
+        } else if *temp <= temperature::FREEZING {
             Some(Ice::default().into())
         } else {
             None
@@ -44,21 +76,19 @@ impl PixelFundamental for Water {
 
 impl PixelInteract for Water {
     fn interact(&mut self, target: Pixel) {
+        if let Pixel::Water(other) = target {
+            self.solution.diffuse(other.solution);
+        }
+
         match target {
-            Pixel::Fire(_) | Pixel::EternalFire(_) => {
-                if !self.is_burning() {
-                    self.temp += 2;
-                }
+            Pixel::Salt(_) => {
+                self.solution.dissolve(PixelKind::Salt);
             }
-            Pixel::Wood(val) => {
-                if val.is_burning() && !self.is_burning() {
-                    self.temp += 2;
-                }
+            Pixel::Soil(soil) if soil.is_thirsty() => {
+                self.absorbed = true;
             }
-            Pixel::Ice(_) => {
-                if !self.is_frozen() {
-                    self.temp -= 2;
-                }
+            Pixel::Poison(_) | Pixel::PoisonedWater(_) => {
+                self.toxin = self.toxin.saturating_add(1);
             }
             _ => {}
         }
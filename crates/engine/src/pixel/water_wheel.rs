@@ -0,0 +1,105 @@
+use serde::{Deserialize, Serialize};
+
+use crate::composite::Composite;
+use crate::pixel::wood::Wood;
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Cell of a 3x3 wheel turned by the liquids and gases flowing past it, sparking into the wires
+/// next to it on every step it turns.
+///
+/// The wheel is driven from its hub, the centre cell: the sandbox adds up the flow past all of
+/// its cells on the hub and copies the hub's state to the rim cells. A wheel missing any of its
+/// cells falls apart into wood.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub struct WaterWheel {
+    /// Row major position in the footprint
+    part: u8,
+    /// Which of the four spoke directions the wheel shows
+    angle: u8,
+    /// Flow gathered towards the next step
+    torque: u8,
+    sparking: bool,
+}
+
+impl Default for WaterWheel {
+    fn default() -> Self {
+        Self {
+            part: Self::HUB,
+            angle: 0,
+            torque: 0,
+            sparking: false,
+        }
+    }
+}
+
+impl Composite for WaterWheel {
+    const FOOTPRINT: (usize, usize) = (3, 3);
+
+    fn part(&self) -> u8 {
+        self.part
+    }
+
+    fn with_part(&self, part: u8) -> Self {
+        Self { part, ..*self }
+    }
+
+    fn debris(&self) -> Pixel {
+        Wood::default().into()
+    }
+}
+
+impl WaterWheel {
+    /// Part of the centre cell
+    pub const HUB: u8 = 4;
+    /// Flow needed to turn the wheel by a step
+    pub const STEP_TORQUE: u8 = 6;
+
+    pub fn is_hub(&self) -> bool {
+        self.part == Self::HUB
+    }
+
+    pub fn angle(&self) -> u8 {
+        self.angle
+    }
+
+    pub fn is_sparking(&self) -> bool {
+        self.sparking
+    }
+
+    /// Whether the cell lies on the spokes at the current angle, which is how the wheel shows
+    /// it turning.
+    pub fn is_spoke(&self) -> bool {
+        let spokes = match self.angle {
+            0 => [1, 7],
+            1 => [2, 6],
+            2 => [5, 3],
+            _ => [8, 0],
+        };
+        self.is_hub() || spokes.contains(&self.part)
+    }
+
+    /// Adds `flow` to the torque of the hub, turning the wheel by a step once there is enough.
+    ///
+    /// Returns whether it turned.
+    pub fn drive(&mut self, flow: u8) -> bool {
+        self.torque = self.torque.saturating_add(flow);
+        self.sparking = self.torque >= Self::STEP_TORQUE;
+        if self.sparking {
+            self.torque -= Self::STEP_TORQUE;
+            self.angle = (self.angle + 1) % 4;
+        }
+        self.sparking
+    }
+}
+
+impl PixelFundamental for WaterWheel {
+    fn name(&self) -> &'static str {
+        "Water wheel"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+}
+
+impl PixelInteract for WaterWheel {}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::sand::Sand;
+use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Sand damp enough to hold steep piles, e.g. for a sandcastle, until heat dries it back out.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct WetSand {
+    heated: bool,
+}
+
+impl PixelFundamental for WetSand {
+    fn name(&self) -> &'static str {
+        "Wet sand"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Solid(55)
+    }
+
+    fn topple_height(&self) -> usize {
+        3
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        self.heated.then(|| Sand.into())
+    }
+}
+
+impl PixelInteract for WetSand {
+    fn interact(&mut self, target: Pixel) {
+        if target.is_hot() {
+            self.heated = true;
+        }
+    }
+}
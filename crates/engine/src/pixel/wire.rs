@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::{Insulation, Pixel, PixelFundamental, PixelInteract, PixelType};
+
+/// Spark travelling through a conductor.
+///
+/// A charged conductor waits a tick, sparks for one tick, then rests for two so the spark
+/// moves on instead of bouncing back.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Charge(u8);
+
+impl Charge {
+    const CHARGED: u8 = 5;
+    const SPARKING: u8 = 3;
+
+    pub fn is_sparking(&self) -> bool {
+        self.0 == Self::SPARKING
+    }
+
+    pub fn interact(&mut self, target: Pixel) {
+        if self.0 == 0 && target.is_sparking() {
+            self.0 = Self::CHARGED;
+        }
+    }
+
+    pub fn update(&mut self) {
+        self.0 = self.0.saturating_sub(1);
+    }
+}
+
+/// Conducts sparks.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Default, Serialize, Deserialize)]
+pub struct Wire {
+    charge: Charge,
+}
+
+impl Wire {
+    pub fn is_sparking(&self) -> bool {
+        self.charge.is_sparking()
+    }
+}
+
+impl PixelFundamental for Wire {
+    fn name(&self) -> &'static str {
+        "Wire"
+    }
+
+    fn pixel_type(&self) -> PixelType {
+        PixelType::Wall
+    }
+
+    fn insulates(&self) -> Insulation {
+        Insulation::Conductor
+    }
+
+    fn update(&mut self, _temp: &mut u8) -> Option<Pixel> {
+        self.charge.update();
+        None
+    }
+}
+
+impl PixelInteract for Wire {
+    fn interact(&mut self, target: Pixel) {
+        self.charge.interact(target);
+    }
+}
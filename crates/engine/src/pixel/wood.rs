@@ -1,21 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use crate::pixel::fungus::Fungus;
 use crate::pixel::void::Void;
-use crate::pixel::{Pixel, PixelFundamental, PixelInteract, PixelType};
+use crate::pixel::{Insulation, Pixel, PixelFundamental, PixelInteract, PixelType};
+use crate::temperature;
 
-#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct Wood {
-    pub temp: u8,
+    /// Whether its cell was hot enough to set the wood on fire at the last update
+    pub(crate) burning: bool,
     pub life: u8,
+    /// How far fungus has eaten into the wood
+    pub rot: u8,
+    /// Water soaked up, heat has to dry it out before the wood catches fire
+    #[serde(default)]
+    pub moisture: u8,
 }
 
 impl Default for Wood {
     fn default() -> Self {
-        Self { temp: 0, life: 225 }
+        Self {
+            burning: false,
+            life: 225,
+            rot: 0,
+            moisture: 0,
+        }
     }
 }
 
 impl Wood {
+    const ROTTEN: u8 = 200;
+    const WET: u8 = 10;
+    const SOAKED: u8 = 100;
+
     pub fn is_burning(&self) -> bool {
-        self.temp >= 99
+        self.burning
+    }
+
+    pub fn is_wet(&self) -> bool {
+        self.moisture >= Self::WET
+    }
+
+    /// Dries the wood by `amount`, e.g. in a hot climate.
+    pub fn dry(&mut self, amount: u8) {
+        self.moisture = self.moisture.saturating_sub(amount);
     }
 }
 
@@ -32,12 +60,25 @@ impl PixelFundamental for Wood {
         }
     }
 
-    fn update(&mut self) -> Option<Pixel> {
-        if self.is_burning() && self.life > 0 {
-            self.life -= 1;
+    fn insulates(&self) -> Insulation {
+        Insulation::Insulator
+    }
+
+    fn update(&mut self, temp: &mut u8) -> Option<Pixel> {
+        // the water soaked up has to boil away before the wood gets any hotter
+        if self.moisture > 0 && *temp > temperature::BOILING {
+            self.moisture = self.moisture.saturating_sub(Self::WET);
+            *temp = temperature::BOILING;
+        }
+        self.burning = *temp >= temperature::IGNITION;
+        if self.burning {
+            *temp = temperature::FLAME;
+            self.life = self.life.saturating_sub(1);
         }
         if self.life == 0 {
             Some(Void::default().into())
+        } else if self.rot >= Self::ROTTEN {
+            Some(Fungus::default().into())
         } else {
             None
         }
@@ -48,24 +89,10 @@ impl PixelInteract for Wood {
     fn interact(&mut self, target: Pixel) {
         match target {
             Pixel::Water(_) => {
-                if self.is_burning() {
-                    self.temp -= 20;
-                }
-            }
-            Pixel::Ice(_) => {
-                if self.is_burning() {
-                    self.temp -= 30;
-                }
-            }
-            Pixel::Fire(_) | Pixel::EternalFire(_) => {
-                if !self.is_burning() {
-                    self.temp += 20;
-                }
+                self.moisture = (self.moisture + 1).min(Self::SOAKED);
             }
-            Pixel::Wood(val) => {
-                if val.is_burning() && !self.is_burning() {
-                    self.temp += 20;
-                }
+            Pixel::Fungus(_) if !self.is_burning() => {
+                self.rot = self.rot.saturating_add(1);
             }
             _ => {}
         }
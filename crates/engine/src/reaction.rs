@@ -0,0 +1,120 @@
+use std::path::Path;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+use strum::IntoEnumIterator;
+
+use crate::pixel::{by_name, Pixel, PixelKind};
+
+/// How likely a pixel is to react to one of its neighbours on a given tick.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Reaction {
+    #[serde(with = "by_name")]
+    pub pixel: Pixel,
+    #[serde(with = "by_name")]
+    pub target: Pixel,
+    pub probability: f32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ReactionsFile {
+    reactions: Vec<Reaction>,
+}
+
+/// Probability of every pair of elements reacting on contact, per tick.
+///
+/// Pairs that aren't configured always react.
+#[derive(Debug, Clone)]
+pub struct Reactions {
+    /// Indexed by the kind of the reacting pixel, then the kind of its neighbour
+    probabilities: Vec<f32>,
+    kinds: usize,
+}
+
+impl Default for Reactions {
+    fn default() -> Self {
+        let kinds = PixelKind::iter().count();
+        Self {
+            probabilities: vec![1.0; kinds * kinds],
+            kinds,
+        }
+    }
+}
+
+impl Reactions {
+    pub fn from_toml(s: &str) -> anyhow::Result<Self> {
+        let file: ReactionsFile = toml::from_str(s)?;
+        let mut reactions = Self::default();
+        for reaction in file.reactions {
+            if !(0.0..=1.0).contains(&reaction.probability) {
+                return Err(anyhow!(
+                    "probability of {} reacting to {} must be between 0 and 1",
+                    reaction.pixel,
+                    reaction.target
+                ));
+            }
+            reactions.set_probability(
+                (&reaction.pixel).into(),
+                (&reaction.target).into(),
+                reaction.probability,
+            );
+        }
+        Ok(reactions)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read reactions {}", path.display()))?;
+        Self::from_toml(&s).with_context(|| format!("failed to parse reactions {}", path.display()))
+    }
+
+    /// The rates shipped with the engine, tuned for 60 ticks per second.
+    pub fn builtin() -> Self {
+        static BUILTIN: OnceLock<Reactions> = OnceLock::new();
+        BUILTIN
+            .get_or_init(|| {
+                Self::from_toml(include_str!("../reactions.toml"))
+                    .expect("builtin reactions must be valid")
+            })
+            .clone()
+    }
+
+    fn index(&self, pixel: PixelKind, target: PixelKind) -> usize {
+        pixel as usize * self.kinds + target as usize
+    }
+
+    pub fn probability(&self, pixel: PixelKind, target: PixelKind) -> f32 {
+        self.probabilities[self.index(pixel, target)]
+    }
+
+    pub fn set_probability(&mut self, pixel: PixelKind, target: PixelKind, probability: f32) {
+        let idx = self.index(pixel, target);
+        self.probabilities[idx] = probability;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pixel::PixelKind;
+    use crate::reaction::Reactions;
+
+    #[test]
+    fn test_reactions() {
+        let reactions = Reactions::builtin();
+        assert!(reactions.probability(PixelKind::Salt, PixelKind::Water) < 1.0);
+        assert_eq!(
+            reactions.probability(PixelKind::Sand, PixelKind::Water),
+            1.0
+        );
+
+        let invalid = r#"
+            [[reactions]]
+            pixel = "Sand"
+            target = "Water"
+            probability = 2.0
+        "#;
+        assert!(Reactions::from_toml(invalid).is_err());
+    }
+}
@@ -0,0 +1,298 @@
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, bail};
+use serde::{Deserialize, Serialize};
+
+use crate::catalog::{self, Category, Chord};
+use crate::palette::Rgb;
+use crate::pixel::custom::Custom;
+use crate::pixel::{Pixel, PixelType};
+
+/// How an element defined by a [`Descriptor`] moves.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Behaviour {
+    /// Falls and piles up like sand
+    Powder,
+    /// Falls and spreads out flat
+    Liquid,
+    /// Rises and drifts around
+    Gas,
+    /// Stays where it is placed
+    Wall,
+}
+
+/// Turns an element defined by a [`Descriptor`] into another when it touches a third.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Reaction {
+    /// Name of the element that sets the reaction off
+    pub touching: String,
+    /// Name of the element it turns into
+    pub becomes: String,
+}
+
+/// Element defined by data rather than by code, for elements added without editing the
+/// engine.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Descriptor {
+    pub name: String,
+    pub behaviour: Behaviour,
+    /// Denser elements sink through lighter ones of the same kind, ignored for walls
+    #[serde(default)]
+    pub density: i8,
+    pub color: Rgb,
+    /// Key selecting the element, any free one
+    #[serde(default)]
+    pub hotkey: Option<char>,
+    /// Keys selecting the element one after the other, for when no single key is left
+    #[serde(default)]
+    pub chord: Option<Chord>,
+    /// See [`crate::pixel::PixelFundamental::viscosity`]
+    #[serde(default)]
+    pub viscosity: u8,
+    /// Checked in order, the first one matching a neighbour wins
+    #[serde(default)]
+    pub reactions: Vec<Reaction>,
+}
+
+impl Descriptor {
+    pub(crate) fn pixel_type(&self) -> PixelType {
+        match self.behaviour {
+            Behaviour::Powder => PixelType::Solid(self.density),
+            Behaviour::Liquid => PixelType::Liquid(self.density),
+            Behaviour::Gas => PixelType::Gas(self.density),
+            Behaviour::Wall => PixelType::Wall,
+        }
+    }
+
+    pub(crate) fn category(&self) -> Category {
+        match self.behaviour {
+            Behaviour::Powder => Category::Powder,
+            Behaviour::Liquid => Category::Liquid,
+            Behaviour::Gas => Category::Gas,
+            Behaviour::Wall => Category::Solid,
+        }
+    }
+}
+
+/// Elements added to the builtin ones, which the sandbox, the catalog and so every frontend
+/// pick up as if they were builtin.
+///
+/// The registry is filled by the crates embedding the engine and installed once, before the
+/// catalog is first used. Pixels of registered elements refer to them by their position in
+/// the registry, so snapshots holding them only load with the same elements registered in the
+/// same order.
+#[derive(Debug, Default, Clone)]
+pub struct PixelRegistry {
+    descriptors: Vec<Descriptor>,
+    /// Keys the frontend binds to something else than an element
+    reserved: Vec<char>,
+}
+
+static REGISTRY: OnceLock<PixelRegistry> = OnceLock::new();
+
+impl PixelRegistry {
+    /// Keeps the elements registered from now on from taking `keys` as a hotkey or starting a
+    /// chord with them, as the frontend binds them to its tools.
+    pub fn reserve_keys(&mut self, keys: impl IntoIterator<Item = char>) {
+        self.reserved.extend(keys);
+    }
+
+    /// Adds the element `descriptor` describes, returning its pixel.
+    ///
+    /// Fails if its name, hotkey or chord is taken, it has no key at all, or its reactions name
+    /// elements that don't exist.
+    pub fn register(&mut self, descriptor: Descriptor) -> anyhow::Result<Pixel> {
+        let builtin = catalog::builtin();
+        let is_taken = |name: &str| {
+            builtin.iter().any(|e| e.name.eq_ignore_ascii_case(name))
+                || self
+                    .descriptors
+                    .iter()
+                    .any(|d| d.name.eq_ignore_ascii_case(name))
+        };
+        if descriptor.name.is_empty() {
+            bail!("an element needs a name");
+        }
+        if is_taken(&descriptor.name) {
+            bail!("an element named {} already exists", descriptor.name);
+        }
+        if descriptor.hotkey.is_none() && descriptor.chord.is_none() {
+            bail!("{} needs a hotkey or a chord", descriptor.name);
+        }
+        if let Some(hotkey) = descriptor.hotkey {
+            if self.reserved.contains(&hotkey) {
+                bail!(
+                    "{} can't use hotkey {}, the interface uses it",
+                    descriptor.name,
+                    hotkey
+                );
+            }
+            if builtin.iter().any(|e| e.hotkey == hotkey)
+                || self.descriptors.iter().any(|d| d.hotkey == Some(hotkey))
+            {
+                bail!(
+                    "{} can't use hotkey {}, it is taken",
+                    descriptor.name,
+                    hotkey
+                );
+            }
+        }
+        if let Some(chord) = descriptor.chord {
+            if self.reserved.contains(&chord.0) {
+                bail!(
+                    "{} can't use chord {}, the interface uses {}",
+                    descriptor.name,
+                    chord,
+                    chord.0
+                );
+            }
+            if builtin.iter().any(|e| e.chord == Some(chord))
+                || self.descriptors.iter().any(|d| d.chord == Some(chord))
+            {
+                bail!("{} can't use chord {}, it is taken", descriptor.name, chord);
+            }
+        }
+        if descriptor.reactions.len() > u8::MAX as usize {
+            bail!("{} has more than {} reactions", descriptor.name, u8::MAX);
+        }
+        for reaction in &descriptor.reactions {
+            for name in [&reaction.touching, &reaction.becomes] {
+                if !is_taken(name) && !name.eq_ignore_ascii_case(&descriptor.name) {
+                    bail!("{} reacts with unknown element {}", descriptor.name, name);
+                }
+            }
+        }
+        let id = u16::try_from(self.descriptors.len())
+            .map_err(|_| anyhow!("too many elements registered"))?;
+        self.descriptors.push(descriptor);
+        Ok(Custom::new(id).into())
+    }
+
+    /// Makes the registered elements part of the game.
+    ///
+    /// Fails once the catalog has been built, which freezes the set of elements.
+    pub fn install(self) -> anyhow::Result<()> {
+        REGISTRY
+            .set(self)
+            .map_err(|_| anyhow!("elements must be registered before the catalog is used"))
+    }
+
+    pub fn descriptors(&self) -> &[Descriptor] {
+        &self.descriptors
+    }
+
+    /// Default pixels of the registered elements.
+    pub fn pixels(&self) -> impl Iterator<Item = Pixel> + '_ {
+        (0..self.descriptors.len()).map(|id| Custom::new(id as u16).into())
+    }
+}
+
+/// The installed registry, an empty one if none was installed before the first call.
+pub fn registry() -> &'static PixelRegistry {
+    REGISTRY.get_or_init(PixelRegistry::default)
+}
+
+/// Registered element `id`, if any was installed under it.
+pub(crate) fn descriptor(id: u16) -> Option<&'static Descriptor> {
+    // not `registry`, which would keep elements from being registered later on
+    REGISTRY.get()?.descriptors.get(id as usize)
+}
+
+#[cfg(test)]
+mod test {
+    use crate::catalog::Chord;
+    use crate::palette::Rgb;
+    use crate::registry::{Behaviour, Descriptor, PixelRegistry, Reaction};
+
+    fn slime() -> Descriptor {
+        Descriptor {
+            name: "Slime".to_string(),
+            behaviour: Behaviour::Liquid,
+            density: 40,
+            color: Rgb(0x5f, 0xd7, 0x00),
+            hotkey: Some('Z'),
+            chord: None,
+            viscosity: 60,
+            reactions: vec![Reaction {
+                touching: "Fire".to_string(),
+                becomes: "Steam".to_string(),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_registry_register() {
+        let mut registry = PixelRegistry::default();
+        assert!(registry.register(slime()).is_ok());
+        assert_eq!(registry.pixels().count(), 1);
+
+        // names and hotkeys are unique among the builtin and registered elements
+        let err = registry.register(slime()).unwrap_err();
+        assert!(err.to_string().contains("already exists"), "{}", err);
+        let sand = Descriptor {
+            name: "Goo".to_string(),
+            hotkey: Some('1'),
+            ..slime()
+        };
+        assert!(registry.register(sand).is_err());
+        let goo = Descriptor {
+            name: "Goo".to_string(),
+            hotkey: Some('Z'),
+            ..slime()
+        };
+        assert!(registry.register(goo).is_err());
+
+        let unknown = Descriptor {
+            name: "Goo".to_string(),
+            hotkey: Some('V'),
+            reactions: vec![Reaction {
+                touching: "Plasma".to_string(),
+                becomes: "Goo".to_string(),
+            }],
+            ..slime()
+        };
+        let err = registry.register(unknown).unwrap_err();
+        assert!(err.to_string().contains("Plasma"), "{}", err);
+
+        // elements past the single keys only need a chord, which can't be taken either
+        let keyless = Descriptor {
+            name: "Goo".to_string(),
+            hotkey: None,
+            ..slime()
+        };
+        assert!(registry.register(keyless.clone()).is_err());
+        let goo = Descriptor {
+            chord: Some(Chord('g', 'o')),
+            ..keyless.clone()
+        };
+        assert!(registry.register(goo).is_ok());
+        let gel = Descriptor {
+            name: "Gel".to_string(),
+            chord: Some(Chord('g', 'o')),
+            ..keyless
+        };
+        assert!(registry.register(gel).is_err());
+        assert_eq!(registry.descriptors().len(), 2);
+    }
+
+    #[test]
+    fn test_registry_reserved_keys() {
+        let mut registry = PixelRegistry::default();
+        registry.reserve_keys(['e', 'f']);
+        let err = registry
+            .register(Descriptor {
+                hotkey: Some('e'),
+                ..slime()
+            })
+            .unwrap_err();
+        assert!(err.to_string().contains("interface"), "{}", err);
+        let chorded = Descriptor {
+            hotkey: None,
+            chord: Some(Chord('f', 'o')),
+            ..slime()
+        };
+        assert!(registry.register(chorded).is_err());
+        assert!(registry.register(slime()).is_ok());
+    }
+}
@@ -1,12 +1,53 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::fmt::{Display, Formatter};
+use std::hash::{Hash, Hasher};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
 use rand::rngs::SmallRng;
-use rand::{Rng, SeedableRng};
+use rand::seq::SliceRandom;
+use rand::{Rng, RngCore, SeedableRng};
 
-use crate::pixel::{Direction, Pixel, PixelFundamental, PixelInteract, PixelType};
+use crate::annotation::{Annotation, MoveRule};
+use crate::catalog::{self, Category};
+use crate::clock::WorldTime;
+use crate::command::Command;
+use crate::composite::{self, Composite};
+use crate::config::{AirDrag, SandboxConfig, UpdateOrder};
+use crate::entity::{Entity, EntityKind};
+#[cfg(feature = "debug")]
+use crate::event::Invariant;
+use crate::event::{EventKind, SandboxEvent};
+use crate::hash::StableHasher;
+use crate::patch::Patch;
+use crate::pixel::battery::Battery;
+use crate::pixel::fire::Fire;
+use crate::pixel::levitation_field::LevitationField;
+use crate::pixel::{
+    Direction, Gravity, Neighbours, Pixel, PixelFundamental, PixelInteract, PixelKind, PixelType,
+};
+use crate::reaction::Reactions;
+use crate::rng::{CounterRng, RngStrategy};
+use crate::scenario::{Scenario, ScenarioPlayer, ScenarioReport};
+use crate::scene::{Region, Scene};
+use crate::temperature;
 
 #[derive(Debug, Default, Clone)]
 pub struct PixelContainer {
     pixel: Pixel,
     is_moved: bool,
+    /// Cells travelled per tick along each axis, the pixel ignores its usual movement until
+    /// it comes to rest
+    velocity: (i8, i8),
+    /// Consecutive ticks the pixel has been falling for
+    fall: u8,
+    /// Temperature of the cell, see [`temperature`], carried along as the pixel moves
+    temp: u8,
+    /// Stable id of the placed pixel, following it as it moves and changes state, `0` for
+    /// pixels the simulation created on its own
+    #[cfg(feature = "debug")]
+    id: u32,
 }
 
 impl PixelContainer {
@@ -14,6 +55,11 @@ impl PixelContainer {
         Self {
             pixel,
             is_moved: false,
+            velocity: (0, 0),
+            fall: 0,
+            temp: pixel.initial_temperature(),
+            #[cfg(feature = "debug")]
+            id: 0,
         }
     }
 
@@ -26,36 +72,347 @@ impl PixelContainer {
     pub fn is_moved(&self) -> bool {
         self.is_moved
     }
+    pub fn velocity(&self) -> (i8, i8) {
+        self.velocity
+    }
+    pub fn temperature(&self) -> u8 {
+        self.temp
+    }
+    #[cfg(feature = "debug")]
+    pub fn id(&self) -> u32 {
+        self.id
+    }
 
     pub fn mark_is_moved(&mut self, flag: bool) {
         self.is_moved = flag;
     }
 }
 
+/// Time spent in each phase of a tick.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct TickTimings {
+    pub movement: Duration,
+    pub interaction: Duration,
+    /// Applying commands, computing gravity and resetting the grid for the next tick
+    pub bookkeeping: Duration,
+}
+
+impl TickTimings {
+    pub fn total(&self) -> Duration {
+        self.movement + self.interaction + self.bookkeeping
+    }
+}
+
+/// State of a tracked pixel at the end of a tick, see [`Sandbox::track`].
+#[cfg(feature = "debug")]
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct PixelSample {
+    pub tick: u64,
+    pub temperature: u8,
+    pub velocity: (i8, i8),
+    /// Consecutive ticks the pixel had been falling for
+    pub fall: u8,
+}
+
+/// Cell filled by [`Sandbox::try_place_pixel`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub struct Placed {
+    pub x: usize,
+    pub y: usize,
+}
+
+/// Why [`Sandbox::try_place_pixel`] left a cell as it was.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PlaceError {
+    OutOfBounds,
+    /// The cell already holds a pixel of this element
+    Occupied(PixelKind),
+    /// The element isn't in [`Sandbox::allowed_elements`]
+    Disallowed(PixelKind),
+}
+
+impl Display for PlaceError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PlaceError::OutOfBounds => write!(f, "the cell is out of bounds"),
+            PlaceError::Occupied(kind) => {
+                write!(f, "the cell is occupied by {}", kind.pixel().name())
+            }
+            PlaceError::Disallowed(kind) => {
+                write!(f, "{} is not allowed here", kind.pixel().name())
+            }
+        }
+    }
+}
+
+impl std::error::Error for PlaceError {}
+
 #[derive(Debug)]
 pub struct Sandbox<R: Rng> {
     pub width: usize,
     pub height: usize,
     pub pixels: Vec<PixelContainer>,
     rng: R,
+    /// Replaces `rng` in deterministic mode
+    keyed_rng: Option<CounterRng>,
+    seed: Option<u64>,
+    /// Size the sandbox was created with, before any resize
+    initial_size: (usize, usize),
+    ticks: u64,
+    commands: Vec<Command>,
+    /// Every applied command along with the tick it was applied before, `None` once the grid
+    /// changed in a way they don't replay or there were too many of them
+    input_log: Option<Vec<(u64, Command)>>,
+    events: Vec<SandboxEvent>,
+    vacuumed: Vec<Pixel>,
+    /// Id given to the next placed pixel
+    #[cfg(feature = "debug")]
+    next_id: u32,
+    /// Gravity of every cell for the current tick, empty while no field overrides it
+    gravity: Vec<Gravity>,
+    /// Whether every cell is frozen, empty until a cell is
+    frozen: Vec<bool>,
+    /// Part of the grid the player is looking at, see [`Command::Focus`]
+    focus: Option<Region>,
+    /// Part of the grid whose moves are recorded, see [`Sandbox::annotate`]
+    annotated: Option<Region>,
+    annotations: Vec<Annotation>,
+    /// Cells the placements changed since [`Sandbox::record_patch`]
+    patch: Option<Patch>,
+    /// Chunks updated this tick, as the left, top, right and bottom ones inclusive, `None`
+    /// when every chunk is
+    awake_chunks: Option<(usize, usize, usize, usize)>,
+    reactions: Reactions,
+    config: SandboxConfig,
+    entities: Vec<Entity>,
+    /// Id given to the next spawned entity
+    next_entity_id: u32,
+    /// Elements commands may place, every one when `None`
+    allowed_elements: Option<Vec<PixelKind>>,
+    timings: TickTimings,
+    /// Id of the pixel whose history is kept
+    #[cfg(feature = "debug")]
+    tracked: Option<u32>,
+    #[cfg(feature = "debug")]
+    history: VecDeque<PixelSample>,
 }
 
 impl<R: Rng> Sandbox<R> {
+    /// Keys the numbers drawn while moving a pixel in deterministic mode
+    const MOVEMENT_STREAM: u64 = 0;
+    /// Keys the numbers drawn while a pixel reacts to its neighbours in deterministic mode
+    const INTERACTION_STREAM: u64 = 1;
+    /// Keys the numbers drawn to shuffle the columns of a row in deterministic mode
+    const ORDER_STREAM: u64 = 2;
+    /// Keys the numbers drawn for a charged cloud to strike in deterministic mode
+    const LIGHTNING_STREAM: u64 = 3;
+    /// Keys the numbers drawn to even out the heat flowing between cells in deterministic mode
+    const HEAT_STREAM: u64 = 4;
+    /// Keys the numbers drawn to scatter the fire of an explosion in deterministic mode
+    const EXPLOSION_STREAM: u64 = 5;
+
+    /// Ticks a solid must have been falling for to splash the liquid it lands in
+    const SPLASH_FALL: u8 = 4;
+    /// Fastest a splash throws liquid up, in cells per tick
+    const MAX_SPLASH: u8 = 6;
+    /// Ticks between two steps of the temperatures towards the ambient one
+    const AMBIENT_PERIOD: u64 = 60;
+    /// 256ths of the difference in temperature flowing every tick through the air or a gas
+    const GAS_CONDUCTION: u16 = 2;
+    /// Least 256ths of the difference in temperature flowing every tick out of a flame
+    const FLAME_CONDUCTION: u16 = 16;
+    /// Width and height of the chunks the far field sleeps in
+    pub const FAR_FIELD_CHUNK: usize = 32;
+    /// A charged cloud strikes on one tick out of this many
+    const STRIKE_ODDS: u32 = 20;
+    /// Speed a blast throws the pixels right outside of its radius at, in cells per tick
+    const BLAST_SPEED: f32 = 6.0;
+    /// Ticks of history kept for the tracked pixel
+    #[cfg(feature = "debug")]
+    pub const HISTORY: usize = 64;
+    /// Commands logged before the log is dropped rather than growing through a long session
+    pub const INPUT_LOG_LIMIT: usize = 1 << 16;
+
     fn new_with_rng(width: usize, height: usize, rng: R) -> Sandbox<R> {
         Self {
             width,
             height,
             pixels: vec![PixelContainer::default(); width * height],
             rng,
+            keyed_rng: None,
+            seed: None,
+            initial_size: (width, height),
+            ticks: 0,
+            commands: Vec::new(),
+            input_log: Some(Vec::new()),
+            events: Vec::new(),
+            vacuumed: Vec::new(),
+            #[cfg(feature = "debug")]
+            next_id: 1,
+            gravity: Vec::new(),
+            frozen: Vec::new(),
+            focus: None,
+            annotated: None,
+            annotations: Vec::new(),
+            patch: None,
+            awake_chunks: None,
+            reactions: Reactions::builtin(),
+            config: SandboxConfig::default(),
+            entities: Vec::new(),
+            next_entity_id: 1,
+            allowed_elements: None,
+            timings: TickTimings::default(),
+            #[cfg(feature = "debug")]
+            tracked: None,
+            #[cfg(feature = "debug")]
+            history: VecDeque::new(),
         }
     }
 
     pub fn new(width: usize, height: usize) -> Sandbox<SmallRng> {
-        Sandbox::new_with_rng(width, height, SmallRng::from_entropy())
+        Sandbox::<SmallRng>::with_rng_strategy(width, height, RngStrategy::Entropy)
+    }
+
+    /// Creates a sandbox whose randomness is fully determined by `seed`.
+    pub fn new_with_seed(width: usize, height: usize, seed: u64) -> Sandbox<SmallRng> {
+        Sandbox::<SmallRng>::with_rng_strategy(width, height, RngStrategy::Seeded(seed))
+    }
+
+    /// Creates a sandbox holding the [`Scene`] written in the TOML file at `path`.
+    pub fn from_scene(path: impl AsRef<Path>) -> anyhow::Result<Sandbox<SmallRng>> {
+        Ok(Scene::load(path)?.build())
+    }
+
+    /// Creates a sandbox in deterministic mode, where every random number is keyed by `seed`, the
+    /// tick and the coordinates of the pixel drawing it.
+    ///
+    /// Runs then only depend on the seed and the inputs, never on the platform or on the order
+    /// cells are processed in.
+    pub fn new_deterministic(width: usize, height: usize, seed: u64) -> Sandbox<SmallRng> {
+        Sandbox::<SmallRng>::with_rng_strategy(width, height, RngStrategy::Deterministic(seed))
+    }
+
+    /// Creates a sandbox drawing its random numbers as `strategy` says.
+    pub fn with_rng_strategy(
+        width: usize,
+        height: usize,
+        strategy: RngStrategy,
+    ) -> Sandbox<SmallRng> {
+        let seed = match strategy {
+            RngStrategy::Entropy => rand::random(),
+            RngStrategy::Seeded(seed) | RngStrategy::Deterministic(seed) => seed,
+        };
+        let mut sandbox = Sandbox::new_with_rng(width, height, SmallRng::seed_from_u64(seed));
+        sandbox.seed = Some(seed);
+        if let RngStrategy::Deterministic(seed) = strategy {
+            sandbox.keyed_rng = Some(CounterRng::new(seed));
+        }
+        sandbox
+    }
+
+    /// How the sandbox draws its random numbers, `None` for one created with a custom generator.
+    pub fn rng_strategy(&self) -> Option<RngStrategy> {
+        let seed = self.seed?;
+        Some(match self.is_deterministic() {
+            true => RngStrategy::Deterministic(seed),
+            false => RngStrategy::Seeded(seed),
+        })
+    }
+
+    pub fn is_deterministic(&self) -> bool {
+        self.keyed_rng.is_some()
+    }
+
+    pub fn config(&self) -> SandboxConfig {
+        self.config
+    }
+
+    pub fn set_config(&mut self, config: SandboxConfig) {
+        self.config = config;
+    }
+
+    /// Degrees Celsius the sandbox relaxes towards, see [`SandboxConfig::ambient`].
+    pub fn ambient_temperature(&self) -> Option<i16> {
+        self.config.ambient
+    }
+
+    pub fn set_ambient_temperature(&mut self, celsius: Option<i16>) {
+        self.config.ambient = celsius;
+    }
+
+    pub fn reactions(&self) -> &Reactions {
+        &self.reactions
+    }
+
+    /// Replaces the reaction rates, e.g. with ones tuned by the user.
+    pub fn set_reactions(&mut self, reactions: Reactions) {
+        self.reactions = reactions;
+    }
+
+    /// Elements commands may place, `None` when every one is allowed.
+    pub fn allowed_elements(&self) -> Option<&[PixelKind]> {
+        self.allowed_elements.as_deref()
+    }
+
+    /// Restricts the elements commands may place, e.g. for a puzzle, `None` lifts the restriction.
+    ///
+    /// Erasing is always allowed. Disallowed placements and transmutations are dropped when the
+    /// commands are applied, and [`Sandbox::place_pixel`] refuses them too, while
+    /// [`Sandbox::place_pixel_force`] still builds scenes with anything.
+    pub fn set_allowed_elements(&mut self, elements: Option<Vec<PixelKind>>) {
+        self.allowed_elements = elements;
+    }
+
+    /// Whether commands may place `pixel`, see [`Sandbox::set_allowed_elements`].
+    pub fn is_element_allowed(&self, pixel: &Pixel) -> bool {
+        match (&self.allowed_elements, pixel) {
+            (None, _) | (_, Pixel::Void(_)) => true,
+            (Some(elements), pixel) => elements.contains(&pixel.into()),
+        }
+    }
+
+    /// Seed the sandbox was created with, if it was created from one.
+    pub fn seed(&self) -> Option<u64> {
+        self.seed
+    }
+
+    /// Commands applied so far, each with the number of ticks that had passed at that point.
+    ///
+    /// `None` once the grid changed outside of them, e.g. by [`Sandbox::apply_patch`], or more
+    /// than [`Sandbox::INPUT_LOG_LIMIT`] were applied.
+    pub fn input_log(&self) -> Option<&[(u64, Command)]> {
+        self.input_log.as_deref()
+    }
+
+    pub(crate) fn initial_size(&self) -> (usize, usize) {
+        self.initial_size
+    }
+
+    /// Number of ticks run since the sandbox was created.
+    pub fn tick_count(&self) -> u64 {
+        self.ticks
     }
 
-    pub(crate) fn rng(&mut self) -> &mut R {
-        &mut self.rng
+    /// Time of day in the world, if [`SandboxConfig::day_length`] turns the clock on.
+    pub fn world_time(&self) -> Option<WorldTime> {
+        self.config
+            .day_length
+            .map(|day_length| WorldTime::at(self.ticks, day_length))
+    }
+
+    pub(crate) fn rng(&mut self) -> &mut dyn RngCore {
+        match self.keyed_rng.as_mut() {
+            Some(rng) => rng,
+            None => &mut self.rng,
+        }
+    }
+
+    /// Keys the random numbers drawn next to the pixel at `idx`, in deterministic mode.
+    fn focus_rng(&mut self, idx: usize, stream: u64) {
+        let (x, y) = self.index_to_coordinates(idx);
+        if let Some(rng) = self.keyed_rng.as_mut() {
+            rng.focus_stream(self.ticks, x, y, stream);
+        }
     }
 
     pub fn coordinates_to_index(&self, x: usize, y: usize) -> usize {
@@ -97,6 +454,45 @@ impl<R: Rng> Sandbox<R> {
         }
     }
 
+    /// Whether the cell at (`x`, `y`) was frozen with [`Command::Freeze`].
+    pub fn is_frozen(&self, x: usize, y: usize) -> bool {
+        self.is_frozen_at(self.coordinates_to_index(x, y))
+    }
+
+    fn is_frozen_at(&self, idx: usize) -> bool {
+        self.frozen.get(idx).copied().unwrap_or(false)
+    }
+
+    /// Whether the cell at `idx` is in the far field and sits this tick out.
+    fn is_dormant(&self, idx: usize) -> bool {
+        let Some((left, top, right, bottom)) = self.awake_chunks else {
+            return false;
+        };
+        let (x, y) = self.index_to_coordinates(idx);
+        let (x, y) = (x / Self::FAR_FIELD_CHUNK, y / Self::FAR_FIELD_CHUNK);
+        x < left || x > right || y < top || y > bottom
+    }
+
+    /// Picks the chunks updated this tick: all of them every
+    /// [`SandboxConfig::far_field_period`] ticks, otherwise the ones around the focus.
+    fn update_far_field(&mut self) {
+        self.awake_chunks = None;
+        let (Some(period), Some(focus)) = (self.config.far_field_period, self.focus) else {
+            return;
+        };
+        if period <= 1 || self.ticks.is_multiple_of(period as u64) {
+            return;
+        }
+        // a chunk of margin so what enters the view was already moving
+        let chunk = |at: usize| at / Self::FAR_FIELD_CHUNK;
+        self.awake_chunks = Some((
+            chunk(focus.x).saturating_sub(1),
+            chunk(focus.y).saturating_sub(1),
+            chunk(focus.x + focus.width.saturating_sub(1)) + 1,
+            chunk(focus.y + focus.height.saturating_sub(1)) + 1,
+        ));
+    }
+
     pub fn get_neighbour_pixel(
         &self,
         x: usize,
@@ -110,211 +506,3663 @@ impl<R: Rng> Sandbox<R> {
                     .map(|p| (x, y, p))
             })
     }
+    /// Places `pixel` at (`x`, `y`) if the cell is empty, see [`Sandbox::try_place_pixel`].
     pub fn place_pixel(&mut self, pixel: Pixel, x: usize, y: usize) {
+        let _ = self.try_place_pixel(pixel, x, y);
+    }
+
+    /// Places `pixel` at (`x`, `y`) if the cell is in bounds and empty and the element is
+    /// allowed, telling why not otherwise.
+    pub fn try_place_pixel(
+        &mut self,
+        pixel: Pixel,
+        x: usize,
+        y: usize,
+    ) -> Result<Placed, PlaceError> {
+        if !self.is_coordinate_in_bound(x, y) {
+            return Err(PlaceError::OutOfBounds);
+        }
+        if !self.is_element_allowed(&pixel) {
+            return Err(PlaceError::Disallowed((&pixel).into()));
+        }
         let index = self.coordinates_to_index(x, y);
-        if let Some(p) = self.pixels.get_mut(index) {
-            if p.pixel.pixel_type() != PixelType::Void {
-                return;
-            }
-            *p = PixelContainer::new(pixel);
+        let current = self.pixels[index].pixel;
+        if current.pixel_type() != PixelType::Void {
+            return Err(PlaceError::Occupied((&current).into()));
         }
+        self.pixels[index] = self.new_container(pixel);
+        Ok(Placed { x, y })
+    }
+
+    /// [`Sandbox::try_place_pixel`] over the cells of a brush, with the outcome of each in turn.
+    pub fn try_place_pixels(
+        &mut self,
+        pixel: Pixel,
+        cells: impl IntoIterator<Item = (usize, usize)>,
+    ) -> Vec<Result<Placed, PlaceError>> {
+        cells
+            .into_iter()
+            .map(|(x, y)| self.try_place_pixel(pixel, x, y))
+            .collect()
     }
 
     pub fn place_pixel_force(&mut self, pixel: Pixel, x: usize, y: usize) {
         let index = self.coordinates_to_index(x, y);
-        if let Some(p) = self.pixels.get_mut(index) {
-            *p = PixelContainer::new(pixel);
+        if index < self.pixels.len() {
+            self.pixels[index] = self.new_container(pixel);
         }
     }
 
-    pub fn tick(&mut self) {
-        for idx in (0..self.pixels.len() - 1).rev() {
-            let pixel = self.pixels.get(idx).unwrap();
-            if pixel.pixel().pixel_type() == PixelType::Void {
-                continue;
-            }
-
-            if pixel.is_moved {
-                continue;
+    /// Empties the cells over the rectangle starting at (`x`, `y`) whatever they hold, leaving
+    /// out the part past the border. The patch being recorded keeps what they held.
+    pub fn erase_area(&mut self, x: usize, y: usize, width: usize, height: usize) {
+        for y in y..(y + height).min(self.height) {
+            for x in x..(x + width).min(self.width) {
+                let idx = self.coordinates_to_index(x, y);
+                if self.pixels[idx].pixel().pixel_type() == PixelType::Void {
+                    continue;
+                }
+                let empty = self.new_container(Pixel::default());
+                let erased = std::mem::replace(&mut self.pixels[idx], empty);
+                if let Some(patch) = self.patch.as_mut() {
+                    patch.record(idx, erased, PixelKind::Void);
+                }
             }
+        }
+    }
 
-            let (x, y) = self.index_to_coordinates(idx);
+    /// Places a whole composite of `pixel`'s element with its top left cell at (`x`, `y`), over
+    /// empty cells unless `force` is set. Returns where its anchor went.
+    pub fn place_composite(
+        &mut self,
+        pixel: Pixel,
+        x: usize,
+        y: usize,
+        force: bool,
+    ) -> Result<Placed, PlaceError> {
+        self.check_composite(pixel, x, y, force)?;
+        let footprint = pixel.footprint().unwrap_or((1, 1));
+        for (part, (x, y)) in composite::footprint_cells(x, y, footprint).enumerate() {
+            self.place_pixel_force(pixel.with_part(part as u8), x, y);
+        }
+        Ok(Placed { x, y })
+    }
 
-            if let Some((new_x, new_y)) = pixel.pixel().tick_move(x, y, self) {
-                let new_index = self.coordinates_to_index(new_x, new_y);
+    fn check_composite(
+        &self,
+        pixel: Pixel,
+        x: usize,
+        y: usize,
+        force: bool,
+    ) -> Result<(), PlaceError> {
+        if !self.is_element_allowed(&pixel) {
+            return Err(PlaceError::Disallowed((&pixel).into()));
+        }
+        let footprint = pixel.footprint().unwrap_or((1, 1));
+        for (x, y) in composite::footprint_cells(x, y, footprint) {
+            if !self.is_coordinate_in_bound(x, y) {
+                return Err(PlaceError::OutOfBounds);
+            }
+            let current = self.pixels[self.coordinates_to_index(x, y)].pixel;
+            if !force && current.pixel_type() != PixelType::Void {
+                return Err(PlaceError::Occupied((&current).into()));
+            }
+        }
+        Ok(())
+    }
 
-                let pixel = self.pixels.get_mut(idx).unwrap();
-                pixel.mark_is_moved(true);
-                let swapping_pixel = self.pixels.get_mut(new_index).unwrap();
-                if swapping_pixel.pixel().pixel_type() != PixelType::Void {
-                    swapping_pixel.mark_is_moved(true);
+    /// Cells of the composite the pixel at `idx` is part of, if all of them are still there.
+    pub(crate) fn composite_cells(&self, idx: usize) -> Option<Vec<(usize, usize)>> {
+        let pixel = self.pixels[idx].pixel();
+        let (width, height) = pixel.footprint()?;
+        let (x, y) = self.index_to_coordinates(idx);
+        let part = pixel.part() as usize;
+        let left = x.checked_sub(part % width)?;
+        let top = y.checked_sub(part / width)?;
+        let kind = PixelKind::from(&pixel);
+        let cells = composite::footprint_cells(left, top, (width, height)).collect::<Vec<_>>();
+        cells
+            .iter()
+            .enumerate()
+            .all(|(part, &(x, y))| {
+                self.is_coordinate_in_bound(x, y) && {
+                    let cell = self.pixels[self.coordinates_to_index(x, y)].pixel();
+                    PixelKind::from(&cell) == kind && cell.part() as usize == part
                 }
+            })
+            .then_some(cells)
+    }
 
-                self.pixels.swap(idx, new_index);
+    /// Moves the `width` by `height` group of cells with its top left cell at (`x`, `y`) by a
+    /// cell in `dir`, the cells it moves over taking the place of the ones it leaves.
+    ///
+    /// The cells in front of the group have to be in bounds.
+    fn shift_group(&mut self, x: usize, y: usize, (width, height): (usize, usize), dir: Direction) {
+        // every line of cells along the move, from the back of the group to the cell in front
+        let lines = match dir {
+            Direction::Down => (x..x + width)
+                .map(|x| (y..=y + height).map(|y| (x, y)).collect::<Vec<_>>())
+                .collect::<Vec<_>>(),
+            Direction::Up => (x..x + width)
+                .map(|x| (y - 1..y + height).rev().map(|y| (x, y)).collect())
+                .collect(),
+            Direction::Right => (y..y + height)
+                .map(|y| (x..=x + width).map(|x| (x, y)).collect())
+                .collect(),
+            Direction::Left => (y..y + height)
+                .map(|y| (x - 1..x + width).rev().map(|x| (x, y)).collect())
+                .collect(),
+            _ => return,
+        };
+        for line in lines {
+            // the cell in front bubbles to the back as the group moves forward
+            for pair in line.windows(2).rev() {
+                let (back, front) = (
+                    self.coordinates_to_index(pair[0].0, pair[0].1),
+                    self.coordinates_to_index(pair[1].0, pair[1].1),
+                );
+                self.pixels.swap(back, front);
+            }
+            for (x, y) in line {
+                let idx = self.coordinates_to_index(x, y);
+                if self.pixels[idx].pixel().pixel_type() != PixelType::Void {
+                    self.pixels[idx].mark_is_moved(true);
+                }
             }
         }
+    }
 
-        for idx in (0..self.pixels.len() - 1).rev() {
-            let (x, y) = self.index_to_coordinates(idx);
+    #[cfg(not(feature = "debug"))]
+    fn new_container(&mut self, pixel: Pixel) -> PixelContainer {
+        PixelContainer::new(pixel)
+    }
+
+    /// Wraps a placed pixel, handing out the next id unless it is empty.
+    #[cfg(feature = "debug")]
+    fn new_container(&mut self, pixel: Pixel) -> PixelContainer {
+        let mut container = PixelContainer::new(pixel);
+        if pixel.pixel_type() != PixelType::Void {
+            container.id = self.next_id;
+            self.next_id += 1;
+        }
+        container
+    }
 
-            let neighbour = [
-                self.get_neighbour_pixel(x, y, Direction::Up)
-                    .map(|(_, _, c)| c.pixel()),
-                self.get_neighbour_pixel(x, y, Direction::Down)
-                    .map(|(_, _, c)| c.pixel()),
-                self.get_neighbour_pixel(x, y, Direction::Left)
-                    .map(|(_, _, c)| c.pixel()),
-                self.get_neighbour_pixel(x, y, Direction::Right)
-                    .map(|(_, _, c)| c.pixel()),
-            ];
+    /// Coordinates of the pixel placed with `id`, if it is still around.
+    #[cfg(feature = "debug")]
+    pub fn find_pixel(&self, id: u32) -> Option<(usize, usize)> {
+        self.pixels
+            .iter()
+            .position(|p| id != 0 && p.id == id)
+            .map(|idx| self.index_to_coordinates(idx))
+    }
 
-            let pixel = self.pixels.get_mut(idx).unwrap();
-            neighbour.into_iter().for_each(|t| {
-                if let Some(target) = t {
-                    pixel.pixel_mut().interact(target);
+    /// Keeps the history of the pixel placed with `id` from now on, for the last
+    /// [`Self::HISTORY`] ticks it is around. `None` stops tracking.
+    #[cfg(feature = "debug")]
+    pub fn track(&mut self, id: Option<u32>) {
+        if self.tracked != id {
+            self.tracked = id;
+            self.history.clear();
+        }
+    }
+
+    #[cfg(feature = "debug")]
+    pub fn tracked(&self) -> Option<u32> {
+        self.tracked
+    }
+
+    /// Samples of the tracked pixel, oldest first.
+    #[cfg(feature = "debug")]
+    pub fn history(&self) -> &VecDeque<PixelSample> {
+        &self.history
+    }
+
+    #[cfg(feature = "debug")]
+    fn record_history(&mut self) {
+        let Some(id) = self.tracked else {
+            return;
+        };
+        let Some(container) = self.pixels.iter().find(|p| id != 0 && p.id == id) else {
+            return;
+        };
+        if self.history.len() == Self::HISTORY {
+            self.history.pop_front();
+        }
+        self.history.push_back(PixelSample {
+            tick: self.ticks,
+            temperature: container.temp,
+            velocity: container.velocity,
+            fall: container.fall,
+        });
+    }
+
+    /// Spawns an entity of `kind` with its top left cell at (`x`, `y`), returning its id.
+    ///
+    /// Nothing is spawned if the entity doesn't fit there.
+    pub fn spawn_entity(&mut self, kind: EntityKind, x: usize, y: usize) -> Option<u32> {
+        let entity = Entity::new(self.next_entity_id, kind, x, y);
+        let fits = entity.cells().all(|(x, y)| {
+            self.is_coordinate_in_bound(x, y)
+                && !matches!(
+                    self.pixels[self.coordinates_to_index(x, y)]
+                        .pixel()
+                        .pixel_type(),
+                    PixelType::Solid(_) | PixelType::Wall
+                )
+        });
+        if !fits {
+            return None;
+        }
+        self.next_entity_id += 1;
+        self.entities.push(entity);
+        Some(entity.id)
+    }
+
+    pub fn entities(&self) -> &[Entity] {
+        &self.entities
+    }
+
+    /// Replaces the entities wholesale, for mirrors and restored snapshots.
+    pub(crate) fn set_entities(&mut self, entities: Vec<Entity>) {
+        let last_id = entities.iter().map(|e| e.id).max().unwrap_or(0);
+        self.next_entity_id = self.next_entity_id.max(last_id + 1);
+        self.entities = entities;
+    }
+
+    pub fn entity(&self, id: u32) -> Option<&Entity> {
+        self.entities.iter().find(|entity| entity.id == id)
+    }
+
+    pub fn entity_mut(&mut self, id: u32) -> Option<&mut Entity> {
+        self.entities.iter_mut().find(|entity| entity.id == id)
+    }
+
+    /// Entities covering the cell at (`x`, `y`).
+    pub fn entities_at(&self, x: usize, y: usize) -> impl Iterator<Item = &Entity> {
+        self.entities
+            .iter()
+            .filter(move |entity| entity.contains(x, y))
+    }
+
+    /// Walks the player `id` a cell left or right, climbing a cell high steps, makes it jump
+    /// or swim up, or dig down. Walking into powder digs through it.
+    pub fn steer_entity(&mut self, id: u32, direction: Direction) {
+        let Some(idx) = self
+            .entities
+            .iter()
+            .position(|entity| entity.id == id && entity.kind == EntityKind::Player)
+        else {
+            return;
+        };
+        let mut entity = self.entities[idx];
+        let (width, height) = entity.footprint();
+        let (x, y) = (entity.x, entity.y);
+        match direction {
+            Direction::Up => entity.jump(self),
+            Direction::Down => self.dig((x..x + width).map(|x| (x, y + height))),
+            Direction::Left | Direction::Right => {
+                let (column, dx) = match direction {
+                    Direction::Left => (x.checked_sub(1), -1),
+                    _ => (Some(x + width), 1),
+                };
+                if let Some(column) = column {
+                    self.dig((y..y + height).map(|y| (column, y)));
                 }
-            });
+                let x = x as isize + dx;
+                if entity.fits(self, x, y as isize) {
+                    entity.x = x as usize;
+                } else if entity.fits(self, x, y as isize - 1) {
+                    (entity.x, entity.y) = (x as usize, y - 1);
+                }
+            }
+            _ => {}
+        }
+        self.entities[idx] = entity;
+    }
 
-            if let Some(new_pixel) = PixelFundamental::update(pixel.pixel_mut()) {
-                pixel.pixel = new_pixel;
+    /// Clears the powders out of `cells`.
+    fn dig(&mut self, cells: impl Iterator<Item = (usize, usize)>) {
+        for (x, y) in cells {
+            if !self.is_coordinate_in_bound(x, y) || self.is_frozen(x, y) {
+                continue;
+            }
+            let idx = self.coordinates_to_index(x, y);
+            if catalog::element(&self.pixels[idx].pixel()).category == Category::Powder {
+                self.pixels[idx] = PixelContainer::default();
             }
         }
+    }
 
-        self.pixels.iter_mut().for_each(|p| p.mark_is_moved(false));
+    pub fn remove_entity(&mut self, id: u32) -> Option<Entity> {
+        let idx = self.entities.iter().position(|entity| entity.id == id)?;
+        Some(self.entities.remove(idx))
     }
 
-    pub fn resize(&mut self, width: usize, height: usize) {
-        let width_delta = width as isize - self.width as isize;
-        let height_delta = height as isize - self.height as isize;
+    /// Queues a command to be applied at the start of the next [`Sandbox::tick`].
+    pub fn queue_command(&mut self, command: Command) {
+        self.commands.push(command);
+    }
 
-        let mut new_sandbox = Sandbox::<SmallRng>::new(width, height);
-        self.pixels.iter().enumerate().for_each(|(idx, p)| {
-            let (x, y) = self.index_to_coordinates(idx);
-            let new_x = x as isize + width_delta / 2;
-            let new_y = y as isize + height_delta / 2;
-            if new_sandbox.is_coordinate_in_bound(new_x as usize, new_y as usize) {
-                new_sandbox.place_pixel_force(p.pixel, new_x as usize, new_y as usize);
-            }
-        });
+    /// Whether commands are waiting for the next tick.
+    pub fn has_queued_commands(&self) -> bool {
+        !self.commands.is_empty()
+    }
 
-        self.width = new_sandbox.width;
-        self.height = new_sandbox.height;
-        self.pixels = new_sandbox.pixels;
+    /// Records the earlier contents of the cells the placement commands change from now on,
+    /// until [`Sandbox::take_patch`]. Recording goes on if it already was.
+    pub fn record_patch(&mut self) {
+        let (width, height) = (self.width, self.height);
+        self.patch.get_or_insert_with(|| Patch::new(width, height));
     }
-}
 
-#[cfg(test)]
-mod test {
-    use rand::rngs::mock::StepRng;
+    pub fn is_recording_patch(&self) -> bool {
+        self.patch.is_some()
+    }
 
-    use crate::pixel::sand::Sand;
-    use crate::pixel::water::Water;
-    use crate::sandbox::Sandbox;
+    /// Stops recording, returning the patch undoing the placements since
+    /// [`Sandbox::record_patch`] if they changed anything.
+    pub fn take_patch(&mut self) -> Option<Patch> {
+        self.patch.take().filter(|patch| !patch.is_empty())
+    }
 
-    fn new_rng() -> StepRng {
-        StepRng::new(42, 1)
+    /// Puts back the cells of `patch` still holding what the placements left there, returning
+    /// the patch redoing what it undid. Patches of a sandbox of another size don't apply.
+    ///
+    /// The cells change outside of any command, so the run can't be recorded as a fixture
+    /// afterwards.
+    pub fn apply_patch(&mut self, patch: &Patch) -> Option<Patch> {
+        if patch.size != (self.width, self.height) {
+            return None;
+        }
+        self.input_log = None;
+        let mut inverse = Patch::new(self.width, self.height);
+        for (&idx, container) in &patch.cells {
+            if patch.placed.get(&idx) != Some(&(&self.pixels[idx].pixel()).into()) {
+                continue;
+            }
+            let replaced = std::mem::replace(&mut self.pixels[idx], container.clone());
+            inverse.record(idx, replaced, (&container.pixel()).into());
+        }
+        Some(inverse)
     }
 
-    #[test]
-    fn test_sandbox_creation() {
-        // create a sandbox
-        let sandbox = Sandbox::new_with_rng(3, 3, new_rng());
-        assert_eq!(sandbox.pixels.len(), 9);
+    /// Events emitted during the last tick or [`Sandbox::apply_commands`].
+    pub fn events(&self) -> &[SandboxEvent] {
+        &self.events
     }
-    #[test]
-    fn test_sandbox_tick() {
-        // create a sandbox
-        let mut sandbox = Sandbox::new_with_rng(3, 3, new_rng());
-        sandbox.place_pixel_force(Sand.into(), 1, 0);
-        sandbox.tick();
-        let new_cord = sandbox.coordinates_to_index(1, 1);
-        assert_eq!(sandbox.pixels[new_cord].pixel, Sand.into());
-        sandbox.tick();
-        let new_cord = sandbox.coordinates_to_index(1, 2);
-        assert_eq!(sandbox.pixels[new_cord].pixel, Sand.into());
+
+    /// Records why the pixels moving out of the cells in `region` moved from now on, to show how
+    /// the automaton works. `None` stops recording.
+    pub fn annotate(&mut self, region: Option<Region>) {
+        if self.annotated != region {
+            self.annotated = region;
+            self.annotations.clear();
+        }
     }
-    #[test]
-    fn test_sandbox_tick2() {
-        // create a sandbox
-        let mut sandbox = Sandbox::new_with_rng(3, 3, new_rng());
-        sandbox.place_pixel_force(Sand.into(), 1, 0);
-        sandbox.place_pixel_force(Water::default().into(), 1, 1);
-        sandbox.tick();
-        let sand_new_cord = sandbox.coordinates_to_index(1, 1);
-        let water_new_cord = sandbox.coordinates_to_index(1, 2);
-        assert_eq!(
-            sandbox.pixels[sand_new_cord].pixel,
-            Sand.into(),
-            "{:?}",
-            &sandbox.pixels
-        );
-        assert_eq!(
-            sandbox.pixels[water_new_cord].pixel,
-            Water::default().into(),
-            "{:?}",
-            &sandbox.pixels
-        );
-        sandbox.tick();
-        let sand_new_cord = sandbox.coordinates_to_index(1, 2);
-        let water_new_cord = sandbox.coordinates_to_index(0, 2);
-        assert_eq!(
-            sandbox.pixels[sand_new_cord].pixel,
-            Sand.into(),
-            "{:?}",
-            &sandbox.pixels
-        );
-        assert_eq!(
-            sandbox.pixels[water_new_cord].pixel,
-            Water::default().into(),
-            "{:?}",
-            &sandbox.pixels
-        );
+
+    /// Moves out of the annotated region during the last tick, in the order they happened.
+    pub fn annotations(&self) -> &[Annotation] {
+        &self.annotations
     }
 
-    #[test]
-    fn test_sandbox_tick3() {
-        // create a sandbox
-        let mut sandbox = Sandbox::new_with_rng(3, 4, new_rng());
-        sandbox.place_pixel_force(Sand.into(), 1, 1);
-        sandbox.place_pixel_force(Sand.into(), 1, 2);
-        sandbox.place_pixel_force(Water::default().into(), 0, 3);
-        sandbox.place_pixel_force(Water::default().into(), 1, 3);
-        sandbox.place_pixel_force(Water::default().into(), 2, 3);
-        sandbox.tick();
-        let sand1_new_cord = sandbox.coordinates_to_index(0, 2);
-        let sand2_new_cord = sandbox.coordinates_to_index(1, 3);
-        let water1_new_cord = sandbox.coordinates_to_index(0, 3);
-        let water2_new_cord = sandbox.coordinates_to_index(1, 2);
+    fn annotate_move(&mut self, from: usize, to: usize, rule: MoveRule) {
+        let from = self.index_to_coordinates(from);
+        if self
+            .annotated
+            .is_some_and(|region| region.contains(from.0, from.1))
+        {
+            let to = self.index_to_coordinates(to);
+            self.annotations.push(Annotation { from, to, rule });
+        }
+    }
+
+    /// Pixels removed by [`Command::Vacuum`] since the last call, over however many ticks ran.
+    pub fn take_vacuumed(&mut self) -> Vec<Pixel> {
+        std::mem::take(&mut self.vacuumed)
+    }
+
+    /// Lets the pixel at `idx` interact with `targets` and react to `neighbours`, then updates
+    /// it in place.
+    fn interact_pixel(
+        &mut self,
+        idx: usize,
+        targets: impl IntoIterator<Item = Pixel>,
+        neighbours: Option<&Neighbours>,
+    ) {
+        let (x, y) = self.index_to_coordinates(idx);
+        let pixel = self.pixels.get_mut(idx).unwrap();
+        let before = pixel.pixel();
+
+        targets
+            .into_iter()
+            .for_each(|target| pixel.pixel_mut().interact(target));
+        if let Some(neighbours) = neighbours {
+            pixel.pixel_mut().react(neighbours);
+            let drained = neighbours.iter().any(|p| matches!(p, Pixel::Drain(_)));
+            if drained && pixel.pixel().pixel_type().is_movable() {
+                pixel.pixel = Pixel::default();
+            }
+        }
+
+        if let Some(new_pixel) = PixelFundamental::update(&mut pixel.pixel, &mut pixel.temp) {
+            pixel.pixel = new_pixel;
+        }
+
+        if let Some(event) = SandboxEvent::from_transition(before, pixel.pixel(), x, y) {
+            self.events.push(event);
+        }
+    }
+
+    /// Turns the pixels in `cells` matching `predicate` into `pixel`, keeping the state of their
+    /// container like velocity and fall. Returns how many were replaced.
+    pub fn replace_where(
+        &mut self,
+        cells: impl IntoIterator<Item = (usize, usize)>,
+        predicate: impl Fn(&Pixel) -> bool,
+        pixel: Pixel,
+    ) -> usize {
+        let mut replaced = 0;
+        for (x, y) in cells {
+            if !self.is_coordinate_in_bound(x, y) {
+                continue;
+            }
+            let container = &mut self.pixels[x + y * self.width];
+            if predicate(&container.pixel) {
+                container.pixel = pixel;
+                replaced += 1;
+            }
+        }
+        replaced
+    }
+
+    /// Number of cells `command` would fill if it were applied now.
+    pub fn placement_count(&self, command: &Command) -> usize {
+        match command.placement() {
+            Some((pixel, force)) if pixel.footprint().is_some() => {
+                let (x, y) = command.cells().next().unwrap();
+                match self.check_composite(pixel, x, y, force) {
+                    Ok(()) => command.cells().count(),
+                    Err(_) => 0,
+                }
+            }
+            Some((_, force)) => command
+                .cells()
+                .filter(|&(x, y)| self.is_coordinate_in_bound(x, y))
+                .filter(|&(x, y)| {
+                    force
+                        || self.pixels[self.coordinates_to_index(x, y)]
+                            .pixel()
+                            .pixel_type()
+                            == PixelType::Void
+                })
+                .count(),
+            None => 0,
+        }
+    }
+
+    /// Counts the pixels of every element currently in the sandbox.
+    pub fn census(&self) -> BTreeMap<&'static str, usize> {
+        let mut census = BTreeMap::new();
+        for p in &self.pixels {
+            *census.entry(p.pixel().name()).or_insert(0) += 1;
+        }
+        census
+    }
+
+    /// Applies queued commands without advancing the simulation, e.g. while paused.
+    pub fn apply_commands(&mut self) {
+        self.events.clear();
+        self.annotations.clear();
+        for command in std::mem::take(&mut self.commands) {
+            if let Some(log) = self.input_log.as_mut() {
+                log.push((self.ticks, command));
+                if log.len() > Self::INPUT_LOG_LIMIT {
+                    self.input_log = None;
+                }
+            }
+            match command {
+                Command::PlaceBrush { .. }
+                | Command::PlaceSpawner { .. }
+                | Command::PlaceSpout { .. } => {
+                    let (pixel, force) = command.placement().unwrap();
+                    let spawns_disallowed = matches!(command, Command::PlaceSpawner { element, .. } | Command::PlaceSpout { element, .. } if !self.is_element_allowed(&element));
+                    if !self.is_element_allowed(&pixel) || spawns_disallowed {
+                        continue;
+                    }
+                    // what the cells held, to tell which ones the placement changed
+                    let before = match self.patch.is_some() {
+                        true => command
+                            .cells()
+                            .filter(|&(x, y)| self.is_coordinate_in_bound(x, y))
+                            .map(|(x, y)| {
+                                let idx = self.coordinates_to_index(x, y);
+                                (idx, self.pixels[idx].clone())
+                            })
+                            .collect(),
+                        false => Vec::new(),
+                    };
+                    if pixel.footprint().is_some() {
+                        let (x, y) = command.cells().next().unwrap();
+                        let _ = self.place_composite(pixel, x, y, force);
+                    } else {
+                        for (x, y) in command.cells() {
+                            if !self.is_coordinate_in_bound(x, y) {
+                                continue;
+                            }
+                            match force {
+                                true => self.place_pixel_force(pixel, x, y),
+                                false => self.place_pixel(pixel, x, y),
+                            }
+                        }
+                    }
+                    if let Some(patch) = self.patch.as_mut() {
+                        for (idx, container) in before {
+                            let placed = self.pixels[idx].pixel();
+                            if placed != container.pixel() {
+                                patch.record(idx, container, (&placed).into());
+                            }
+                        }
+                    }
+                }
+                Command::TriggerEvent { source, x, y } => {
+                    if !self.is_coordinate_in_bound(x, y) {
+                        continue;
+                    }
+                    self.interact_pixel(self.coordinates_to_index(x, y), [source], None);
+                }
+                Command::Flick { dx, dy, .. } => {
+                    for (x, y) in command.cells() {
+                        if !self.is_coordinate_in_bound(x, y) {
+                            continue;
+                        }
+                        let idx = self.coordinates_to_index(x, y);
+                        let pixel = &mut self.pixels[idx];
+                        if pixel.pixel().pixel_type().is_movable() {
+                            pixel.velocity = (dx, dy);
+                        }
+                    }
+                }
+                Command::Vacuum { .. } => {
+                    for (x, y) in command.cells() {
+                        if !self.is_coordinate_in_bound(x, y) {
+                            continue;
+                        }
+                        let pixel = self.pixels[self.coordinates_to_index(x, y)].pixel();
+                        if pixel.pixel_type().is_movable() {
+                            self.vacuumed.push(pixel);
+                            self.place_pixel_force(Pixel::default(), x, y);
+                        }
+                    }
+                }
+                Command::Erase {
+                    x,
+                    y,
+                    width,
+                    height,
+                } => self.erase_area(x, y, width, height),
+                Command::Activate { .. } => {
+                    for (x, y) in command.cells() {
+                        if !self.is_coordinate_in_bound(x, y) {
+                            continue;
+                        }
+                        let idx = self.coordinates_to_index(x, y);
+                        self.pixels[idx].pixel_mut().activate();
+                    }
+                }
+                Command::Transmute { from, to, .. } => {
+                    if !self.is_element_allowed(&to) {
+                        continue;
+                    }
+                    self.replace_where(command.cells(), |p| p.name() == from.name(), to);
+                }
+                Command::SpawnEntity { entity, x, y } => {
+                    self.spawn_entity(entity, x, y);
+                }
+                Command::RemoveEntity { entity } => {
+                    self.remove_entity(entity);
+                }
+                Command::Steer { entity, direction } => self.steer_entity(entity, direction),
+                Command::Focus {
+                    x,
+                    y,
+                    width,
+                    height,
+                } => {
+                    self.focus = Some(Region {
+                        x,
+                        y,
+                        width,
+                        height,
+                    });
+                }
+                Command::Freeze { frozen, .. } => {
+                    if self.frozen.is_empty() {
+                        self.frozen = vec![false; self.pixels.len()];
+                    }
+                    for (x, y) in command.cells() {
+                        if !self.is_coordinate_in_bound(x, y) {
+                            continue;
+                        }
+                        let idx = self.coordinates_to_index(x, y);
+                        self.frozen[idx] = frozen;
+                    }
+                }
+                Command::Resize { width, height } => self.resize(width, height),
+            }
+        }
+    }
+
+    /// Moves the pixel at `idx` along its velocity, then slows it down.
+    ///
+    /// Thrown pixels travel through empty cells, slowed down by the air, and solids through
+    /// liquids which drag them down by their viscosity. Hitting anything else or the border
+    /// stops them dead.
+    fn tick_velocity(&mut self, idx: usize) {
+        let (x, y) = self.index_to_coordinates(idx);
+        let (dx, dy) = self.pixels[idx].velocity;
+        let steps = dx.unsigned_abs().max(dy.unsigned_abs()) as isize;
+
+        let mut current = idx;
+        let mut blocked = false;
+        let mut drag = None;
+        for step in 1..=steps {
+            let next_x = x as isize + dx as isize * step / steps;
+            let next_y = y as isize + dy as isize * step / steps;
+            if next_x < 0
+                || next_y < 0
+                || !self.is_coordinate_in_bound(next_x as usize, next_y as usize)
+            {
+                blocked = true;
+                break;
+            }
+            let next = self.coordinates_to_index(next_x as usize, next_y as usize);
+            if next == current {
+                continue;
+            }
+            if self.is_frozen_at(next) || self.is_corner_sealed(current, next) {
+                blocked = true;
+                break;
+            }
+            match self.pixels[next].pixel().pixel_type() {
+                PixelType::Void => {}
+                PixelType::Liquid(_)
+                    if matches!(
+                        self.pixels[current].pixel().pixel_type(),
+                        PixelType::Solid(_)
+                    ) =>
+                {
+                    // the rest of the way is spent pushing the liquid aside
+                    let (next_x, next_y) = (next_x as usize, next_y as usize);
+                    if !self.is_submerged(current) {
+                        let moving = self.pixels[current].pixel();
+                        self.events.extend(SandboxEvent::from_swap(
+                            moving,
+                            self.pixels[next].pixel(),
+                            next_x,
+                            next_y,
+                        ));
+                    }
+                    drag = Some(self.drag(self.pixels[current].pixel(), next_x, next_y));
+                    self.pixels[next].mark_is_moved(true);
+                    self.pixels.swap(current, next);
+                    current = next;
+                    break;
+                }
+                _ => {
+                    blocked = true;
+                    break;
+                }
+            }
+            self.pixels.swap(current, next);
+            current = next;
+        }
+
+        if current != idx {
+            self.annotate_move(idx, current, MoveRule::Thrown);
+        }
+        let velocity = match (blocked, drag) {
+            (true, _) => self.rebound(current, (dx, dy)),
+            (false, Some(drag)) => (damp(dx - dx.signum(), drag), damp(dy - dy.signum(), drag)),
+            (false, None) => {
+                let air = self.air_drag(self.pixels[current].pixel());
+                (
+                    self.resist(dx - dx.signum(), air),
+                    self.resist(dy - dy.signum(), air),
+                )
+            }
+        };
+        let pixel = &mut self.pixels[current];
+        pixel.mark_is_moved(true);
+        pixel.velocity = velocity;
+        if blocked {
+            self.collide(current, steps as u8);
+        }
+    }
+
+    /// Velocity the pixel at `idx` bounces off with after being stopped while travelling at
+    /// (`dx`, `dy`): turned back along the axes it hit something on, and slowed down by its
+    /// restitution.
+    fn rebound(&self, idx: usize, (dx, dy): (i8, i8)) -> (i8, i8) {
+        let restitution = self.pixels[idx].pixel().restitution() as i16;
+        if restitution == 0 {
+            return (0, 0);
+        }
+        let (x, y) = self.index_to_coordinates(idx);
+        let is_open = |dx: i8, dy: i8| {
+            let (x, y) = (
+                x as isize + dx.signum() as isize,
+                y as isize + dy.signum() as isize,
+            );
+            x >= 0
+                && y >= 0
+                && self.is_coordinate_in_bound(x as usize, y as usize)
+                && !self.is_frozen(x as usize, y as usize)
+                && self.pixels[self.coordinates_to_index(x as usize, y as usize)]
+                    .pixel()
+                    .pixel_type()
+                    == PixelType::Void
+        };
+        let mut hit = (dx != 0 && !is_open(dx, 0), dy != 0 && !is_open(0, dy));
+        if hit == (false, false) {
+            // straight into a corner
+            hit = (dx != 0, dy != 0);
+        }
+        let bounce = |speed: i8, hit: bool| {
+            // widened first, turning back -128 doesn't fit an i8
+            let speed = speed as i16;
+            let speed = if hit { -speed } else { speed };
+            (speed * restitution / 100).clamp(i8::MIN as i16, i8::MAX as i16) as i8
+        };
+        (bounce(dx, hit.0), bounce(dy, hit.1))
+    }
+
+    /// Lets the pixel at `idx` land after falling for `fall` ticks, an elastic one springs back
+    /// up to its restitution of the height it fell from.
+    fn land(&mut self, idx: usize, fall: u8) {
+        self.collide(idx, fall);
+        let height = fall as u16 * self.pixels[idx].pixel().restitution() as u16 / 100;
+        // rising at `speed` cells per tick, slowing down by one every tick, covers about
+        // speed² / 2 cells
+        let speed = ((2 * height) as f32).sqrt() as i8;
+        if speed > 0 {
+            self.pixels[idx].velocity = (0, -speed);
+        }
+    }
+
+    /// Whether the pixel at `idx` is under a liquid, rather than hitting its surface when moving
+    /// into it.
+    fn is_submerged(&self, idx: usize) -> bool {
+        idx.checked_sub(self.width).is_some_and(|above| {
+            matches!(
+                self.pixels[above].pixel().pixel_type(),
+                PixelType::Liquid(_)
+            )
+        })
+    }
+
+    /// Whether the pixel at `from` is kept from stepping diagonally to `to` by the two cells
+    /// beside the step, so fast pixels can't slip through a diagonal wall one cell thick.
+    fn is_corner_sealed(&self, from: usize, to: usize) -> bool {
+        let (from_x, from_y) = self.index_to_coordinates(from);
+        let (to_x, to_y) = self.index_to_coordinates(to);
+        if from_x == to_x || from_y == to_y {
+            return false;
+        }
+        let solid = matches!(self.pixels[from].pixel().pixel_type(), PixelType::Solid(_));
+        [(to_x, from_y), (from_x, to_y)].into_iter().all(|(x, y)| {
+            let idx = self.coordinates_to_index(x, y);
+            self.is_frozen_at(idx)
+                || match self.pixels[idx].pixel().pixel_type() {
+                    PixelType::Void => false,
+                    // a solid pushes liquids aside
+                    PixelType::Liquid(_) => !solid,
+                    _ => true,
+                }
+        })
+    }
+
+    /// Lets the pixel at `idx` react to hitting something at `speed` cells per tick.
+    fn collide(&mut self, idx: usize, speed: u8) {
+        let (x, y) = self.index_to_coordinates(idx);
+        if let Some(event) = SandboxEvent::from_impact(speed, x, y) {
+            self.events.push(event);
+        }
+
+        let pixel = self.pixels[idx].pixel();
+        if let Some(new_pixel) = pixel.impact(speed, self.rng()) {
+            self.pixels[idx].pixel = new_pixel;
+        }
+    }
+
+    /// Gravity pulling on the cell at (`x`, `y`) during the movement pass.
+    pub fn gravity_at(&self, x: usize, y: usize) -> Gravity {
+        self.gravity
+            .get(self.coordinates_to_index(x, y))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Lays the levitation fields over the grid, inverting gravity wins where fields overlap.
+    fn update_gravity(&mut self) {
+        self.gravity.clear();
+        let radius = LevitationField::RADIUS as isize;
+        for idx in 0..self.pixels.len() {
+            let Pixel::LevitationField(field) = self.pixels[idx].pixel() else {
+                continue;
+            };
+            if self.gravity.is_empty() {
+                self.gravity.resize(self.pixels.len(), Gravity::Normal);
+            }
+            let (x, y) = self.index_to_coordinates(idx);
+            for dy in 1..=radius {
+                for dx in -radius..=radius {
+                    let (cell_x, cell_y) = (x as isize + dx, y as isize - dy);
+                    if dx * dx + dy * dy > radius * radius
+                        || cell_x < 0
+                        || cell_y < 0
+                        || !self.is_coordinate_in_bound(cell_x as usize, cell_y as usize)
+                    {
+                        continue;
+                    }
+                    let cell = self.coordinates_to_index(cell_x as usize, cell_y as usize);
+                    self.gravity[cell] = self.gravity[cell].max(field.gravity());
+                }
+            }
+        }
+    }
+
+    /// Runs exactly one tick, the queued commands first, and returns what happened during it.
+    ///
+    /// Needs nothing else to run around it, so a debugger or a paused frontend can step the
+    /// simulation one tick at a time and look at the grid in between.
+    pub fn tick_once(&mut self) -> &[SandboxEvent] {
+        self.tick();
+        self.events()
+    }
+
+    pub fn tick(&mut self) {
+        let start = Instant::now();
+        self.apply_commands();
+        self.update_gravity();
+        self.update_far_field();
+        let moving = Instant::now();
+        self.tick_movement();
+        let interacting = Instant::now();
+        self.tick_interactions();
+        self.tick_entities();
+        let ending = Instant::now();
+        self.end_tick();
+        let end = Instant::now();
+
+        self.timings = TickTimings {
+            movement: interacting - moving,
+            interaction: ending - interacting,
+            bookkeeping: (moving - start) + (end - ending),
+        };
+    }
+
+    /// Time spent in each phase of the last tick.
+    pub fn last_tick_timings(&self) -> TickTimings {
+        self.timings
+    }
+
+    /// Ticks like [`Sandbox::tick`], adding the time spent in each phase to `report`.
+    pub(crate) fn tick_timed(&mut self, report: &mut ScenarioReport) {
+        self.tick();
+        report.ticks += 1;
+        report.movement += self.timings.movement;
+        report.interaction += self.timings.interaction;
+        report.bookkeeping += self.timings.bookkeeping;
+    }
+
+    /// Plays `scenario` for `ticks` ticks from a fixed seed, timing every phase of the ticks.
+    ///
+    /// The run does the same work whatever the machine, so reports of different builds compare.
+    /// Pause steps are ignored and the sandbox keeps ticking once the scenario is over.
+    pub fn run_scenario(scenario: Scenario, ticks: u64) -> ScenarioReport {
+        const SEED: u64 = 0;
+
+        let mut sandbox = Sandbox::<SmallRng>::new_with_seed(scenario.width, scenario.height, SEED);
+        for placement in &scenario.scene {
+            for command in placement.commands() {
+                sandbox.queue_command(command);
+            }
+        }
+        let mut player = ScenarioPlayer::new(scenario);
+        let mut report = ScenarioReport::default();
+        for _ in 0..ticks {
+            player.tick_timed(&mut sandbox, &mut report);
+        }
+        report.census = sandbox.census();
+        report
+    }
+
+    /// Ticks the sandbox `ticks` times, timing every phase like [`Sandbox::run_scenario`].
+    pub fn run_timed(&mut self, ticks: u64) -> ScenarioReport {
+        let mut report = ScenarioReport::default();
+        for _ in 0..ticks {
+            self.tick_timed(&mut report);
+        }
+        report.census = self.census();
+        report
+    }
+
+    /// Ticks like [`Sandbox::tick`], reporting broken invariants as
+    /// [`EventKind::InvariantViolation`] events.
+    ///
+    /// Snapshots the grid around every phase, so it is much slower than a plain tick.
+    #[cfg(feature = "debug")]
+    pub fn tick_checked(&mut self) {
+        self.apply_commands();
+        self.update_gravity();
+        self.update_far_field();
+
+        for (idx, p) in self.pixels.iter().enumerate() {
+            if p.is_moved() {
+                let (x, y) = self.index_to_coordinates(idx);
+                self.events
+                    .push(Self::violation(Invariant::MovedLeak, x, y, 1));
+            }
+        }
+        let before = self.pixels.clone();
+        self.tick_movement();
+        self.check_movement(&before);
+
+        self.tick_interactions();
+        self.tick_entities();
+        self.end_tick();
+    }
+
+    #[cfg(feature = "debug")]
+    fn violation(invariant: Invariant, x: usize, y: usize, magnitude: u32) -> SandboxEvent {
+        SandboxEvent {
+            kind: EventKind::InvariantViolation(invariant),
+            x,
+            y,
+            magnitude,
+        }
+    }
+
+    /// Compares the grid against its state `before` the movement pass.
+    #[cfg(feature = "debug")]
+    fn check_movement(&mut self, before: &[PixelContainer]) {
+        // impacts change pixels in place, so only the count of pixels is conserved
+        let count = |pixels: &[PixelContainer]| {
+            pixels
+                .iter()
+                .filter(|p| p.pixel().pixel_type() != PixelType::Void)
+                .count()
+        };
+        let (before_count, after_count) = (count(before), count(&self.pixels));
+        if before_count != after_count {
+            self.events.push(Self::violation(
+                Invariant::Conservation,
+                0,
+                0,
+                before_count.abs_diff(after_count) as u32,
+            ));
+        }
+
+        // placed pixels can be told apart, each of them must still be around exactly once
+        let mut ids = before
+            .iter()
+            .map(|p| p.id())
+            .filter(|&id| id != 0)
+            .collect::<Vec<_>>();
+        let mut after_ids = self
+            .pixels
+            .iter()
+            .map(|p| p.id())
+            .filter(|&id| id != 0)
+            .collect::<Vec<_>>();
+        ids.sort_unstable();
+        after_ids.sort_unstable();
+        if ids != after_ids {
+            self.events.push(Self::violation(
+                Invariant::Conservation,
+                0,
+                0,
+                ids.len().abs_diff(after_ids.len()).max(1) as u32,
+            ));
+        }
+
+        for (idx, p) in before.iter().enumerate() {
+            let moved = p.pixel().pixel_type() == PixelType::Wall
+                && (self.pixels[idx].pixel() != p.pixel() || self.pixels[idx].id() != p.id());
+            if moved {
+                let (x, y) = self.index_to_coordinates(idx);
+                self.events
+                    .push(Self::violation(Invariant::WallMoved, x, y, 1));
+            }
+        }
+    }
+
+    /// Lets every pump push the liquid behind it through the pipes in front of it.
+    fn tick_pumps(&mut self) {
+        let pumps = self
+            .pixels
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, p)| match p.pixel() {
+                Pixel::Pump(pump) => Some((idx, pump)),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+
+        for (idx, pump) in pumps {
+            if self.is_frozen_at(idx) || self.is_dormant(idx) {
+                continue;
+            }
+            let (x, y) = self.index_to_coordinates(idx);
+            let Some((inlet_x, inlet_y)) = self.get_neighbour_coordinates(x, y, pump.inlet())
+            else {
+                continue;
+            };
+            let inlet = self.coordinates_to_index(inlet_x, inlet_y);
+            if !matches!(
+                self.pixels[inlet].pixel().pixel_type(),
+                PixelType::Liquid(_)
+            ) {
+                continue;
+            }
+            let outlet = self.trace_pipes(x, y, pump.outlet());
+            if let Some(outlet) =
+                outlet.filter(|&outlet| !self.is_frozen_at(inlet) && !self.is_frozen_at(outlet))
+            {
+                self.pixels.swap(inlet, outlet);
+                self.pixels[outlet].mark_is_moved(true);
+            }
+        }
+    }
+
+    /// Empty cell liquid pumped out of (`x`, `y`) towards `outlet` ends up in: the cell in front
+    /// of the pump, or the one next to the far end of the pipes connected there.
+    fn trace_pipes(&self, x: usize, y: usize, outlet: Direction) -> Option<usize> {
+        let (start_x, start_y) = self.get_neighbour_coordinates(x, y, outlet)?;
+        let start = self.coordinates_to_index(start_x, start_y);
+        match self.pixels[start].pixel() {
+            pixel if pixel.pixel_type() == PixelType::Void => return Some(start),
+            Pixel::Pipe(_) => {}
+            _ => return None,
+        }
+
+        let mut visited = HashSet::from([start]);
+        let mut queue = VecDeque::from([start]);
+        let mut exit = None;
+        while let Some(idx) = queue.pop_front() {
+            let (x, y) = self.index_to_coordinates(idx);
+            for dir in [
+                Direction::Up,
+                Direction::Down,
+                Direction::Left,
+                Direction::Right,
+            ] {
+                let Some((x, y)) = self.get_neighbour_coordinates(x, y, dir) else {
+                    continue;
+                };
+                let next = self.coordinates_to_index(x, y);
+                if !visited.insert(next) {
+                    continue;
+                }
+                match self.pixels[next].pixel() {
+                    Pixel::Pipe(_) => queue.push_back(next),
+                    // pipes are visited by distance, so the last exit found is the farthest
+                    pixel if pixel.pixel_type() == PixelType::Void => exit = Some(next),
+                    _ => {}
+                }
+            }
+        }
+        exit
+    }
+
+    /// Moves every pixel along its velocity or according to its type.
+    fn tick_movement(&mut self) {
+        self.tick_pumps();
+        self.move_pixels();
+        self.move_composites();
+    }
+
+    fn move_pixels(&mut self) {
+        if self.config.update_order == UpdateOrder::Fixed {
+            for idx in (0..self.pixels.len().saturating_sub(1)).rev() {
+                self.move_pixel(idx);
+            }
+            return;
+        }
+
+        let mut columns = (0..self.width).collect::<Vec<_>>();
+        for y in (0..self.height).rev() {
+            match self.config.update_order {
+                UpdateOrder::Alternating => {
+                    let ltr = (y as u64 + self.ticks).is_multiple_of(2);
+                    if ltr != (columns.first() < columns.last()) {
+                        columns.reverse();
+                    }
+                }
+                _ => {
+                    if let Some(rng) = self.keyed_rng.as_mut() {
+                        rng.focus_stream(self.ticks, 0, y, Self::ORDER_STREAM);
+                    }
+                    columns.shuffle(self.rng());
+                }
+            }
+            for &x in &columns {
+                self.move_pixel(self.coordinates_to_index(x, y));
+            }
+        }
+    }
+
+    /// Lets the whole solid composites fall by a cell where nothing holds them up, pushing the
+    /// lighter liquids and gases under them up into the cells they leave.
+    fn move_composites(&mut self) {
+        // bottom up, so a stack of composites falls together
+        for idx in (0..self.pixels.len()).rev() {
+            let pixel = self.pixels[idx].pixel();
+            let (Some(footprint), PixelType::Solid(density)) =
+                (pixel.footprint(), pixel.pixel_type())
+            else {
+                continue;
+            };
+            if pixel.part() != 0 || self.is_frozen_at(idx) || self.is_dormant(idx) {
+                continue;
+            }
+            let (x, y) = self.index_to_coordinates(idx);
+            let below = y + footprint.1;
+            if below >= self.height
+                || self.gravity_at(x, y) != Gravity::Normal
+                || self.composite_cells(idx).is_none()
+            {
+                continue;
+            }
+            let falls = (x..x + footprint.0).all(|x| {
+                let cell = self.coordinates_to_index(x, below);
+                !self.is_frozen_at(cell)
+                    && match self.pixels[cell].pixel().pixel_type() {
+                        PixelType::Void => true,
+                        PixelType::Liquid(other) | PixelType::Gas(other) => other < density,
+                        _ => false,
+                    }
+            });
+            if falls {
+                self.shift_group(x, y, footprint, Direction::Down);
+            }
+        }
+    }
+
+    fn move_pixel(&mut self, idx: usize) {
+        let pixel = self.pixels.get(idx).unwrap();
+        // composites only move as a whole
+        if pixel.pixel().pixel_type() == PixelType::Void || pixel.pixel().footprint().is_some() {
+            return;
+        }
+
+        if pixel.is_moved || self.is_frozen_at(idx) || self.is_dormant(idx) {
+            return;
+        }
+
+        self.focus_rng(idx, Self::MOVEMENT_STREAM);
+        let pixel = &self.pixels[idx];
+        if pixel.velocity != (0, 0) {
+            self.tick_velocity(idx);
+            return;
+        }
+
+        let (x, y) = self.index_to_coordinates(idx);
+
+        let moving_pixel = pixel.pixel();
+        let target = moving_pixel
+            .tick_move(x, y, self)
+            .filter(|&(new_x, new_y)| !self.is_frozen(new_x, new_y))
+            .filter(|&(new_x, new_y)| {
+                // thick liquids hold sinking pixels back
+                let drag = self.drag(moving_pixel, new_x, new_y);
+                drag == 0 || self.rng().gen_range(0..100) >= drag
+            });
+        if let Some((new_x, new_y)) = target {
+            let new_index = self.coordinates_to_index(new_x, new_y);
+            // only hitting the surface splashes, not every cell sunk through after it
+            let is_impact = new_y > y && !self.is_submerged(idx);
+
+            let pixel = self.pixels.get_mut(idx).unwrap();
+            pixel.mark_is_moved(true);
+            let swapping_pixel = self.pixels.get_mut(new_index).unwrap();
+            if swapping_pixel.pixel().pixel_type() != PixelType::Void {
+                swapping_pixel.mark_is_moved(true);
+            }
+
+            let is_splash = matches!(
+                (
+                    moving_pixel.pixel_type(),
+                    swapping_pixel.pixel().pixel_type()
+                ),
+                (PixelType::Solid(_), PixelType::Liquid(_))
+            );
+            let rule = match (swapping_pixel.pixel().pixel_type(), new_x == x) {
+                (PixelType::Void, true) => MoveRule::Gravity,
+                (PixelType::Void, false) => MoveRule::Dispersion,
+                _ => MoveRule::DensitySwap,
+            };
+            if let Some(event) =
+                SandboxEvent::from_swap(moving_pixel, swapping_pixel.pixel(), new_x, new_y)
+                    .filter(|_| is_impact)
+            {
+                self.events.push(event);
+            }
+
+            self.annotate_move(idx, new_index, rule);
+            self.pixels.swap(idx, new_index);
+
+            let drag = self.drag(moving_pixel, x, y);
+            let air = self.air_drag(moving_pixel);
+            let pixel = &mut self.pixels[new_index];
+            if self.config.splash && is_splash && pixel.fall >= Self::SPLASH_FALL {
+                // the liquid takes the impact instead of the ground
+                let fall = std::mem::take(&mut pixel.fall);
+                self.splash(idx, new_index, fall);
+                return;
+            }
+            if new_y > y {
+                pixel.fall = (pixel.fall as u16 * (100 - drag as u16) / 100) as u8;
+                // the same whole cells off as `resist`, up to a terminal fall of 100 / air
+                let fall = pixel.fall.saturating_add(1);
+                pixel.fall = fall - (fall as u16 * air.min(100) as u16 / 100) as u8;
+                return;
+            }
+            let fall = std::mem::take(&mut pixel.fall);
+            if fall > 0 {
+                self.land(new_index, fall);
+            }
+        } else {
+            let fall = std::mem::take(&mut self.pixels[idx].fall);
+            if fall > 0 {
+                self.land(idx, fall);
+            }
+        }
+    }
+
+    /// Throws the liquid at `displaced` up, after a pixel that fell for `fall` ticks took its
+    /// place at `impact`, along with the liquid on both sides of the impact.
+    fn splash(&mut self, displaced: usize, impact: usize, fall: u8) {
+        let strength = (fall / 2).min(Self::MAX_SPLASH) as i8;
+        self.pixels[displaced].velocity = (0, -strength);
+
+        let (x, y) = self.index_to_coordinates(impact);
+        for (dir, dx) in [(Direction::Left, -1), (Direction::Right, 1)] {
+            let Some((x, y)) = self.get_neighbour_coordinates(x, y, dir) else {
+                continue;
+            };
+            let idx = self.coordinates_to_index(x, y);
+            if matches!(self.pixels[idx].pixel.pixel_type(), PixelType::Liquid(_))
+                && !self.is_frozen_at(idx)
+            {
+                self.pixels[idx].velocity = (dx, 1 - strength);
+            }
+        }
+    }
+
+    /// Viscosity of the liquid at (`x`, `y`) slowing `pixel` down as it moves into it, or of
+    /// `pixel` itself if it is a gas.
+    fn drag(&self, pixel: Pixel, x: usize, y: usize) -> u8 {
+        let liquid = self.pixels[self.coordinates_to_index(x, y)].pixel();
+        match (pixel.pixel_type(), liquid.pixel_type()) {
+            (PixelType::Solid(_), PixelType::Liquid(_)) => liquid.viscosity().min(100),
+            (PixelType::Gas(_), _) => pixel.viscosity().min(100),
+            _ => 0,
+        }
+    }
+
+    /// Takes `drag` percent off `speed`, the fraction of a cell left over being taken off as
+    /// often as it amounts to, so slow pixels are held back too.
+    fn resist(&mut self, speed: i8, drag: u8) -> i8 {
+        let loss = speed.unsigned_abs() as u16 * drag.min(100) as u16;
+        let (mut cells, fraction) = (loss / 100, loss % 100);
+        if fraction > 0 && self.rng().gen_ratio(fraction as u32, 100) {
+            cells += 1;
+        }
+        speed - speed.signum() * cells as i8
+    }
+
+    /// Percent of its speed `pixel` loses to the air on every tick.
+    fn air_drag(&self, pixel: Pixel) -> u8 {
+        let AirDrag { solid, liquid, gas } = self.config.drag;
+        match pixel.pixel_type() {
+            PixelType::Solid(_) => solid,
+            PixelType::Liquid(_) => liquid,
+            PixelType::Gas(_) => gas,
+            PixelType::Wall | PixelType::Void => 0,
+        }
+    }
+
+    /// Whether a pixel of `kind` reacts to touching `target` this tick.
+    fn reacts(&mut self, kind: PixelKind, target: Pixel) -> bool {
+        let probability = self.reactions.probability(kind, (&target).into());
+        // certain reactions don't draw, so runs without tuned rates keep their random sequence
+        probability >= 1.0 || self.rng().gen::<f32>() < probability
+    }
+
+    /// Lets every pixel interact with its neighbours and update its state.
+    fn tick_interactions(&mut self) {
+        for idx in (0..self.pixels.len()).rev() {
+            if self.is_frozen_at(idx) || self.is_dormant(idx) {
+                continue;
+            }
+            self.focus_rng(idx, Self::INTERACTION_STREAM);
+            let (x, y) = self.index_to_coordinates(idx);
+
+            let neighbour = |dir| {
+                self.get_neighbour_pixel(x, y, dir)
+                    .map(|(_, _, c)| c.pixel())
+            };
+            let neighbours = Neighbours {
+                up: neighbour(Direction::Up),
+                down: neighbour(Direction::Down),
+                left: neighbour(Direction::Left),
+                right: neighbour(Direction::Right),
+            };
+
+            // turbines count the fluids that flowed past before deciding to spark on update
+            if let Pixel::Turbine(mut turbine) = self.pixels[idx].pixel() {
+                turbine.record_flow(self.flow_at(x, y));
+                self.pixels[idx].pixel = turbine.into();
+            }
+
+            let kind = PixelKind::from(&self.pixels[idx].pixel());
+            let targets = [
+                neighbours.up,
+                neighbours.down,
+                neighbours.left,
+                neighbours.right,
+            ]
+            .map(|target| target.filter(|&target| self.reacts(kind, target)));
+            self.interact_pixel(idx, targets.into_iter().flatten(), Some(&neighbours));
+        }
+        self.diffuse_heat();
+        self.relax_temperatures();
+        self.tick_lightning();
+        self.tick_explosions();
+        self.tick_composites();
+        self.tick_water_wheels();
+    }
+
+    /// Breaks the composites missing a cell into debris.
+    fn tick_composites(&mut self) {
+        for idx in 0..self.pixels.len() {
+            let pixel = self.pixels[idx].pixel();
+            if pixel.footprint().is_none() || self.is_frozen_at(idx) || self.is_dormant(idx) {
+                continue;
+            }
+            if self.composite_cells(idx).is_none() {
+                self.pixels[idx] = self.new_container(pixel.debris());
+            }
+        }
+    }
+
+    /// Turns the water wheels by the flow past all of their cells.
+    fn tick_water_wheels(&mut self) {
+        for idx in 0..self.pixels.len() {
+            let Pixel::WaterWheel(wheel) = self.pixels[idx].pixel() else {
+                continue;
+            };
+            if !wheel.is_hub() || self.is_frozen_at(idx) || self.is_dormant(idx) {
+                continue;
+            }
+            let Some(cells) = self.composite_cells(idx) else {
+                continue;
+            };
+            let (x, y) = self.index_to_coordinates(idx);
+
+            let flow = cells
+                .iter()
+                .map(|&(x, y)| self.flow_at(x, y) as u32)
+                .sum::<u32>();
+            let mut hub = wheel;
+            if hub.drive(flow.min(u8::MAX as u32) as u8) {
+                self.events.push(SandboxEvent {
+                    kind: EventKind::Torque,
+                    x,
+                    y,
+                    magnitude: flow,
+                });
+            }
+            for (part, (x, y)) in cells.into_iter().enumerate() {
+                let cell = self.coordinates_to_index(x, y);
+                self.pixels[cell].pixel = hub.with_part(part as u8).into();
+            }
+        }
+    }
+
+    /// Lets the charged clouds strike lightning now and then.
+    fn tick_lightning(&mut self) {
+        for idx in 0..self.pixels.len() {
+            let Pixel::Cloud(cloud) = self.pixels[idx].pixel() else {
+                continue;
+            };
+            if !cloud.is_charged() || self.is_frozen_at(idx) || self.is_dormant(idx) {
+                continue;
+            }
+            self.focus_rng(idx, Self::LIGHTNING_STREAM);
+            if self.rng().gen_range(0..Self::STRIKE_ODDS) == 0 {
+                self.strike(idx);
+            }
+        }
+    }
+
+    /// Discharges the cloud at `idx` into a bolt straight down to the first liquid, solid or
+    /// wall under it, setting the air on the way on fire.
+    ///
+    /// The pixel hit reacts as if it touched fire and a battery, so it burns, boils or sparks.
+    fn strike(&mut self, idx: usize) {
+        if let Pixel::Cloud(cloud) = &mut self.pixels[idx].pixel {
+            cloud.discharge();
+        }
+        let (x, top) = self.index_to_coordinates(idx);
+        let mut y = top + 1;
+        while y < self.height {
+            let cell = self.coordinates_to_index(x, y);
+            match self.pixels[cell].pixel().pixel_type() {
+                PixelType::Void if !self.is_frozen_at(cell) => {
+                    self.pixels[cell] = self.new_container(Fire::default().into());
+                }
+                PixelType::Void | PixelType::Gas(_) => {}
+                _ => {
+                    if !self.is_frozen_at(cell) {
+                        self.interact_pixel(cell, [Fire::default().into(), Battery.into()], None);
+                    }
+                    break;
+                }
+            }
+            y += 1;
+        }
+        self.events.push(SandboxEvent {
+            kind: EventKind::Thunder,
+            x,
+            y: top,
+            magnitude: (y - top) as u32,
+        });
+    }
+
+    /// Sets off the lit explosives.
+    fn tick_explosions(&mut self) {
+        for idx in 0..self.pixels.len() {
+            let Some(radius) = self.pixels[idx].pixel().blast_radius() else {
+                continue;
+            };
+            if self.is_frozen_at(idx) || self.is_dormant(idx) {
+                continue;
+            }
+            self.explode(idx, radius);
+        }
+    }
+
+    /// Blows up the explosive at `idx`, destroying the cells within `radius` of it, half of
+    /// them left on fire, and throwing the movable pixels out to twice the radius away from
+    /// it, the faster the closer they were.
+    ///
+    /// Explosives caught in the blast are lit instead, so they go off in turn.
+    fn explode(&mut self, idx: usize, radius: usize) {
+        self.focus_rng(idx, Self::EXPLOSION_STREAM);
+        let (cx, cy) = self.index_to_coordinates(idx);
+        for (x, y) in self.cells_within(cx, cy, radius * 2) {
+            let cell = self.coordinates_to_index(x, y);
+            if self.is_frozen_at(cell) || (cell != idx && self.pixels[cell].pixel.light()) {
+                continue;
+            }
+            let (dx, dy) = (x as f32 - cx as f32, y as f32 - cy as f32);
+            let distance = dx.hypot(dy);
+            if distance <= radius as f32 {
+                let debris = match self.rng().gen_bool(0.5) {
+                    true => Fire::default().into(),
+                    false => Pixel::default(),
+                };
+                self.pixels[cell] = self.new_container(debris);
+            } else if self.pixels[cell].pixel().pixel_type().is_movable() {
+                let speed = Self::BLAST_SPEED * (2.0 - distance / radius as f32);
+                self.pixels[cell].velocity = (
+                    (dx / distance * speed).round() as i8,
+                    (dy / distance * speed).round() as i8,
+                );
+            }
+        }
+        self.events.push(SandboxEvent {
+            kind: EventKind::Explosion,
+            x: cx,
+            y: cy,
+            magnitude: radius as u32,
+        });
+    }
+
+    /// Cells of the sandbox within `radius` of (`x`, `y`), the centre included, row by row.
+    pub fn cells_within(
+        &self,
+        x: usize,
+        y: usize,
+        radius: usize,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        let (width, height) = (self.width, self.height);
+        let r = radius as isize;
+        (-r..=r)
+            .flat_map(move |dy| (-r..=r).map(move |dx| (dx, dy)))
+            .filter(move |(dx, dy)| dx * dx + dy * dy <= r * r)
+            .filter_map(move |(dx, dy)| {
+                Some((x.checked_add_signed(dx)?, y.checked_add_signed(dy)?))
+            })
+            .filter(move |&(x, y)| x < width && y < height)
+    }
+
+    /// Liquid and gas pixels that moved into the cells next to (`x`, `y`) this tick.
+    fn flow_at(&self, x: usize, y: usize) -> u8 {
+        [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ]
+        .into_iter()
+        .filter_map(|dir| self.get_neighbour_pixel(x, y, dir))
+        .filter(|(_, _, container)| {
+            container.is_moved()
+                && matches!(
+                    container.pixel().pixel_type(),
+                    PixelType::Liquid(_) | PixelType::Gas(_)
+                )
+        })
+        .count() as u8
+    }
+
+    /// Lets heat flow between every two neighbouring cells, by the share the less conductive
+    /// of them lets through, see [`crate::pixel::Insulation::conduction`].
+    ///
+    /// Empty cells are the air, which stays at the ambient temperature. Like other gases it
+    /// passes little heat on, so solids and liquids mostly exchange heat where they touch.
+    /// Flames heat whatever they lick at, insulators included.
+    fn diffuse_heat(&mut self) {
+        let air = self
+            .config
+            .ambient
+            .map_or(temperature::ROOM, temperature::from_celsius);
+        for container in &mut self.pixels {
+            if let Pixel::Void(_) = container.pixel {
+                container.temp = air;
+            }
+        }
+
+        for idx in 0..self.pixels.len() {
+            if self.is_frozen_at(idx) || self.is_dormant(idx) {
+                continue;
+            }
+            let (x, y) = self.index_to_coordinates(idx);
+            for (x, y) in [(x + 1, y), (x, y + 1)] {
+                if !self.is_coordinate_in_bound(x, y) {
+                    continue;
+                }
+                let other = self.coordinates_to_index(x, y);
+                if !self.is_frozen_at(other) {
+                    self.exchange_heat(idx, other);
+                }
+            }
+        }
+    }
+
+    fn exchange_heat(&mut self, a: usize, b: usize) {
+        let conduction = |pixel: Pixel| match pixel.pixel_type() {
+            PixelType::Void | PixelType::Gas(_) => Self::GAS_CONDUCTION,
+            _ => pixel.insulates().conduction(),
+        };
+        let (pixel_a, pixel_b) = (self.pixels[a].pixel, self.pixels[b].pixel);
+        let mut share = conduction(pixel_a).min(conduction(pixel_b));
+        if pixel_a.is_hot() || pixel_b.is_hot() {
+            share = share.max(Self::FLAME_CONDUCTION);
+        }
+
+        let heat = (self.pixels[a].temp as i32 - self.pixels[b].temp as i32) * share as i32;
+        let mut flow = heat / 256;
+        // the remainder flows on a share of the ticks, so small differences even out too
+        if heat % 256 != 0 {
+            self.focus_rng(a, Self::HEAT_STREAM);
+            if self.rng().gen_range(0..256) < (heat % 256).abs() {
+                flow += heat.signum();
+            }
+        }
+        if flow == 0 {
+            return;
+        }
+        // the air takes and gives as much as it needs
+        for (idx, flow) in [(a, -flow), (b, flow)] {
+            let container = &mut self.pixels[idx];
+            if !matches!(container.pixel, Pixel::Void(_)) {
+                container.temp = (container.temp as i32 + flow).clamp(0, u8::MAX as i32) as u8;
+            }
+        }
+    }
+
+    /// Temperature of the cell at (`x`, `y`), see [`temperature`].
+    pub fn temperature_at(&self, x: usize, y: usize) -> u8 {
+        self.pixels[self.coordinates_to_index(x, y)].temp
+    }
+
+    /// Heats or cools the cell at (`x`, `y`) to `temp`, see [`temperature`].
+    pub fn set_temperature(&mut self, x: usize, y: usize, temp: u8) {
+        let idx = self.coordinates_to_index(x, y);
+        self.pixels[idx].temp = temp;
+    }
+
+    /// Brings the temperature of every cell closer to the ambient one, every
+    /// [`Self::AMBIENT_PERIOD`] ticks, by as many degrees as it is exposed to. Above room
+    /// temperature, wood and soil dry out as much.
+    fn relax_temperatures(&mut self) {
+        let Some(ambient) = self.config.ambient else {
+            return;
+        };
+        if !self.ticks.is_multiple_of(Self::AMBIENT_PERIOD) {
+            return;
+        }
+
+        let ambient = temperature::from_celsius(ambient);
+        for idx in 0..self.pixels.len() {
+            if self.is_frozen_at(idx) || self.is_dormant(idx) {
+                continue;
+            }
+            let exposure = self.exposure(idx);
+            let container = &mut self.pixels[idx];
+            container.temp = match container.temp.cmp(&ambient) {
+                Ordering::Less => container.temp.saturating_add(exposure).min(ambient),
+                Ordering::Equal => container.temp,
+                Ordering::Greater => container.temp.saturating_sub(exposure).max(ambient),
+            };
+            match &mut container.pixel {
+                // a warm climate dries porous pixels out
+                Pixel::Wood(wood) if ambient > temperature::ROOM => wood.dry(exposure),
+                Pixel::Soil(soil) if ambient > temperature::ROOM => soil.dry(exposure),
+                _ => {}
+            }
+        }
+    }
+
+    /// Degrees the pixel at `idx` exchanges with its surroundings per ambient period, through
+    /// the sides it doesn't share with the same element.
+    ///
+    /// The inside of a body only follows the ambient temperature as heat spreads from its
+    /// surface, which insulators hold back and conductors speed up.
+    fn exposure(&self, idx: usize) -> u8 {
+        let kind = PixelKind::from(&self.pixels[idx].pixel());
+        let (x, y) = self.index_to_coordinates(idx);
+        [
+            Direction::Up,
+            Direction::Down,
+            Direction::Left,
+            Direction::Right,
+        ]
+        .into_iter()
+        .filter_map(|dir| self.get_neighbour_pixel(x, y, dir))
+        .map(|(_, _, container)| container.pixel())
+        .filter(|pixel| PixelKind::from(pixel) != kind)
+        .map(|pixel| pixel.insulates().conductance())
+        .sum()
+    }
+
+    /// Moves every entity over the grid the pixels just settled in.
+    fn tick_entities(&mut self) {
+        let mut entities = std::mem::take(&mut self.entities);
+        for entity in &mut entities {
+            entity.tick(self);
+        }
+        entities.retain(Entity::is_alive);
+        self.entities = entities;
+    }
+
+    fn end_tick(&mut self) {
+        self.pixels.iter_mut().for_each(|p| p.mark_is_moved(false));
+        #[cfg(feature = "debug")]
+        self.record_history();
+        self.ticks += 1;
+    }
+
+    /// Stable hash of the grid, covering every pixel along with its state, its temperature and
+    /// motion, and the entities.
+    ///
+    /// Two sandboxes with the same hash hold the same world, regardless of platform. Elements
+    /// are hashed by name like [`Pixel`] does, so adding one doesn't change the hash of the
+    /// others and registered elements don't collide. Only replaying the inputs, as fixtures do,
+    /// reproduces it: mirrors leave the motion out, see [`Sandbox::grid_hash`].
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StableHasher::default();
+        self.width.hash(&mut hasher);
+        self.height.hash(&mut hasher);
+        self.pixels.iter().for_each(|p| {
+            p.pixel().hash(&mut hasher);
+            p.temp.hash(&mut hasher);
+            p.velocity.hash(&mut hasher);
+            p.fall.hash(&mut hasher);
+        });
+        self.entities.iter().for_each(|e| e.hash(&mut hasher));
+        hasher.finish()
+    }
+
+    /// Stable hash of the pixels, their temperatures and the entities, leaving out the motion,
+    /// which is what a [`crate::sync::GridDiff`] carries to a mirror.
+    pub fn grid_hash(&self) -> u64 {
+        let mut hasher = StableHasher::default();
+        self.width.hash(&mut hasher);
+        self.height.hash(&mut hasher);
+        self.pixels.iter().for_each(|p| {
+            p.pixel().hash(&mut hasher);
+            p.temp.hash(&mut hasher);
+        });
+        self.entities.iter().for_each(|e| e.hash(&mut hasher));
+        hasher.finish()
+    }
+
+    pub fn resize(&mut self, width: usize, height: usize) {
+        let width_delta = width as isize - self.width as isize;
+        let height_delta = height as isize - self.height as isize;
+
+        let mut new_sandbox = Sandbox::<SmallRng>::new(width, height);
+        if !self.frozen.is_empty() {
+            new_sandbox.frozen = vec![false; width * height];
+        }
+        self.pixels.iter().enumerate().for_each(|(idx, p)| {
+            let (x, y) = self.index_to_coordinates(idx);
+            let new_x = x as isize + width_delta / 2;
+            let new_y = y as isize + height_delta / 2;
+            if new_sandbox.is_coordinate_in_bound(new_x as usize, new_y as usize) {
+                let new_idx = new_sandbox.coordinates_to_index(new_x as usize, new_y as usize);
+                new_sandbox.pixels[new_idx] = p.clone();
+                if let Some(frozen) = self.frozen.get(idx) {
+                    new_sandbox.frozen[new_idx] = *frozen;
+                }
+            }
+        });
+
+        self.width = new_sandbox.width;
+        self.height = new_sandbox.height;
+        self.pixels = new_sandbox.pixels;
+        // the cells recorded so far moved, the patch goes on with the new size
+        if self.patch.is_some() {
+            self.patch = Some(Patch::new(width, height));
+        }
+        self.frozen = new_sandbox.frozen;
+
+        self.entities.retain_mut(|entity| {
+            let x = entity.x as isize + width_delta / 2;
+            let y = entity.y as isize + height_delta / 2;
+            let (entity_width, entity_height) = entity.footprint();
+            let fits = x >= 0
+                && y >= 0
+                && x as usize + entity_width <= width
+                && y as usize + entity_height <= height;
+            (entity.x, entity.y) = (x.max(0) as usize, y.max(0) as usize);
+            fits
+        });
+    }
+}
+
+/// Takes `drag` percent off `speed`, rounding towards zero.
+fn damp(speed: i8, drag: u8) -> i8 {
+    (speed as i16 * (100 - drag.min(100) as i16) / 100) as i8
+}
+
+#[cfg(test)]
+mod test {
+    use std::hash::{Hash, Hasher};
+
+    use rand::rngs::mock::StepRng;
+    use rand::rngs::SmallRng;
+
+    use crate::annotation::{Annotation, MoveRule};
+    use crate::command::Command;
+    use crate::config::{AirDrag, SandboxConfig, UpdateOrder};
+    use crate::entity::EntityKind;
+    use crate::event::EventKind;
+    use crate::hash::StableHasher;
+    use crate::pixel::battery::Battery;
+    use crate::pixel::boulder::Boulder;
+    use crate::pixel::cloud::Cloud;
+    use crate::pixel::drain::Drain;
+    use crate::pixel::eternal_fire::EternalFire;
+    use crate::pixel::fire::Fire;
+    use crate::pixel::fungus::Fungus;
+    use crate::pixel::grass::Grass;
+    use crate::pixel::gravel::Gravel;
+    use crate::pixel::gunpowder::Gunpowder;
+    use crate::pixel::ice::Ice;
+    use crate::pixel::lava::Lava;
+    use crate::pixel::levitation_field::LevitationField;
+    use crate::pixel::oil::Oil;
+    use crate::pixel::pipe::Pipe;
+    use crate::pixel::plank::Plank;
+    use crate::pixel::poison::Poison;
+    use crate::pixel::pump::Pump;
+    use crate::pixel::rock::Rock;
+    use crate::pixel::rubber::Rubber;
+    use crate::pixel::salt::Salt;
+    use crate::pixel::sand::Sand;
+    use crate::pixel::sieve::Sieve;
+    use crate::pixel::soil::Soil;
+    use crate::pixel::steam::Steam;
+    use crate::pixel::switch::Switch;
+    use crate::pixel::timer::Timer;
+    use crate::pixel::tnt::Tnt;
+    use crate::pixel::turbine::Turbine;
+    use crate::pixel::vine::Vine;
+    use crate::pixel::void::Void;
+    use crate::pixel::water::Water;
+    use crate::pixel::water_wheel::WaterWheel;
+    use crate::pixel::wet_sand::WetSand;
+    use crate::pixel::wire::Wire;
+    use crate::pixel::wood::Wood;
+    use crate::pixel::{Direction, Pixel, PixelFundamental, PixelKind};
+    use crate::reaction::Reactions;
+    use crate::sandbox::{PlaceError, Placed, Sandbox};
+    use crate::scene::Region;
+    use crate::temperature;
+
+    fn new_rng() -> StepRng {
+        StepRng::new(42, 1)
+    }
+
+    #[test]
+    fn test_sandbox_creation() {
+        // create a sandbox
+        let sandbox = Sandbox::new_with_rng(3, 3, new_rng());
+        assert_eq!(sandbox.pixels.len(), 9);
+    }
+    #[test]
+    fn test_sandbox_tick() {
+        // create a sandbox
+        let mut sandbox = Sandbox::new_with_rng(3, 3, new_rng());
+        sandbox.place_pixel_force(Sand.into(), 1, 0);
+        sandbox.tick();
+        let new_cord = sandbox.coordinates_to_index(1, 1);
+        assert_eq!(sandbox.pixels[new_cord].pixel, Sand.into());
+        sandbox.tick();
+        let new_cord = sandbox.coordinates_to_index(1, 2);
+        assert_eq!(sandbox.pixels[new_cord].pixel, Sand.into());
+    }
+    #[test]
+    fn test_sandbox_tick2() {
+        // create a sandbox
+        let mut sandbox = Sandbox::new_with_rng(3, 3, new_rng());
+        sandbox.place_pixel_force(Sand.into(), 1, 0);
+        sandbox.place_pixel_force(Water::default().into(), 1, 1);
+        sandbox.tick();
+        let sand_new_cord = sandbox.coordinates_to_index(1, 1);
+        let water_new_cord = sandbox.coordinates_to_index(1, 2);
+        assert_eq!(
+            sandbox.pixels[sand_new_cord].pixel,
+            Sand.into(),
+            "{:?}",
+            &sandbox.pixels
+        );
+        assert_eq!(
+            sandbox.pixels[water_new_cord].pixel,
+            Water::default().into(),
+            "{:?}",
+            &sandbox.pixels
+        );
+        sandbox.tick();
+        let sand_new_cord = sandbox.coordinates_to_index(1, 2);
+        let water_new_cord = sandbox.coordinates_to_index(0, 2);
+        assert_eq!(
+            sandbox.pixels[sand_new_cord].pixel,
+            Sand.into(),
+            "{:?}",
+            &sandbox.pixels
+        );
+        assert_eq!(
+            sandbox.pixels[water_new_cord].pixel,
+            Water::default().into(),
+            "{:?}",
+            &sandbox.pixels
+        );
+    }
+
+    #[test]
+    fn test_sandbox_tick3() {
+        // create a sandbox
+        let mut sandbox = Sandbox::new_with_rng(3, 4, new_rng());
+        sandbox.place_pixel_force(Sand.into(), 1, 1);
+        sandbox.place_pixel_force(Sand.into(), 1, 2);
+        sandbox.place_pixel_force(Water::default().into(), 0, 3);
+        sandbox.place_pixel_force(Water::default().into(), 1, 3);
+        sandbox.place_pixel_force(Water::default().into(), 2, 3);
+        sandbox.tick();
+        let sand1_new_cord = sandbox.coordinates_to_index(0, 2);
+        let sand2_new_cord = sandbox.coordinates_to_index(1, 3);
+        let water1_new_cord = sandbox.coordinates_to_index(0, 3);
+        let water2_new_cord = sandbox.coordinates_to_index(1, 2);
         let water3_new_cord = sandbox.coordinates_to_index(2, 3);
         assert_eq!(
-            sandbox.pixels[sand1_new_cord].pixel,
-            Sand.into(),
-            "{:?}",
-            &sandbox.pixels
+            sandbox.pixels[sand1_new_cord].pixel,
+            Sand.into(),
+            "{:?}",
+            &sandbox.pixels
+        );
+        assert_eq!(
+            sandbox.pixels[sand2_new_cord].pixel,
+            Sand.into(),
+            "{:?}",
+            &sandbox.pixels
+        );
+        assert_eq!(
+            sandbox.pixels[water1_new_cord].pixel,
+            Water::default().into(),
+            "{:?}",
+            &sandbox.pixels
+        );
+        assert_eq!(
+            sandbox.pixels[water2_new_cord].pixel,
+            Water::default().into(),
+            "{:?}",
+            &sandbox.pixels
+        );
+        assert_eq!(
+            sandbox.pixels[water3_new_cord].pixel,
+            Water::default().into(),
+            "{:?}",
+            &sandbox.pixels
+        );
+    }
+
+    #[test]
+    fn test_sandbox_queue_command() {
+        let mut sandbox = Sandbox::new_with_rng(3, 3, new_rng());
+        sandbox.queue_command(Command::PlaceBrush {
+            pixel: Sand.into(),
+            x: 1,
+            y: 1,
+            width: 3,
+            height: 3,
+            force: false,
+        });
+        // nothing is applied until the next tick
+        assert!(sandbox.pixels.iter().all(|p| p.pixel() == Pixel::default()));
+
+        sandbox.tick();
+        let placed = sandbox
+            .pixels
+            .iter()
+            .filter(|p| p.pixel() == Sand.into())
+            .count();
+        assert_eq!(placed, 4, "{:?}", &sandbox.pixels);
+    }
+
+    /// No air slowing thrown pixels down, see [`test_sandbox_air_drag`] for the air.
+    fn without_air() -> SandboxConfig {
+        SandboxConfig {
+            drag: AirDrag {
+                solid: 0,
+                liquid: 0,
+                gas: 0,
+            },
+            ..SandboxConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_sandbox_flick() {
+        let mut sandbox = Sandbox::new_with_rng(10, 10, new_rng());
+        sandbox.set_config(without_air());
+        sandbox.place_pixel_force(Sand.into(), 0, 5);
+        sandbox.queue_command(Command::Flick {
+            x: 0,
+            y: 5,
+            width: 1,
+            height: 1,
+            dx: 3,
+            dy: 0,
+        });
+
+        // the pixel flies while slowing down, then falls again
+        for (x, velocity) in [(3, (2, 0)), (5, (1, 0)), (6, (0, 0))] {
+            sandbox.tick();
+            let idx = sandbox.coordinates_to_index(x, 5);
+            assert_eq!(
+                sandbox.pixels[idx].pixel(),
+                Sand.into(),
+                "{:?}",
+                &sandbox.pixels
+            );
+            assert_eq!(sandbox.pixels[idx].velocity(), velocity);
+        }
+        sandbox.tick();
+        let idx = sandbox.coordinates_to_index(6, 6);
+        assert_eq!(sandbox.pixels[idx].pixel(), Sand.into());
+    }
+
+    #[test]
+    fn test_sandbox_restitution() {
+        let height_of = |sandbox: &Sandbox<_>, pixel: &str| {
+            let idx = sandbox
+                .pixels
+                .iter()
+                .position(|p| p.pixel().name() == pixel);
+            sandbox.index_to_coordinates(idx.unwrap()).1
+        };
+
+        // rubber springs back up after landing while sand stays on the floor
+        let mut sandbox = Sandbox::new_with_rng(3, 20, new_rng());
+        sandbox.set_config(without_air());
+        sandbox.place_pixel_force(Rubber.into(), 0, 0);
+        sandbox.place_pixel_force(Sand.into(), 2, 0);
+        for _ in 0..20 {
+            sandbox.tick();
+        }
+        assert_eq!(height_of(&sandbox, "Sand"), 19);
+        let mut highest = 19;
+        for _ in 0..10 {
+            sandbox.tick();
+            highest = highest.min(height_of(&sandbox, "Rubber"));
+        }
+        assert!((2..10).contains(&highest), "{}", highest);
+
+        // a thrown pellet turns back off the wall it hits, losing some of its speed
+        let mut sandbox = Sandbox::new_with_rng(4, 1, new_rng());
+        sandbox.set_config(without_air());
+        sandbox.place_pixel_force(Rubber.into(), 0, 0);
+        sandbox.queue_command(Command::Flick {
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+            dx: 6,
+            dy: 0,
+        });
+        sandbox.tick();
+        let idx = sandbox.coordinates_to_index(3, 0);
+        assert_eq!(sandbox.pixels[idx].velocity(), (-4, 0));
+
+        // flicked as hard as can be into the wall it lies against
+        let mut sandbox = Sandbox::new_with_rng(4, 4, new_rng());
+        sandbox.place_pixel_force(Rubber.into(), 0, 3);
+        sandbox.queue_command(Command::Flick {
+            x: 0,
+            y: 3,
+            width: 1,
+            height: 1,
+            dx: i8::MIN,
+            dy: i8::MIN,
+        });
+        sandbox.tick();
+        assert_eq!(sandbox.census().get("Rubber"), Some(&1));
+    }
+
+    #[test]
+    fn test_sandbox_tick_once() {
+        let mut stepped = Sandbox::new_with_rng(4, 4, new_rng());
+        let mut ticked = Sandbox::new_with_rng(4, 4, new_rng());
+        for sandbox in [&mut stepped, &mut ticked] {
+            sandbox.queue_command(Command::PlaceBrush {
+                pixel: Sand.into(),
+                x: 1,
+                y: 0,
+                width: 2,
+                height: 2,
+                force: false,
+            });
+        }
+        for _ in 0..3 {
+            let events = stepped.tick_once().to_vec();
+            ticked.tick();
+            assert_eq!(events, ticked.events());
+        }
+        assert_eq!(stepped.tick_count(), 3);
+        assert_eq!(
+            stepped.pixels.iter().map(|p| p.pixel()).collect::<Vec<_>>(),
+            ticked.pixels.iter().map(|p| p.pixel()).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_sandbox_air_drag() {
+        let fall_after = |config: SandboxConfig, ticks: usize| {
+            let mut sandbox = Sandbox::new_with_rng(1, 60, new_rng());
+            sandbox.set_config(config);
+            sandbox.place_pixel_force(Sand.into(), 0, 0);
+            for _ in 0..ticks {
+                sandbox.tick();
+            }
+            sandbox.pixels.iter().map(|p| p.fall).max().unwrap()
+        };
+        // falling sand settles at its terminal speed instead of speeding up all the way down
+        assert_eq!(fall_after(SandboxConfig::default(), 40), 24);
+        assert_eq!(fall_after(SandboxConfig::default(), 50), 24);
+        let vacuum = SandboxConfig {
+            drag: AirDrag {
+                solid: 0,
+                ..AirDrag::default()
+            },
+            ..SandboxConfig::default()
+        };
+        assert_eq!(fall_after(vacuum, 40), 40);
+
+        // a gas thrown as hard as a solid loses its push much sooner
+        let speed_after_throw = |pixel: Pixel, config: SandboxConfig, ticks: usize| {
+            let mut sandbox = Sandbox::<SmallRng>::new_with_seed(200, 1, 1);
+            sandbox.set_config(config);
+            sandbox.place_pixel_force(pixel, 0, 0);
+            sandbox.queue_command(Command::Flick {
+                x: 0,
+                y: 0,
+                width: 1,
+                height: 1,
+                dx: 20,
+                dy: 0,
+            });
+            for _ in 0..ticks {
+                sandbox.tick();
+            }
+            let idx = sandbox
+                .pixels
+                .iter()
+                .position(|p| p.pixel().name() == pixel.name());
+            sandbox.pixels[idx.unwrap()].velocity().0
+        };
+        let air = SandboxConfig::default();
+        assert_eq!(speed_after_throw(Steam::default().into(), air, 1), 12);
+        // the air takes its share of slow pixels' speed too, not only of the ones past 25
+        assert_eq!(speed_after_throw(Sand.into(), vacuum, 5), 15);
+        assert!(speed_after_throw(Sand.into(), air, 5) < 15);
+    }
+
+    #[test]
+    fn test_sandbox_flick_diagonal_wall() {
+        let flick = |sandbox: &mut Sandbox<_>| {
+            sandbox.place_pixel_force(Sand.into(), 0, 0);
+            sandbox.queue_command(Command::Flick {
+                x: 0,
+                y: 0,
+                width: 1,
+                height: 1,
+                dx: 4,
+                dy: 4,
+            });
+            sandbox.tick();
+        };
+
+        // a diagonal wall one cell thick stops the pixel in front of it
+        let mut sandbox = Sandbox::new_with_rng(10, 10, new_rng());
+        for x in 0..4 {
+            sandbox.place_pixel_force(Rock.into(), x, 3 - x);
+        }
+        flick(&mut sandbox);
+        let idx = sandbox.coordinates_to_index(1, 1);
+        assert_eq!(
+            sandbox.pixels[idx].pixel(),
+            Sand.into(),
+            "{:?}",
+            &sandbox.pixels
+        );
+        assert_eq!(sandbox.pixels[idx].velocity(), (0, 0));
+
+        // a single cell beside the path lets it through the corner
+        let mut sandbox = Sandbox::new_with_rng(10, 10, new_rng());
+        sandbox.place_pixel_force(Rock.into(), 2, 1);
+        flick(&mut sandbox);
+        let idx = sandbox.coordinates_to_index(4, 4);
+        assert_eq!(
+            sandbox.pixels[idx].pixel(),
+            Sand.into(),
+            "{:?}",
+            &sandbox.pixels
+        );
+
+        // so does water on both sides, which sand pushes aside
+        let mut sandbox = Sandbox::new_with_rng(10, 10, new_rng());
+        sandbox.place_pixel_force(Rock.into(), 2, 1);
+        sandbox.place_pixel_force(Water::default().into(), 1, 2);
+        sandbox.place_pixel_force(Rock.into(), 0, 3);
+        sandbox.place_pixel_force(Rock.into(), 1, 3);
+        sandbox.place_pixel_force(Rock.into(), 2, 3);
+        flick(&mut sandbox);
+        let idx = sandbox.coordinates_to_index(4, 4);
+        assert_eq!(
+            sandbox.pixels[idx].pixel(),
+            Sand.into(),
+            "{:?}",
+            &sandbox.pixels
+        );
+    }
+
+    #[test]
+    fn test_sandbox_empty() {
+        for update_order in [UpdateOrder::Fixed, UpdateOrder::Alternating] {
+            let mut sandbox = Sandbox::new_with_rng(0, 0, new_rng());
+            sandbox.set_config(SandboxConfig {
+                update_order,
+                ..Default::default()
+            });
+            sandbox.tick();
+        }
+    }
+
+    #[test]
+    fn test_sandbox_input_log_limit() {
+        let mut sandbox = Sandbox::new_with_rng(2, 2, new_rng());
+        let flick = Command::Flick {
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+            dx: 1,
+            dy: 0,
+        };
+        for _ in 0..Sandbox::<SmallRng>::INPUT_LOG_LIMIT {
+            sandbox.queue_command(flick);
+        }
+        sandbox.apply_commands();
+        assert_eq!(
+            sandbox.input_log().map(<[_]>::len),
+            Some(Sandbox::<SmallRng>::INPUT_LOG_LIMIT)
+        );
+        sandbox.queue_command(flick);
+        sandbox.apply_commands();
+        assert!(sandbox.input_log().is_none());
+    }
+
+    #[test]
+    fn test_sandbox_vacuum() {
+        let mut sandbox = Sandbox::new_with_rng(3, 3, new_rng());
+        sandbox.place_pixel_force(Sand.into(), 0, 2);
+        sandbox.place_pixel_force(Water::default().into(), 1, 2);
+        sandbox.place_pixel_force(Rock.into(), 2, 2);
+        sandbox.queue_command(Command::Vacuum {
+            x: 0,
+            y: 1,
+            width: 3,
+            height: 2,
+        });
+        sandbox.apply_commands();
+        sandbox.queue_command(Command::Vacuum {
+            x: 2,
+            y: 0,
+            width: 1,
+            height: 3,
+        });
+        sandbox.place_pixel_force(Sand.into(), 2, 0);
+        sandbox.tick();
+        sandbox.tick();
+
+        // walls stay where they are, and nothing is lost over several ticks
+        assert_eq!(
+            sandbox.take_vacuumed(),
+            [Sand.into(), Water::default().into(), Sand.into()]
+        );
+        assert!(sandbox.take_vacuumed().is_empty());
+        assert_eq!(sandbox.census().get("Rock"), Some(&1));
+        assert_eq!(sandbox.census().get("Void"), Some(&8));
+    }
+
+    #[test]
+    fn test_sandbox_erase() {
+        let mut sandbox = Sandbox::new_with_rng(3, 3, new_rng());
+        sandbox.place_pixel_force(Sand.into(), 0, 2);
+        sandbox.place_pixel_force(Rock.into(), 1, 2);
+        sandbox.place_pixel_force(Drain.into(), 2, 2);
+        sandbox.place_pixel_force(Water::default().into(), 2, 1);
+        sandbox.record_patch();
+        // walls go too, and the part past the border is left out
+        sandbox.queue_command(Command::Erase {
+            x: 1,
+            y: 1,
+            width: 5,
+            height: 5,
+        });
+        sandbox.apply_commands();
+        assert_eq!(sandbox.census().get("Void"), Some(&8));
+        assert_eq!(sandbox.census().get("Sand"), Some(&1));
+
+        let undo = sandbox.take_patch().unwrap();
+        assert_eq!(undo.len(), 3);
+        sandbox.apply_patch(&undo).unwrap();
+        assert_eq!(sandbox.census().get("Void"), Some(&5));
+    }
+
+    #[test]
+    fn test_sandbox_lava_quench() {
+        let mut sandbox = Sandbox::new_with_rng(3, 3, new_rng());
+        sandbox.place_pixel_force(Lava::default().into(), 1, 1);
+        sandbox.place_pixel_force(Water::default().into(), 1, 2);
+        sandbox.tick();
+
+        assert_eq!(sandbox.census().get("Obsidian"), Some(&1));
+        assert_eq!(sandbox.census().get("Lava"), None);
+    }
+
+    #[test]
+    fn test_sandbox_topple_height() {
+        let mut sandbox = Sandbox::new_with_rng(5, 4, new_rng());
+        sandbox.place_pixel_force(Gravel.into(), 2, 2);
+        sandbox.place_pixel_force(Gravel.into(), 2, 3);
+        sandbox.place_pixel_force(Sand.into(), 2, 1);
+        sandbox.tick();
+        sandbox.tick();
+
+        // sand slides off a step of one, gravel needs a steeper slope
+        let idx = sandbox.coordinates_to_index(2, 2);
+        assert_eq!(sandbox.pixels[idx].pixel(), Gravel.into());
+        assert!((0..4).all(|y| {
+            let idx = sandbox.coordinates_to_index(2, y);
+            sandbox.pixels[idx].pixel() != Sand.into()
+        }));
+    }
+
+    #[test]
+    fn test_sandbox_angle_of_repose() {
+        // pours grains on a single spot, returns the height and the width of the pile
+        let pile = |pixel: Pixel| {
+            let mut sandbox = Sandbox::new_with_rng(21, 16, new_rng());
+            for _ in 0..30 {
+                sandbox.place_pixel(pixel, 10, 0);
+                sandbox.tick();
+            }
+            for _ in 0..50 {
+                sandbox.tick();
+            }
+            let is_filled =
+                |x, y| sandbox.pixels[sandbox.coordinates_to_index(x, y)].pixel() == pixel;
+            let height = (0..16).filter(|&y| is_filled(10, y)).count();
+            let width = (0..21).filter(|&x| is_filled(x, 15)).count();
+            (height, width)
+        };
+
+        let sand = pile(Sand.into());
+        let gravel = pile(Gravel.into());
+        let wet_sand = pile(WetSand::default().into());
+        assert!(sand.0 < gravel.0 && gravel.0 < wet_sand.0);
+        assert!(sand.1 > gravel.1 && gravel.1 > wet_sand.1);
+    }
+
+    #[test]
+    fn test_sandbox_soil_grows_grass() {
+        let mut sandbox = Sandbox::new_with_rng(3, 4, new_rng());
+        for x in 0..3 {
+            sandbox.place_pixel_force(Soil::default().into(), x, 3);
+            sandbox.place_pixel_force(Soil::default().into(), x, 2);
+        }
+        sandbox.place_pixel_force(Water::default().into(), 1, 1);
+        sandbox.place_pixel_force(Water::default().into(), 1, 0);
+
+        for _ in 0..200 {
+            sandbox.tick();
+        }
+
+        // the water soaks in, then the top layer grows grass
+        assert_eq!(sandbox.census().get("Water"), None);
+        let idx = sandbox.coordinates_to_index(1, 2);
+        assert_eq!(sandbox.pixels[idx].pixel(), Grass::default().into());
+        let idx = sandbox.coordinates_to_index(1, 3);
+        assert!(matches!(sandbox.pixels[idx].pixel(), Pixel::Soil(_)));
+    }
+
+    #[test]
+    fn test_sandbox_vine_climbs() {
+        let mut sandbox = Sandbox::new_with_rng(4, 8, new_rng());
+        for y in 0..8 {
+            sandbox.place_pixel_force(Rock.into(), 0, y);
+        }
+        sandbox.place_pixel_force(Rock.into(), 1, 7);
+        sandbox.place_pixel_force(Vine::default().into(), 1, 6);
+        // cut off from the ground, this one never grows
+        sandbox.place_pixel_force(Vine::default().into(), 3, 3);
+        sandbox.place_pixel_force(Rock.into(), 2, 3);
+
+        for _ in 0..300 {
+            sandbox.tick();
+        }
+
+        let vine_at = |x, y| {
+            matches!(
+                sandbox.pixels[sandbox.coordinates_to_index(x, y)].pixel(),
+                Pixel::Vine(_)
+            )
+        };
+        assert!((3..=6).all(|y| vine_at(1, y)), "{:?}", &sandbox.pixels);
+        assert!(!vine_at(3, 2));
+        assert_eq!(sandbox.census().get("Vine"), Some(&5));
+    }
+
+    #[test]
+    fn test_sandbox_moisture() {
+        let ignition = |moisture: u8| {
+            let mut sandbox = Sandbox::new_with_rng(2, 1, new_rng());
+            let wood = Wood {
+                moisture,
+                ..Default::default()
+            };
+            sandbox.place_pixel_force(wood.into(), 0, 0);
+            sandbox.place_pixel_force(EternalFire.into(), 1, 0);
+            (1..100)
+                .find(|_| {
+                    sandbox.tick();
+                    sandbox.pixels[0].pixel().is_hot()
+                })
+                .unwrap()
+        };
+        // wet wood has to dry out before it catches fire
+        assert!(ignition(100) > ignition(0) + 5);
+
+        let mut sandbox = Sandbox::new_with_rng(2, 1, new_rng());
+        sandbox.place_pixel_force(Soil::default().into(), 0, 0);
+        sandbox.place_pixel_force(EternalFire.into(), 1, 0);
+        if let Pixel::Soil(soil) = sandbox.pixels[0].pixel_mut() {
+            soil.moisture = 50;
+        }
+        for _ in 0..20 {
+            sandbox.tick();
+        }
+        assert!(matches!(sandbox.pixels[0].pixel(), Pixel::Soil(soil) if soil.moisture == 0));
+    }
+
+    #[test]
+    fn test_sandbox_fungus_eats_wood() {
+        let mut sandbox = Sandbox::new_with_rng(4, 3, new_rng());
+        for x in 0..4 {
+            sandbox.place_pixel_force(Rock.into(), x, 2);
+            sandbox.place_pixel_force(Wood::default().into(), x, 1);
+        }
+        sandbox.place_pixel_force(Fungus::default().into(), 0, 1);
+
+        // the fungus spreads along the plank, then crumbles once the wood is gone
+        for _ in 0..1000 {
+            sandbox.tick();
+        }
+        assert_eq!(sandbox.census().get("Wood"), None);
+        assert_eq!(sandbox.census().get("Fungus"), None);
+        assert_eq!(sandbox.census().get("Dust"), Some(&4));
+    }
+
+    #[test]
+    fn test_sandbox_fungus_killed() {
+        for killer in [Poison::default().into(), Salt::default().into()] {
+            let mut sandbox = Sandbox::new_with_rng(3, 2, new_rng());
+            for x in 0..3 {
+                sandbox.place_pixel_force(Wood::default().into(), x, 1);
+            }
+            sandbox.place_pixel_force(Fungus::default().into(), 1, 1);
+            sandbox.place_pixel_force(killer, 1, 0);
+
+            // the fungus dies before it gets to eat any wood
+            sandbox.tick();
+            sandbox.tick();
+            assert_eq!(sandbox.census().get("Fungus"), None, "{:?}", killer);
+            assert_eq!(sandbox.census().get("Wood"), Some(&2));
+        }
+    }
+
+    #[test]
+    fn test_sandbox_waterfall_foams() {
+        let mut sandbox = Sandbox::<SmallRng>::new_with_seed(5, 20, 1);
+        let mut foamed = false;
+        for _ in 0..100 {
+            sandbox.place_pixel(Water::default().into(), 2, 0);
+            sandbox.tick();
+            foamed |= sandbox.census().contains_key("Foam");
+        }
+        assert!(foamed);
+
+        // water resting in a pool never foams
+        let mut sandbox = Sandbox::<SmallRng>::new_with_seed(5, 3, 1);
+        for x in 0..5 {
+            sandbox.place_pixel_force(Water::default().into(), x, 2);
+        }
+        for _ in 0..100 {
+            sandbox.tick();
+            assert!(!sandbox.census().contains_key("Foam"));
+        }
+    }
+
+    #[test]
+    fn test_sandbox_poison_spreads() {
+        let mut sandbox = Sandbox::<SmallRng>::new_with_seed(10, 3, 1);
+        for x in 0..10 {
+            sandbox.place_pixel_force(Rock.into(), x, 2);
+            sandbox.place_pixel_force(Water::default().into(), x, 1);
+        }
+        sandbox.place_pixel_force(Poison::default().into(), 0, 1);
+        sandbox.place_pixel_force(Vine::default().into(), 9, 0);
+
+        // the whole pond ends up poisoned, killing the vine hanging over it
+        for _ in 0..1000 {
+            sandbox.tick();
+        }
+        assert_eq!(sandbox.census().get("Water"), None);
+        assert_eq!(sandbox.census().get("Poisoned water"), Some(&9));
+        assert_eq!(sandbox.census().get("Vine"), None);
+    }
+
+    #[test]
+    fn test_sandbox_oil() {
+        // oil poured under water rises to the top
+        let mut sandbox = Sandbox::new_with_rng(1, 3, new_rng());
+        sandbox.place_pixel_force(Oil::default().into(), 0, 2);
+        sandbox.place_pixel_force(Water::default().into(), 0, 1);
+        sandbox.place_pixel_force(Water::default().into(), 0, 0);
+        for _ in 0..10 {
+            sandbox.tick();
+        }
+        assert!(
+            matches!(sandbox.pixels[0].pixel(), Pixel::Oil(_)),
+            "{:?}",
+            &sandbox.pixels
+        );
+
+        // a flame sets a pool of oil alight, which burns down into fire catching the wood by it
+        let mut sandbox = Sandbox::<SmallRng>::new_with_seed(5, 2, 1);
+        for x in 0..5 {
+            sandbox.place_pixel_force(Rock.into(), x, 1);
+        }
+        for x in 0..4 {
+            sandbox.place_pixel_force(Oil::default().into(), x, 0);
+        }
+        sandbox.place_pixel_force(Wood::default().into(), 4, 0);
+        sandbox.place_pixel_force(Fire::default().into(), 0, 0);
+        let mut ignited = false;
+        for _ in 0..50 {
+            sandbox.tick();
+            ignited |= sandbox.pixels[..4].iter().all(|p| p.pixel().is_hot());
+        }
+        assert!(ignited, "{:?}", &sandbox.pixels);
+        let Pixel::Wood(wood) = sandbox.pixels[4].pixel() else {
+            panic!("{:?}", &sandbox.pixels);
+        };
+        assert!(wood.is_burning());
+        for _ in 0..200 {
+            sandbox.tick();
+        }
+        assert_eq!(sandbox.census().get("Oil"), None);
+    }
+
+    #[test]
+    fn test_sandbox_annotations() {
+        let whole = |sandbox: &Sandbox<StepRng>| Region {
+            x: 0,
+            y: 0,
+            width: sandbox.width,
+            height: sandbox.height,
+        };
+        let annotation = |from, to, rule| Annotation { from, to, rule };
+
+        // sand falls into the air, then sinks through the water walled in under it
+        let mut sandbox = Sandbox::new_with_rng(3, 3, new_rng());
+        sandbox.place_pixel_force(Sand.into(), 1, 0);
+        sandbox.place_pixel_force(Water::default().into(), 1, 2);
+        sandbox.place_pixel_force(Rock.into(), 0, 2);
+        sandbox.place_pixel_force(Rock.into(), 2, 2);
+        sandbox.annotate(Some(whole(&sandbox)));
+        sandbox.tick();
+        assert_eq!(
+            sandbox.annotations(),
+            [annotation((1, 0), (1, 1), MoveRule::Gravity)]
+        );
+        sandbox.tick();
+        assert_eq!(
+            sandbox.annotations(),
+            [annotation((1, 1), (1, 2), MoveRule::DensitySwap)]
+        );
+
+        // only the moves out of the region are recorded
+        sandbox.annotate(Some(Region {
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+        }));
+        sandbox.tick();
+        assert!(sandbox.annotations().is_empty());
+
+        let mut sandbox = Sandbox::new_with_rng(5, 1, new_rng());
+        sandbox.place_pixel_force(Sand.into(), 0, 0);
+        sandbox.annotate(Some(whole(&sandbox)));
+        sandbox.queue_command(Command::Flick {
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+            dx: 3,
+            dy: 0,
+        });
+        sandbox.tick();
+        assert_eq!(
+            sandbox.annotations(),
+            [annotation((0, 0), (3, 0), MoveRule::Thrown)]
+        );
+    }
+
+    #[test]
+    fn test_sandbox_explosion() {
+        let sandbox = Sandbox::new_with_rng(4, 4, new_rng());
+        assert_eq!(
+            sandbox.cells_within(0, 0, 1).collect::<Vec<_>>(),
+            [(0, 0), (1, 0), (0, 1)]
+        );
+        assert_eq!(sandbox.cells_within(1, 1, 1).count(), 5);
+
+        // a spark sets the tnt off, and the gunpowder caught in its blast goes off in turn
+        let mut sandbox = Sandbox::new_with_rng(30, 3, new_rng());
+        for x in 0..30 {
+            sandbox.place_pixel_force(Rock.into(), x, 2);
+        }
+        sandbox.place_pixel_force(Rock.into(), 0, 1);
+        sandbox.place_pixel_force(Rock.into(), 5, 1);
+        sandbox.place_pixel_force(Tnt::default().into(), 10, 1);
+        sandbox.place_pixel_force(Gunpowder::default().into(), 15, 1);
+        sandbox.place_pixel_force(Sand.into(), 20, 1);
+        sandbox.queue_command(Command::TriggerEvent {
+            source: Battery.into(),
+            x: 10,
+            y: 1,
+        });
+        sandbox.tick();
+        let blasts = sandbox
+            .events()
+            .iter()
+            .filter(|e| e.kind == EventKind::Explosion)
+            .map(|e| (e.x, e.magnitude))
+            .collect::<Vec<_>>();
+        assert_eq!(blasts, [(10, 6), (15, 3)]);
+        assert_eq!(sandbox.census().get("TNT"), None);
+        assert_eq!(sandbox.census().get("Gunpowder"), None);
+        // walls inside the radius are destroyed, the ones outside stand
+        assert!(matches!(sandbox.pixels[30].pixel(), Pixel::Rock(_)));
+        assert!(!matches!(sandbox.pixels[35].pixel(), Pixel::Rock(_)));
+        // the sand outside of both blasts is thrown away from them
+        let sand = &sandbox.pixels[50];
+        assert!(matches!(sand.pixel(), Pixel::Sand(_)));
+        assert!(sand.velocity().0 > 0, "{:?}", sand);
+
+        // gunpowder goes off as soon as a flame licks it
+        let mut sandbox = Sandbox::new_with_rng(3, 1, new_rng());
+        sandbox.place_pixel_force(Gunpowder::default().into(), 0, 0);
+        sandbox.place_pixel_force(Fire::default().into(), 1, 0);
+        sandbox.tick();
+        assert!(sandbox
+            .events()
+            .iter()
+            .any(|e| e.kind == EventKind::Explosion));
+    }
+
+    #[test]
+    fn test_sandbox_wire() {
+        let mut sandbox = Sandbox::new_with_rng(12, 2, new_rng());
+        sandbox.place_pixel_force(Battery.into(), 0, 0);
+        sandbox.place_pixel_force(Switch::default().into(), 1, 0);
+        for x in 2..12 {
+            sandbox.place_pixel_force(Wire::default().into(), x, 0);
+        }
+        let sparks = |sandbox: &Sandbox<StepRng>| {
+            sandbox
+                .pixels
+                .iter()
+                .filter(|p| matches!(p.pixel(), Pixel::Wire(w) if w.is_sparking()))
+                .count()
+        };
+
+        // the switch is off, nothing gets through
+        for _ in 0..20 {
+            sandbox.tick();
+            assert_eq!(sparks(&sandbox), 0);
+        }
+
+        sandbox.queue_command(Command::Activate {
+            x: 1,
+            y: 0,
+            width: 1,
+            height: 1,
+        });
+        let mut reached_end = false;
+        for _ in 0..60 {
+            sandbox.tick();
+            // sparks travel as separate pulses rather than flooding the wire
+            assert!(sparks(&sandbox) <= 4, "{:?}", &sandbox.pixels);
+            let idx = sandbox.coordinates_to_index(11, 0);
+            reached_end |= sandbox.pixels[idx].pixel().is_sparking();
+        }
+        assert!(reached_end);
+    }
+
+    #[test]
+    fn test_sandbox_timer() {
+        let mut sandbox = Sandbox::new_with_rng(3, 2, new_rng());
+        sandbox.place_pixel_force(Timer::default().into(), 0, 0);
+        sandbox.place_pixel_force(Wire::default().into(), 1, 0);
+        // one click moves on to the next period
+        sandbox.queue_command(Command::Activate {
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+        });
+
+        let mut pulses = 0;
+        for _ in 0..600 {
+            sandbox.tick();
+            let idx = sandbox.coordinates_to_index(1, 0);
+            pulses += sandbox.pixels[idx].pixel().is_sparking() as usize;
+        }
+        assert_eq!(pulses, 600 / Timer::PERIODS[2] as usize);
+    }
+
+    #[test]
+    fn test_sandbox_turbine() {
+        let mut sandbox = Sandbox::new_with_rng(3, 12, new_rng());
+        sandbox.place_pixel_force(Turbine::default().into(), 1, 5);
+        sandbox.place_pixel_force(Wire::default().into(), 2, 5);
+        let pulses = |sandbox: &mut Sandbox<StepRng>, pour: bool| {
+            let mut pulses = 0;
+            for _ in 0..100 {
+                // a waterfall down the left column, drained at the bottom
+                if pour {
+                    sandbox.place_pixel_force(Water::default().into(), 0, 0);
+                }
+                sandbox.place_pixel_force(Void::default().into(), 0, 11);
+                sandbox.tick();
+                let idx = sandbox.coordinates_to_index(2, 5);
+                pulses += sandbox.pixels[idx].pixel().is_sparking() as usize;
+            }
+            pulses
+        };
+
+        assert_eq!(pulses(&mut sandbox, false), 0);
+        let flowing = pulses(&mut sandbox, true);
+        assert!(flowing > 0);
+        // one pulse per window at most
+        assert!(flowing <= 100 / Turbine::WINDOW as usize);
+    }
+
+    #[test]
+    fn test_sandbox_water_wheel() {
+        let mut sandbox = Sandbox::new_with_rng(5, 12, new_rng());
+        // placed whole around the centre of the brush
+        sandbox.queue_command(Command::PlaceBrush {
+            pixel: WaterWheel::default().into(),
+            x: 2,
+            y: 6,
+            width: 1,
+            height: 1,
+            force: false,
+        });
+        sandbox.apply_commands();
+        assert_eq!(sandbox.census().get("Water wheel"), Some(&9));
+        assert_eq!(
+            sandbox.place_composite(WaterWheel::default().into(), 0, 4, false),
+            Err(PlaceError::Occupied(PixelKind::WaterWheel))
         );
+        sandbox.place_pixel_force(Wire::default().into(), 4, 6);
+
+        let mut pulses = 0;
+        let mut turns = 0;
+        for _ in 0..100 {
+            // a waterfall down the left column, drained at the bottom
+            sandbox.place_pixel_force(Water::default().into(), 0, 0);
+            sandbox.place_pixel_force(Void::default().into(), 0, 11);
+            sandbox.tick();
+            pulses += sandbox.pixels[sandbox.coordinates_to_index(4, 6)]
+                .pixel()
+                .is_sparking() as usize;
+            turns += sandbox
+                .events()
+                .iter()
+                .filter(|event| event.kind == EventKind::Torque)
+                .count();
+        }
+        assert!(turns > 0);
+        assert!(pulses > 0);
+        let Pixel::WaterWheel(hub) = sandbox.pixels[sandbox.coordinates_to_index(2, 6)].pixel()
+        else {
+            panic!("the hub is gone");
+        };
+        assert!(hub.is_hub());
+
+        // a wheel missing a cell falls apart
+        sandbox.place_pixel_force(Void::default().into(), 3, 7);
+        sandbox.tick();
+        sandbox.tick();
+        assert_eq!(sandbox.census().get("Water wheel"), None);
+        assert_eq!(sandbox.census().get("Wood"), Some(&8));
+    }
+
+    #[test]
+    fn test_sandbox_composites() {
+        let mut sandbox = Sandbox::new_with_rng(6, 8, new_rng());
+        for x in 0..6 {
+            sandbox.place_pixel_force(Water::default().into(), x, 7);
+        }
+        sandbox
+            .place_composite(Boulder::default().into(), 0, 0, false)
+            .unwrap();
+        sandbox
+            .place_composite(Plank::default().into(), 3, 0, false)
+            .unwrap();
+        for _ in 0..10 {
+            sandbox.tick();
+        }
+
+        let kind_at = |sandbox: &Sandbox<StepRng>, x, y| {
+            PixelKind::from(&sandbox.pixels[sandbox.coordinates_to_index(x, y)].pixel())
+        };
+        // the boulder sinks to the bottom whole, pushing the water up, the plank floats on it
+        for (x, y) in [(0, 6), (1, 6), (0, 7), (1, 7)] {
+            assert_eq!(kind_at(&sandbox, x, y), PixelKind::Boulder);
+        }
+        for x in 3..6 {
+            assert_eq!(kind_at(&sandbox, x, 6), PixelKind::Plank);
+        }
+        assert_eq!(sandbox.census().get("Water"), Some(&6));
+
+        // a plank missing a cell splinters
+        sandbox.place_pixel_force(Void::default().into(), 4, 6);
+        sandbox.tick();
+        assert_eq!(sandbox.census().get("Plank"), None);
+    }
+
+    #[test]
+    fn test_sandbox_sieve() {
+        let mut sandbox = Sandbox::new_with_rng(2, 5, new_rng());
+        for x in 0..2 {
+            sandbox.place_pixel_force(Sieve.into(), x, 2);
+        }
+        sandbox.place_pixel_force(Water::default().into(), 0, 0);
+        sandbox.place_pixel_force(Sand.into(), 1, 0);
+        sandbox.place_pixel_force(Steam::default().into(), 1, 4);
+        for _ in 0..10 {
+            sandbox.tick();
+        }
+
+        let kind = |sandbox: &Sandbox<StepRng>, x, y| {
+            PixelKind::from(&sandbox.pixels[sandbox.coordinates_to_index(x, y)].pixel())
+        };
+        // the water drained through, the sand is held back and the steam rose past
+        assert_eq!(kind(&sandbox, 1, 1), PixelKind::Sand);
+        assert!((3..5).any(|y| (0..2).any(|x| kind(&sandbox, x, y) == PixelKind::Water)));
+        assert!((0..2).any(|y| (0..2).any(|x| kind(&sandbox, x, y) == PixelKind::Steam)));
+        assert!((0..2).all(|x| kind(&sandbox, x, 2) == PixelKind::Sieve));
+    }
+
+    #[test]
+    fn test_sandbox_lightning() {
+        let mut sandbox = Sandbox::new_with_rng(3, 10, new_rng());
+        for x in 0..3 {
+            sandbox.place_pixel_force(Cloud::default().into(), x, 0);
+            sandbox.place_pixel_force(Wire::default().into(), x, 8);
+        }
+
+        let thunder = (0..1000).find_map(|_| {
+            sandbox.tick();
+            sandbox
+                .events()
+                .iter()
+                .find(|event| event.kind == EventKind::Thunder)
+                .copied()
+        });
+        // the bolt set the air on fire down to the wire, which carries the spark on
+        let x = thunder.unwrap().x;
+        assert!((1..8).all(|y| {
+            let pixel = sandbox.pixels[sandbox.coordinates_to_index(x, y)].pixel();
+            matches!(pixel, Pixel::Fire(_))
+        }));
+        let sparked = (0..3).any(|_| {
+            sandbox.tick();
+            (0..3).any(|x| {
+                sandbox.pixels[sandbox.coordinates_to_index(x, 8)]
+                    .pixel()
+                    .is_sparking()
+            })
+        });
+        assert!(sparked);
+    }
+
+    #[test]
+    fn test_sandbox_allowed_elements() {
+        let mut sandbox = Sandbox::new_with_rng(3, 1, new_rng());
+        sandbox.set_allowed_elements(Some(vec![PixelKind::Sand]));
+        let brush = |pixel: Pixel, x| Command::PlaceBrush {
+            pixel,
+            x,
+            y: 0,
+            width: 1,
+            height: 1,
+            force: true,
+        };
+        sandbox.queue_command(brush(Sand.into(), 0));
+        sandbox.queue_command(brush(Rock.into(), 1));
+        sandbox.queue_command(Command::PlaceSpawner {
+            element: Rock.into(),
+            period: 5,
+            x: 2,
+            y: 0,
+            width: 1,
+            height: 1,
+        });
+        sandbox.apply_commands();
+        let kinds = (0..3)
+            .map(|x| PixelKind::from(&sandbox.pixels[x].pixel()))
+            .collect::<Vec<_>>();
+        assert_eq!(kinds, [PixelKind::Sand, PixelKind::Void, PixelKind::Void]);
+
+        // erasing is always allowed, and lifting the restriction lets anything through
+        sandbox.queue_command(brush(Void::default().into(), 0));
+        sandbox.apply_commands();
+        assert!(matches!(sandbox.pixels[0].pixel(), Pixel::Void(_)));
+        sandbox.set_allowed_elements(None);
+        sandbox.queue_command(brush(Rock.into(), 1));
+        sandbox.apply_commands();
+        assert!(matches!(sandbox.pixels[1].pixel(), Pixel::Rock(_)));
+    }
+
+    #[test]
+    fn test_sandbox_try_place_pixel() {
+        let mut sandbox = Sandbox::new_with_rng(2, 2, new_rng());
         assert_eq!(
-            sandbox.pixels[sand2_new_cord].pixel,
-            Sand.into(),
-            "{:?}",
-            &sandbox.pixels
+            sandbox.try_place_pixel(Sand.into(), 1, 0),
+            Ok(Placed { x: 1, y: 0 })
         );
         assert_eq!(
-            sandbox.pixels[water1_new_cord].pixel,
-            Water::default().into(),
-            "{:?}",
-            &sandbox.pixels
+            sandbox.try_place_pixel(Rock.into(), 1, 0),
+            Err(PlaceError::Occupied(PixelKind::Sand))
         );
+        // past the right edge used to wrap onto the next row
         assert_eq!(
-            sandbox.pixels[water2_new_cord].pixel,
-            Water::default().into(),
-            "{:?}",
-            &sandbox.pixels
+            sandbox.try_place_pixel(Rock.into(), 2, 0),
+            Err(PlaceError::OutOfBounds)
         );
+        assert!(matches!(sandbox.pixels[2].pixel(), Pixel::Void(_)));
+
+        sandbox.set_allowed_elements(Some(vec![PixelKind::Sand]));
         assert_eq!(
-            sandbox.pixels[water3_new_cord].pixel,
-            Water::default().into(),
+            sandbox.try_place_pixel(Rock.into(), 0, 0),
+            Err(PlaceError::Disallowed(PixelKind::Rock))
+        );
+        let results = sandbox.try_place_pixels(Sand.into(), [(0, 0), (1, 0), (0, 2)]);
+        assert_eq!(
+            results,
+            [
+                Ok(Placed { x: 0, y: 0 }),
+                Err(PlaceError::Occupied(PixelKind::Sand)),
+                Err(PlaceError::OutOfBounds)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_sandbox_fog() {
+        // steam cooled by the ice under it, returns what it turned into over time
+        let condense = |sandbox: &mut Sandbox<StepRng>| {
+            sandbox.place_pixel_force(Steam::default().into(), 0, 0);
+            sandbox.place_pixel_force(Ice::default().into(), 0, 1);
+            (0..200)
+                .map(|_| {
+                    sandbox.tick();
+                    PixelKind::from(&sandbox.pixels[0].pixel())
+                })
+                .collect::<Vec<_>>()
+        };
+
+        // an empty cell kept open next to the steam lets it turn into fog, which rains out
+        let mut sandbox = Sandbox::new_with_rng(2, 2, new_rng());
+        sandbox.place_pixel_force(Ice::default().into(), 1, 1);
+        sandbox.queue_command(Command::Freeze {
+            x: 1,
+            y: 0,
+            width: 1,
+            height: 1,
+            frozen: true,
+        });
+        let history = condense(&mut sandbox);
+        let fog = history.iter().position(|&kind| kind == PixelKind::Fog);
+        let water = history.iter().position(|&kind| kind == PixelKind::Water);
+        assert!(fog.unwrap() < water.unwrap());
+
+        // walled in, it condenses straight into water
+        let mut sandbox = Sandbox::new_with_rng(1, 2, new_rng());
+        let history = condense(&mut sandbox);
+        assert!(!history.contains(&PixelKind::Fog));
+        assert!(history.contains(&PixelKind::Water));
+    }
+
+    #[test]
+    fn test_sandbox_spawner() {
+        let mut sandbox = Sandbox::new_with_rng(3, 2, new_rng());
+        sandbox.place_pixel_force(Rock.into(), 0, 0);
+        sandbox.queue_command(Command::PlaceSpawner {
+            element: Rock.into(),
+            period: 5,
+            x: 0,
+            y: 0,
+            width: 2,
+            height: 1,
+        });
+        sandbox.tick();
+        // occupied cells are left alone
+        let spawner = sandbox.coordinates_to_index(1, 0);
+        assert!(matches!(sandbox.pixels[0].pixel(), Pixel::Rock(_)));
+        assert!(matches!(sandbox.pixels[spawner].pixel(), Pixel::Spawner(_)));
+
+        for _ in 0..5 {
+            sandbox.tick();
+        }
+        for (x, y) in [(2, 0), (1, 1)] {
+            let idx = sandbox.coordinates_to_index(x, y);
+            assert!(matches!(sandbox.pixels[idx].pixel(), Pixel::Rock(_)));
+        }
+    }
+
+    #[test]
+    fn test_sandbox_patch() {
+        let mut sandbox = Sandbox::new_with_rng(4, 4, new_rng());
+        sandbox.place_pixel_force(Wood::default().into(), 0, 0);
+        sandbox.record_patch();
+        for x in 0..2 {
+            sandbox.queue_command(Command::PlaceBrush {
+                pixel: Rock.into(),
+                x,
+                y: 0,
+                width: 1,
+                height: 2,
+                force: false,
+            });
+        }
+        sandbox.tick();
+        // only the cells the placements changed are kept, the wood was left alone
+        let undo = sandbox.take_patch().unwrap();
+        assert_eq!(undo.len(), 3);
+        assert!(!sandbox.is_recording_patch());
+
+        let redo = sandbox.apply_patch(&undo).unwrap();
+        assert_eq!(sandbox.census()["Void"], 15);
+        assert!(matches!(sandbox.pixels[0].pixel(), Pixel::Wood(_)));
+        let undo = sandbox.apply_patch(&redo).unwrap();
+        assert_eq!(sandbox.census()["Rock"], 3);
+
+        // the cells the water flowed into since stay as they are
+        sandbox.place_pixel_force(Water::default().into(), 1, 1);
+        sandbox.apply_patch(&undo).unwrap();
+        assert!(matches!(sandbox.pixels[5].pixel(), Pixel::Water(_)));
+        assert_eq!(sandbox.census()["Void"], 14);
+
+        sandbox.resize(5, 5);
+        assert!(sandbox.apply_patch(&undo).is_none());
+    }
+
+    #[test]
+    fn test_sandbox_spout_and_drain() {
+        let mut sandbox = Sandbox::new_with_rng(3, 6, new_rng());
+        sandbox.queue_command(Command::PlaceSpout {
+            element: Sand.into(),
+            x: 1,
+            y: 0,
+            width: 1,
+            height: 1,
+        });
+        sandbox.queue_command(Command::PlaceBrush {
+            pixel: Drain.into(),
+            x: 0,
+            y: 5,
+            width: 3,
+            height: 1,
+            force: true,
+        });
+        sandbox.tick();
+        sandbox.tick();
+        // the spout only pours below itself
+        assert!(matches!(
+            sandbox.pixels[sandbox.coordinates_to_index(1, 1)].pixel(),
+            Pixel::Sand(_)
+        ));
+        assert!(matches!(
+            sandbox.pixels[sandbox.coordinates_to_index(0, 0)].pixel(),
+            Pixel::Void(_)
+        ));
+
+        for _ in 0..50 {
+            sandbox.tick();
+        }
+        // the sand reaching the drain is swallowed rather than piling up
+        let census = sandbox.census();
+        assert_eq!(census["Drain"], 3);
+        assert!(census.get("Sand").copied().unwrap_or(0) < 6);
+    }
+
+    #[test]
+    fn test_sandbox_levitation_field() {
+        let mut sandbox = Sandbox::new_with_rng(3, 12, new_rng());
+        sandbox.place_pixel_force(LevitationField::default().into(), 1, 11);
+        sandbox.place_pixel_force(Sand.into(), 1, 8);
+        for _ in 0..10 {
+            sandbox.tick();
+        }
+        // the sand rises to the edge of the field and bobs around it
+        let (_, y) = sandbox.index_to_coordinates(
+            sandbox
+                .pixels
+                .iter()
+                .position(|p| matches!(p.pixel(), Pixel::Sand(_)))
+                .unwrap(),
+        );
+        assert!((2..=3).contains(&y), "{}", y);
+
+        // a nullified field leaves the sand hovering
+        sandbox.queue_command(Command::Activate {
+            x: 1,
+            y: 11,
+            width: 1,
+            height: 1,
+        });
+        sandbox.place_pixel_force(Water::default().into(), 0, 8);
+        for _ in 0..10 {
+            sandbox.tick();
+        }
+        let idx = sandbox.coordinates_to_index(0, 8);
+        assert!(matches!(sandbox.pixels[idx].pixel(), Pixel::Water(_)));
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn test_sandbox_pixel_id() {
+        let mut sandbox = Sandbox::new_with_rng(1, 5, new_rng());
+        sandbox.place_pixel_force(Rock.into(), 0, 4);
+        sandbox.place_pixel_force(Sand.into(), 0, 0);
+        let sand = sandbox.pixels[0].id();
+        assert_ne!(sand, 0);
+
+        for _ in 0..10 {
+            sandbox.tick();
+        }
+        assert_eq!(sandbox.find_pixel(sand), Some((0, 3)));
+        // the rock kept its own id
+        assert_ne!(sandbox.pixels[4].id(), sand);
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn test_sandbox_history() {
+        let mut sandbox = Sandbox::new_with_rng(1, 5, new_rng());
+        sandbox.place_pixel_force(Water::default().into(), 0, 0);
+        sandbox.track(Some(sandbox.pixels[0].id()));
+        for _ in 0..Sandbox::<StepRng>::HISTORY + 10 {
+            sandbox.tick();
+        }
+
+        let history = sandbox.history();
+        assert_eq!(history.len(), Sandbox::<StepRng>::HISTORY);
+        assert_eq!(history.back().unwrap().tick, sandbox.tick_count() - 1);
+        assert!(history
+            .iter()
+            .all(|sample| sample.temperature == temperature::ROOM));
+        sandbox.track(None);
+        assert!(sandbox.history().is_empty());
+    }
+
+    #[cfg(feature = "debug")]
+    #[test]
+    fn test_sandbox_tick_checked() {
+        use crate::event::Invariant;
+
+        let mut sandbox = Sandbox::new_with_rng(8, 8, new_rng());
+        sandbox.place_pixel_force(Rock.into(), 3, 7);
+        sandbox.place_pixel_force(EternalFire.into(), 5, 7);
+        for tick in 0..40 {
+            sandbox.queue_command(Command::PlaceBrush {
+                pixel: match tick % 3 {
+                    0 => Sand.into(),
+                    1 => Water::default().into(),
+                    _ => Wood::default().into(),
+                },
+                x: tick % 8,
+                y: 0,
+                width: 1,
+                height: 1,
+                force: false,
+            });
+            sandbox.tick_checked();
+            assert!(
+                sandbox
+                    .events()
+                    .iter()
+                    .all(|e| !matches!(e.kind, EventKind::InvariantViolation(_))),
+                "{:?}",
+                sandbox.events()
+            );
+        }
+
+        sandbox.pixels[0].mark_is_moved(true);
+        sandbox.tick_checked();
+        assert!(sandbox
+            .events()
+            .iter()
+            .any(|e| e.kind == EventKind::InvariantViolation(Invariant::MovedLeak)));
+    }
+
+    #[test]
+    fn test_sandbox_convection() {
+        let mut sandbox = Sandbox::new_with_rng(1, 3, new_rng());
+        sandbox.place_pixel_force(Water::default().into(), 0, 1);
+        sandbox.place_pixel_force(Water::default().into(), 0, 2);
+        sandbox.set_temperature(0, 2, 28);
+        // the water feels the heat of its cell on the first tick, and rises on the next
+        sandbox.tick();
+        sandbox.tick();
+        // the warm water rose through the cold water
+        assert!(
+            sandbox.temperature_at(0, 1) > sandbox.temperature_at(0, 2),
             "{:?}",
-            &sandbox.pixels
+            sandbox.pixels
+        );
+    }
+
+    #[test]
+    fn test_sandbox_salt_dissolves() {
+        let mut sandbox = Sandbox::new_with_rng(1, 4, new_rng());
+        sandbox.place_pixel_force(Water::default().into(), 0, 1);
+        sandbox.place_pixel_force(Salt::default().into(), 0, 2);
+        sandbox.place_pixel_force(Rock.into(), 0, 3);
+        for _ in 0..25 {
+            sandbox.tick();
+        }
+        let Pixel::Water(water) = sandbox.pixels[2].pixel() else {
+            panic!("{:?}", sandbox.pixels);
+        };
+        assert_eq!(water.solution().solute(), Some(PixelKind::Salt));
+
+        // boiling the water off leaves the salt behind
+        sandbox.place_pixel_force(EternalFire.into(), 0, 1);
+        for _ in 0..10 {
+            sandbox.tick();
+        }
+        assert!(matches!(sandbox.pixels[2].pixel(), Pixel::Salt(_)));
+    }
+
+    #[test]
+    fn test_sandbox_reaction_probability() {
+        let mut sandbox = Sandbox::new_with_rng(2, 1, new_rng());
+        let mut reactions = Reactions::default();
+        reactions.set_probability(PixelKind::Water, PixelKind::Salt, 0.0);
+        sandbox.set_reactions(reactions);
+        sandbox.place_pixel_force(Water::default().into(), 0, 0);
+        sandbox.place_pixel_force(Salt::default().into(), 1, 0);
+        for _ in 0..10 {
+            sandbox.tick();
+        }
+        let Pixel::Water(water) = sandbox.pixels[0].pixel() else {
+            panic!("{:?}", sandbox.pixels);
+        };
+        assert_eq!(water.solution().solute(), None);
+    }
+
+    #[test]
+    fn test_sandbox_pump() {
+        let mut sandbox = Sandbox::new_with_rng(6, 3, new_rng());
+        sandbox.place_pixel_force(Water::default().into(), 0, 1);
+        sandbox.place_pixel_force(Pump::default().into(), 1, 1);
+        for x in 2..5 {
+            sandbox.place_pixel_force(Pipe.into(), x, 1);
+        }
+        sandbox.tick();
+        // out of the far end of the pipe, against gravity
+        assert!(matches!(sandbox.pixels[11].pixel(), Pixel::Water(_)));
+        assert!(matches!(sandbox.pixels[6].pixel(), Pixel::Void(_)));
+
+        // nothing comes out of a pump facing a wall
+        let mut sandbox = Sandbox::new_with_rng(1, 3, new_rng());
+        sandbox.place_pixel_force(Water::default().into(), 0, 0);
+        sandbox.place_pixel_force(Pump::new(Direction::Down).into(), 0, 1);
+        sandbox.place_pixel_force(Rock.into(), 0, 2);
+        sandbox.tick();
+        assert!(matches!(sandbox.pixels[0].pixel(), Pixel::Water(_)));
+    }
+
+    #[test]
+    fn test_sandbox_freeze() {
+        let mut sandbox = Sandbox::new_with_rng(1, 4, new_rng());
+        sandbox.place_pixel_force(Sand.into(), 0, 0);
+        sandbox.place_pixel_force(Water::default().into(), 0, 2);
+        sandbox.queue_command(Command::Freeze {
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 1,
+            frozen: true,
+        });
+        sandbox.queue_command(Command::Freeze {
+            x: 0,
+            y: 3,
+            width: 1,
+            height: 1,
+            frozen: true,
+        });
+        for _ in 0..3 {
+            sandbox.tick();
+        }
+        // frozen pixels stay put, and nothing moves into frozen cells
+        assert!(matches!(sandbox.pixels[0].pixel(), Pixel::Sand(_)));
+        assert!(matches!(sandbox.pixels[2].pixel(), Pixel::Water(_)));
+        assert!(sandbox.is_frozen(0, 3));
+
+        sandbox.queue_command(Command::Freeze {
+            x: 0,
+            y: 0,
+            width: 1,
+            height: 4,
+            frozen: false,
+        });
+        for _ in 0..3 {
+            sandbox.tick();
+        }
+        // the sand sinks through the water once thawed
+        assert!(matches!(sandbox.pixels[3].pixel(), Pixel::Sand(_)));
+    }
+
+    #[test]
+    fn test_sandbox_transmute() {
+        let mut sandbox = Sandbox::new_with_rng(3, 1, new_rng());
+        sandbox.place_pixel_force(Water::default().into(), 0, 0);
+        sandbox.place_pixel_force(Rock.into(), 1, 0);
+        sandbox.place_pixel_force(Water::default().into(), 2, 0);
+        sandbox.pixels[2].fall = 3;
+        sandbox.queue_command(Command::Transmute {
+            from: Water::default().into(),
+            to: Lava::default().into(),
+            x: 0,
+            y: 0,
+            width: 3,
+            height: 1,
+        });
+        sandbox.apply_commands();
+
+        assert!(matches!(sandbox.pixels[0].pixel(), Pixel::Lava(_)));
+        assert!(matches!(sandbox.pixels[1].pixel(), Pixel::Rock(_)));
+        assert!(matches!(sandbox.pixels[2].pixel(), Pixel::Lava(_)));
+        assert_eq!(sandbox.pixels[2].fall, 3);
+    }
+
+    #[test]
+    fn test_sandbox_trigger_event() {
+        let mut sandbox = Sandbox::new_with_rng(3, 3, new_rng());
+        sandbox.queue_command(Command::TriggerEvent {
+            source: EternalFire.into(),
+            x: 0,
+            y: 0,
+        });
+        sandbox.tick();
+        let fire = sandbox
+            .pixels
+            .iter()
+            .filter(|p| p.pixel() != Void::default().into())
+            .count();
+        assert_eq!(fire, 1, "{:?}", &sandbox.pixels);
+    }
+
+    #[test]
+    fn test_sandbox_events() {
+        let mut sandbox = Sandbox::new_with_rng(3, 3, new_rng());
+        sandbox.place_pixel_force(Sand.into(), 1, 1);
+        for x in 0..3 {
+            sandbox.place_pixel_force(Water::default().into(), x, 2);
+        }
+        sandbox.tick();
+        assert!(sandbox
+            .events()
+            .iter()
+            .any(|e| e.kind == EventKind::Splash && (e.x, e.y) == (1, 2)));
+
+        // events only live for a single tick
+        sandbox.tick();
+        assert!(sandbox.events().iter().all(|e| e.kind != EventKind::Splash));
+
+        // sinking through a pool only splashes on the way in
+        let mut pool = Sandbox::new_with_rng(1, 8, new_rng());
+        for y in 1..8 {
+            pool.place_pixel_force(Water::default().into(), 0, y);
+        }
+        pool.place_pixel_force(Sand.into(), 0, 0);
+        let mut splashes = 0;
+        for _ in 0..10 {
+            pool.tick();
+            splashes += pool
+                .events()
+                .iter()
+                .filter(|e| e.kind == EventKind::Splash)
+                .count();
+        }
+        assert_eq!(pool.census().get("Sand"), Some(&1));
+        assert!(matches!(pool.pixels[7].pixel(), Pixel::Sand(_)));
+        assert_eq!(splashes, 1);
+
+        sandbox.queue_command(Command::TriggerEvent {
+            source: EternalFire.into(),
+            x: 0,
+            y: 0,
+        });
+        sandbox.tick();
+        assert!(sandbox
+            .events()
+            .iter()
+            .any(|e| e.kind == EventKind::Ignition && (e.x, e.y) == (0, 0)));
+    }
+
+    #[test]
+    fn test_sandbox_state_hash() {
+        let mut a = Sandbox::new_with_rng(3, 3, new_rng());
+        let mut b = Sandbox::new_with_rng(3, 3, new_rng());
+        assert_eq!(a.state_hash(), b.state_hash());
+
+        a.place_pixel_force(Water::default().into(), 1, 1);
+        b.place_pixel_force(Water::default().into(), 1, 1);
+        b.set_temperature(1, 1, 25);
+        assert_ne!(a.state_hash(), b.state_hash());
+
+        b.place_pixel_force(Water::default().into(), 1, 1);
+        assert_eq!(a.state_hash(), b.state_hash());
+
+        // moving pixels differ from resting ones
+        b.pixels[4].velocity = (2, 0);
+        assert_ne!(a.state_hash(), b.state_hash());
+
+        // kinds, which empty cells keep the element they become as, hash by name rather than
+        // by their position among the elements
+        let hash = |value: &dyn Fn(&mut StableHasher)| {
+            let mut hasher = StableHasher::default();
+            value(&mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(
+            hash(&|h| PixelKind::Void.hash(h)),
+            hash(&|h| "Void".hash(h))
+        );
+
+        let c = Sandbox::new_with_rng(9, 1, new_rng());
+        assert_ne!(
+            Sandbox::new_with_rng(3, 3, new_rng()).state_hash(),
+            c.state_hash()
+        );
+    }
+
+    #[test]
+    fn test_sandbox_splash() {
+        let splashes = |splash: bool| {
+            let mut sandbox = Sandbox::new_with_rng(3, 12, new_rng());
+            sandbox.set_config(SandboxConfig {
+                splash,
+                ..Default::default()
+            });
+            sandbox.place_pixel_force(Gravel.into(), 1, 0);
+            for x in 0..3 {
+                sandbox.place_pixel_force(Water::default().into(), x, 10);
+                sandbox.place_pixel_force(Water::default().into(), x, 11);
+            }
+
+            (0..12).any(|_| {
+                sandbox.tick();
+                sandbox
+                    .pixels
+                    .iter()
+                    .any(|c| matches!(c.pixel(), Pixel::Water(_)) && c.velocity().1 < 0)
+            })
+        };
+
+        assert!(splashes(true));
+        assert!(!splashes(false));
+    }
+
+    #[test]
+    fn test_sandbox_viscosity() {
+        let sink_time = |liquid: Pixel| {
+            let mut sandbox = Sandbox::<SmallRng>::new_with_seed(1, 12, 7);
+            sandbox.place_pixel_force(Gravel.into(), 0, 0);
+            for y in 1..12 {
+                sandbox.place_pixel_force(liquid, 0, y);
+            }
+            (1..200)
+                .find(|_| {
+                    sandbox.tick();
+                    matches!(sandbox.pixels[11].pixel(), Pixel::Gravel(_))
+                })
+                .unwrap()
+        };
+
+        assert!(sink_time(Lava::default().into()) > sink_time(Water::default().into()) * 2);
+    }
+
+    #[test]
+    fn test_sandbox_ambient_temperature() {
+        let run = |ambient: Option<i16>| {
+            let mut sandbox = Sandbox::new_with_rng(1, 2, new_rng());
+            sandbox.set_ambient_temperature(ambient);
+            sandbox.place_pixel_force(Water::default().into(), 0, 0);
+            sandbox.place_pixel_force(Rock.into(), 0, 1);
+            for _ in 0..Sandbox::<StepRng>::AMBIENT_PERIOD * 12 {
+                sandbox.tick();
+            }
+            (sandbox.pixels[0].pixel(), sandbox.temperature_at(0, 0))
+        };
+
+        assert!(matches!(run(None), (Pixel::Water(_), _)));
+        assert!(matches!(run(Some(-10)), (Pixel::Ice(_), _)));
+        assert!(matches!(run(Some(30)), (Pixel::Water(_), temp) if temp > temperature::ROOM));
+    }
+
+    #[test]
+    fn test_sandbox_insulation() {
+        // water between two walls of `wall`
+        let run = |wall: Pixel| {
+            let mut sandbox = Sandbox::new_with_rng(3, 1, new_rng());
+            sandbox.set_ambient_temperature(Some(60));
+            sandbox.place_pixel_force(wall, 0, 0);
+            sandbox.place_pixel_force(Water::default().into(), 1, 0);
+            sandbox.place_pixel_force(wall, 2, 0);
+            for _ in 0..Sandbox::<StepRng>::AMBIENT_PERIOD * 2 {
+                sandbox.tick();
+            }
+            match sandbox.pixels[1].pixel() {
+                Pixel::Water(_) => sandbox.temperature_at(1, 0),
+                pixel => panic!("{:?}", pixel),
+            }
+        };
+
+        let (wood, rock, wire) = (
+            run(Wood::default().into()),
+            run(Rock.into()),
+            run(Wire::default().into()),
+        );
+        assert_eq!(wood, temperature::ROOM);
+        assert!(rock > wood && wire > rock, "{} {} {}", wood, rock, wire);
+    }
+
+    #[test]
+    fn test_sandbox_far_field() {
+        let mut sandbox = Sandbox::new_with_rng(200, 10, new_rng());
+        sandbox.set_config(SandboxConfig {
+            far_field_period: Some(4),
+            ..Default::default()
+        });
+        sandbox.place_pixel_force(Sand.into(), 5, 0);
+        sandbox.place_pixel_force(Sand.into(), 190, 0);
+        sandbox.queue_command(Command::Focus {
+            x: 0,
+            y: 0,
+            width: 10,
+            height: 10,
+        });
+        for _ in 0..4 {
+            sandbox.tick();
+        }
+
+        assert!(matches!(
+            sandbox.pixels[sandbox.coordinates_to_index(5, 4)].pixel(),
+            Pixel::Sand(_)
+        ));
+        assert!(matches!(
+            sandbox.pixels[sandbox.coordinates_to_index(190, 1)].pixel(),
+            Pixel::Sand(_)
+        ));
+    }
+
+    #[test]
+    fn test_sandbox_entities() {
+        let mut sandbox = Sandbox::new_with_rng(10, 12, new_rng());
+        for x in 0..10 {
+            sandbox.place_pixel_force(Rock.into(), x, 11);
+            for y in 6..11 {
+                sandbox.place_pixel_force(Water::default().into(), x, y);
+            }
+        }
+        let boat = sandbox.spawn_entity(EntityKind::Boat, 2, 0).unwrap();
+        sandbox.queue_command(Command::SpawnEntity {
+            entity: EntityKind::Ball,
+            x: 0,
+            y: 0,
+        });
+        assert!(sandbox.spawn_entity(EntityKind::Ball, 0, 10).is_none());
+        for _ in 0..40 {
+            sandbox.tick();
+        }
+
+        // the boat rides on the surface, the ball sinks to the bottom
+        assert_eq!(sandbox.entity(boat).unwrap().y, 5);
+        let ball = sandbox.entities_at(0, 10).next().unwrap();
+        assert_eq!((ball.kind, ball.y), (EntityKind::Ball, 9));
+    }
+
+    #[test]
+    fn test_sandbox_player() {
+        let mut sandbox = Sandbox::new_with_rng(8, 6, new_rng());
+        for x in 0..8 {
+            sandbox.place_pixel_force(Rock.into(), x, 5);
+        }
+        for y in 0..5 {
+            sandbox.place_pixel_force(Sand.into(), 4, y);
+        }
+        let player = sandbox.spawn_entity(EntityKind::Player, 0, 0).unwrap();
+        sandbox.tick();
+        assert_eq!(sandbox.entity(player).unwrap().y, 1);
+
+        // walking into the sand digs through it
+        for _ in 0..4 {
+            sandbox.queue_command(Command::Steer {
+                entity: player,
+                direction: Direction::Right,
+            });
+            sandbox.tick();
+        }
+        assert_eq!(sandbox.entity(player).unwrap().x, 4);
+        assert!(matches!(
+            sandbox.pixels[sandbox.coordinates_to_index(4, 2)].pixel(),
+            Pixel::Void(_)
+        ));
+
+        sandbox.steer_entity(player, Direction::Up);
+        assert_eq!(sandbox.entity(player).unwrap().velocity.1, -3);
+
+        for y in 0..5 {
+            sandbox.place_pixel_force(EternalFire.into(), 7, y);
+        }
+        sandbox.entity_mut(player).unwrap().x = 6;
+        for _ in 0..20 {
+            sandbox.tick();
+        }
+        assert!(
+            sandbox.entity(player).is_none(),
+            "the player burnt to death"
         );
     }
 }
@@ -0,0 +1,367 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Context;
+use rand::rngs::SmallRng;
+use rand::Rng;
+use serde::Deserialize;
+
+use crate::event::EventKind;
+use crate::pixel::{by_name, Pixel, PixelFundamental, PixelKind};
+use crate::sandbox::Sandbox;
+use crate::scene::{self, Placement};
+
+/// Something that happens at a given tick of a scenario.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Step {
+    /// Tick at which the step runs
+    pub at: u64,
+    /// Caption shown from this step on, an empty string clears it
+    pub caption: Option<String>,
+    #[serde(default)]
+    pub place: Vec<Placement>,
+    /// Ask the frontend to pause until the user resumes
+    #[serde(default)]
+    pub pause: bool,
+}
+
+/// What makes a [`Hint`] show up.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Trigger {
+    /// Something catches fire
+    Ignition,
+    /// Water turns into ice
+    Freeze,
+    /// A solid falls into a liquid
+    Splash,
+    /// A falling pixel lands hard
+    Impact,
+    /// Lightning strikes from a cloud
+    Thunder,
+    /// A water wheel turns
+    Torque,
+    /// Night falls on the in-world clock, if the sandbox keeps one
+    Night,
+    /// The first pixel of an element is on the grid, e.g. `{ appears = "Steam" }`
+    Appears(#[serde(with = "by_name")] Pixel),
+}
+
+impl Trigger {
+    fn is_met<R: Rng>(&self, sandbox: &Sandbox<R>) -> bool {
+        let kind = match self {
+            Trigger::Ignition => EventKind::Ignition,
+            Trigger::Freeze => EventKind::Freeze,
+            Trigger::Splash => EventKind::Splash,
+            Trigger::Impact => EventKind::Impact,
+            Trigger::Thunder => EventKind::Thunder,
+            Trigger::Torque => EventKind::Torque,
+            Trigger::Night => return sandbox.world_time().is_some_and(|time| time.is_night()),
+            Trigger::Appears(pixel) => {
+                return sandbox
+                    .pixels
+                    .iter()
+                    .any(|container| container.pixel().name() == pixel.name())
+            }
+        };
+        sandbox.events().iter().any(|event| event.kind == kind)
+    }
+}
+
+/// Message shown once, the first time its trigger is met.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Hint {
+    pub when: Trigger,
+    pub message: String,
+}
+
+/// A scripted scene with a timeline of steps.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scenario {
+    pub name: String,
+    pub author: Option<String>,
+    /// Shown as the caption until a step replaces it
+    pub description: Option<String>,
+    pub width: usize,
+    pub height: usize,
+    /// How many ticks the scenario runs in total
+    pub duration: u64,
+    #[serde(default)]
+    pub scene: Vec<Placement>,
+    #[serde(default)]
+    pub timeline: Vec<Step>,
+    #[serde(default)]
+    pub hints: Vec<Hint>,
+    /// Elements that may be placed once the scene is built, by the player and the timeline
+    /// alike, every one when empty
+    #[serde(default, deserialize_with = "by_name::deserialize_all")]
+    pub elements: Vec<Pixel>,
+}
+
+impl Scenario {
+    pub fn from_toml(s: &str) -> anyhow::Result<Self> {
+        let mut scenario: Self = toml::from_str(s)?;
+        scenario.timeline.sort_by_key(|step| step.at);
+        Ok(scenario)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read scenario {}", path.display()))?;
+        Self::from_toml(&s).with_context(|| format!("failed to parse scenario {}", path.display()))
+    }
+
+    /// The built-in physics demo campaign, in the order it should be played.
+    pub fn demos() -> Vec<Scenario> {
+        [
+            include_str!("../scenarios/density_sorting.toml"),
+            include_str!("../scenarios/water_cycle.toml"),
+            include_str!("../scenarios/combustion_chain.toml"),
+            include_str!("../scenarios/volcano.toml"),
+        ]
+        .into_iter()
+        .map(|s| Self::from_toml(s).expect("builtin scenario must be valid"))
+        .collect()
+    }
+}
+
+/// Where the time of a [`Sandbox::run_scenario`] went.
+#[derive(Debug, Default, Clone)]
+pub struct ScenarioReport {
+    pub ticks: u64,
+    pub movement: Duration,
+    pub interaction: Duration,
+    /// Applying commands, computing gravity and resetting the grid for the next tick
+    pub bookkeeping: Duration,
+    /// Cells holding each element once the run is over
+    pub census: BTreeMap<&'static str, usize>,
+}
+
+impl ScenarioReport {
+    pub fn total(&self) -> Duration {
+        self.movement + self.interaction + self.bookkeeping
+    }
+
+    pub fn ticks_per_second(&self) -> f64 {
+        self.ticks as f64 / self.total().as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+/// Plays a [`Scenario`]'s timeline against a sandbox.
+#[derive(Debug)]
+pub struct ScenarioPlayer {
+    scenario: Scenario,
+    ticks: u64,
+    next_step: usize,
+    caption: Option<String>,
+    /// Whether every hint of the scenario has been shown
+    shown: Vec<bool>,
+    /// Messages of the hints triggered since the frontend last took them
+    hints: Vec<String>,
+}
+
+impl ScenarioPlayer {
+    pub fn new(scenario: Scenario) -> Self {
+        Self {
+            caption: scenario.description.clone(),
+            shown: vec![false; scenario.hints.len()],
+            hints: Vec::new(),
+            scenario,
+            ticks: 0,
+            next_step: 0,
+        }
+    }
+
+    pub fn scenario(&self) -> &Scenario {
+        &self.scenario
+    }
+
+    pub fn caption(&self) -> Option<&str> {
+        self.caption.as_deref()
+    }
+
+    /// Takes the messages of the hints triggered since the last call.
+    pub fn take_hints(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.hints)
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.ticks >= self.scenario.duration
+    }
+
+    /// Creates a sandbox holding the scenario's starting scene.
+    pub fn create_sandbox(&self) -> Sandbox<SmallRng> {
+        let mut sandbox = scene::build_sandbox(
+            self.scenario.width,
+            self.scenario.height,
+            &self.scenario.scene,
+        );
+        if !self.scenario.elements.is_empty() {
+            sandbox.set_allowed_elements(Some(
+                self.scenario.elements.iter().map(PixelKind::from).collect(),
+            ));
+        }
+        sandbox
+    }
+
+    /// Queues the placements of the steps that are due, returns whether one of them asked for a
+    /// pause.
+    fn run_steps<R: Rng>(&mut self, sandbox: &mut Sandbox<R>) -> bool {
+        let mut pause = false;
+        while let Some(step) = self.scenario.timeline.get(self.next_step) {
+            if step.at > self.ticks {
+                break;
+            }
+            self.next_step += 1;
+
+            if let Some(caption) = &step.caption {
+                self.caption = (!caption.is_empty()).then(|| caption.clone());
+            }
+            for placement in &step.place {
+                for command in placement.commands() {
+                    sandbox.queue_command(command);
+                }
+            }
+            pause |= step.pause;
+        }
+        pause
+    }
+
+    /// Queues the hints whose trigger `sandbox` met on its last tick.
+    fn observe<R: Rng>(&mut self, sandbox: &Sandbox<R>) {
+        for (hint, shown) in self.scenario.hints.iter().zip(self.shown.iter_mut()) {
+            if !*shown && hint.when.is_met(sandbox) {
+                *shown = true;
+                self.hints.push(hint.message.clone());
+            }
+        }
+    }
+
+    /// Runs the steps that are due, then ticks `sandbox`.
+    ///
+    /// Returns `true` when a step asked for a pause, the sandbox is not ticked in that case so
+    /// the user gets to see the scene as the step left it.
+    pub fn tick<R: Rng>(&mut self, sandbox: &mut Sandbox<R>) -> bool {
+        if self.run_steps(sandbox) {
+            sandbox.apply_commands();
+            return true;
+        }
+
+        sandbox.tick();
+        self.ticks += 1;
+        self.observe(sandbox);
+        false
+    }
+
+    /// Runs the steps that are due, ignoring pauses, then ticks `sandbox` timing every phase.
+    pub(crate) fn tick_timed<R: Rng>(
+        &mut self,
+        sandbox: &mut Sandbox<R>,
+        report: &mut ScenarioReport,
+    ) {
+        self.run_steps(sandbox);
+        sandbox.tick_timed(report);
+        self.ticks += 1;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+
+    use crate::sandbox::Sandbox;
+    use crate::scenario::{Scenario, ScenarioPlayer};
+
+    const SCENARIO: &str = r#"
+        name = "test"
+        description = "a test"
+        width = 3
+        height = 3
+        duration = 3
+
+        [[timeline]]
+        at = 1
+        caption = "sand"
+        pause = true
+        place = [{ pixel = "Sand", x = 1, y = 0, width = 1, height = 1 }]
+
+        [[timeline]]
+        at = 0
+        caption = "hello"
+    "#;
+
+    #[test]
+    fn test_builtin_demos() {
+        assert!(!Scenario::demos().is_empty());
+    }
+
+    #[test]
+    fn test_scenario_player() {
+        let mut player = ScenarioPlayer::new(Scenario::from_toml(SCENARIO).unwrap());
+        let mut sandbox = player.create_sandbox();
+        assert_eq!(player.caption(), Some("a test"));
+
+        assert!(!player.tick(&mut sandbox));
+        assert_eq!(player.caption(), Some("hello"));
+
+        // the pause step places its pixels without ticking
+        assert!(player.tick(&mut sandbox));
+        assert_eq!(player.caption(), Some("sand"));
+        assert_eq!(sandbox.census().get("Sand"), Some(&1));
+        let idx = sandbox.coordinates_to_index(1, 0);
+        assert_ne!(sandbox.pixels[idx].pixel(), Default::default());
+
+        assert!(!player.tick(&mut sandbox));
+        assert!(!player.tick(&mut sandbox));
+        assert!(player.is_finished());
+    }
+
+    #[test]
+    fn test_scenario_hints() {
+        let scenario = r#"
+            name = "hints"
+            width = 3
+            height = 3
+            duration = 10
+
+            [[timeline]]
+            at = 2
+            place = [{ pixel = "Steam", x = 1, y = 2, width = 1, height = 1 }]
+
+            [[hints]]
+            when = { appears = "Steam" }
+            message = "water boils near fire"
+
+            [[hints]]
+            when = "ignition"
+            message = "fire"
+        "#;
+        let mut player = ScenarioPlayer::new(Scenario::from_toml(scenario).unwrap());
+        let mut sandbox = player.create_sandbox();
+
+        player.tick(&mut sandbox);
+        player.tick(&mut sandbox);
+        assert!(player.take_hints().is_empty());
+
+        player.tick(&mut sandbox);
+        assert_eq!(player.take_hints(), ["water boils near fire"]);
+        // hints only show up once
+        player.tick(&mut sandbox);
+        assert!(player.take_hints().is_empty());
+
+        assert!(Scenario::from_toml(&scenario.replace("Steam\" }", "Stean\" }")).is_err());
+    }
+
+    #[test]
+    fn test_run_scenario() {
+        let report = Sandbox::<SmallRng>::run_scenario(Scenario::from_toml(SCENARIO).unwrap(), 5);
+        assert_eq!(report.ticks, 5);
+        assert_eq!(
+            report.total(),
+            report.movement + report.interaction + report.bookkeeping
+        );
+        assert!(report.ticks_per_second() > 0.0);
+    }
+}
@@ -0,0 +1,292 @@
+use std::path::Path;
+
+use anyhow::Context;
+use rand::rngs::SmallRng;
+use serde::Deserialize;
+
+use crate::command::Command;
+use crate::pixel::{by_name, Pixel};
+use crate::sandbox::Sandbox;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+pub struct Region {
+    pub x: usize,
+    pub y: usize,
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Region {
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        (self.x..self.x + self.width).contains(&x) && (self.y..self.y + self.height).contains(&y)
+    }
+}
+
+/// Cells a placement fills, told apart by the fields given.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Deserialize)]
+#[serde(untagged)]
+pub enum Shape {
+    /// `x`, `y`, `width` and `height`
+    Rect(Region),
+    /// Disc centred on (`x`, `y`), cut off at the top and left borders
+    Circle { x: usize, y: usize, radius: usize },
+    /// Cells on the straight line from one `[x, y]` to another, both ends included
+    Line { from: [usize; 2], to: [usize; 2] },
+}
+
+impl Shape {
+    /// Whether the shape lies in a grid of `width` by `height` cells, discs only needing their
+    /// centre in it and to be no wider than the grid.
+    fn fits(&self, width: usize, height: usize) -> bool {
+        let is_inside = |x: usize, y: usize| x < width && y < height;
+        match *self {
+            Shape::Rect(region) => {
+                is_inside(region.x, region.y)
+                    && region.width <= width - region.x
+                    && region.height <= height - region.y
+            }
+            Shape::Circle { x, y, radius } => is_inside(x, y) && radius <= width.max(height),
+            Shape::Line { from, to } => is_inside(from[0], from[1]) && is_inside(to[0], to[1]),
+        }
+    }
+
+    /// Rows of the shape, as rectangles one cell high, or single cells for lines.
+    fn regions(&self) -> Vec<Region> {
+        match *self {
+            Shape::Rect(region) => vec![region],
+            Shape::Circle { x, y, radius } => {
+                let r = radius as isize;
+                (-r..=r)
+                    .filter_map(|dy| {
+                        let y = usize::try_from(y as isize + dy).ok()?;
+                        let half = ((r * r - dy * dy) as f64).sqrt() as usize;
+                        let left = x.saturating_sub(half);
+                        Some(Region {
+                            x: left,
+                            y,
+                            width: x + half - left + 1,
+                            height: 1,
+                        })
+                    })
+                    .collect()
+            }
+            Shape::Line { from, to } => {
+                // Bresenham's, stepping along both axes on the diagonal
+                let (mut x, mut y) = (from[0] as isize, from[1] as isize);
+                let (dx, dy) = ((to[0] as isize - x).abs(), -(to[1] as isize - y).abs());
+                let (sx, sy) = ((to[0] as isize - x).signum(), (to[1] as isize - y).signum());
+                let mut err = dx + dy;
+                let mut cells = vec![];
+                loop {
+                    cells.push(Region {
+                        x: x as usize,
+                        y: y as usize,
+                        width: 1,
+                        height: 1,
+                    });
+                    if (x, y) == (to[0] as isize, to[1] as isize) {
+                        break cells;
+                    }
+                    let e2 = 2 * err;
+                    if e2 >= dy {
+                        err += dy;
+                        x += sx;
+                    }
+                    if e2 <= dx {
+                        err += dx;
+                        y += sy;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// A shape filled with a pixel.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Placement {
+    #[serde(with = "by_name")]
+    pub pixel: Pixel,
+    #[serde(flatten)]
+    pub shape: Shape,
+}
+
+impl Placement {
+    /// Commands forcing the pixel into every cell of the shape.
+    pub fn commands(&self) -> impl Iterator<Item = Command> + '_ {
+        self.shape.regions().into_iter().map(
+            |Region {
+                 x,
+                 y,
+                 width,
+                 height,
+             }| Command::PlaceBrush {
+                pixel: self.pixel,
+                x,
+                y,
+                width,
+                height,
+                force: true,
+            },
+        )
+    }
+}
+
+/// Creates a sandbox with `placements` already applied.
+pub fn build_sandbox(width: usize, height: usize, placements: &[Placement]) -> Sandbox<SmallRng> {
+    let mut sandbox = Sandbox::<SmallRng>::new(width, height);
+    for placement in placements {
+        placement
+            .commands()
+            .for_each(|command| sandbox.queue_command(command));
+    }
+    sandbox.apply_commands();
+    sandbox
+}
+
+/// A starting world written by hand, its placements drawn in order over each other.
+#[derive(Debug, Clone, Deserialize)]
+pub struct Scene {
+    pub width: usize,
+    pub height: usize,
+    /// Makes the world play out the same on every run, random if unset
+    pub seed: Option<u64>,
+    #[serde(default)]
+    pub placements: Vec<Placement>,
+}
+
+impl Scene {
+    /// Most cells along either side of a scene
+    pub const MAX_SIZE: usize = 4096;
+
+    /// Parses a scene, failing if its size is outside of 1 to [`Scene::MAX_SIZE`] or a
+    /// placement doesn't lie in it.
+    pub fn from_toml(s: &str) -> anyhow::Result<Self> {
+        let scene: Self = toml::from_str(s)?;
+        for side in [scene.width, scene.height] {
+            if !(1..=Self::MAX_SIZE).contains(&side) {
+                anyhow::bail!(
+                    "scene sides must be from 1 to {} cells, got {}",
+                    Self::MAX_SIZE,
+                    side
+                );
+            }
+        }
+        if let Some(idx) = scene
+            .placements
+            .iter()
+            .position(|p| !p.shape.fits(scene.width, scene.height))
+        {
+            anyhow::bail!(
+                "placement {} doesn't lie in the {}x{} scene",
+                idx + 1,
+                scene.width,
+                scene.height
+            );
+        }
+        Ok(scene)
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read scene {}", path.display()))?;
+        Self::from_toml(&s).with_context(|| format!("failed to parse scene {}", path.display()))
+    }
+
+    /// Creates a sandbox holding the scene.
+    pub fn build(&self) -> Sandbox<SmallRng> {
+        let mut sandbox = match self.seed {
+            Some(seed) => Sandbox::<SmallRng>::new_with_seed(self.width, self.height, seed),
+            None => Sandbox::<SmallRng>::new(self.width, self.height),
+        };
+        for placement in &self.placements {
+            placement
+                .commands()
+                .for_each(|command| sandbox.queue_command(command));
+        }
+        sandbox.apply_commands();
+        sandbox
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::pixel::PixelFundamental;
+    use crate::scene::Scene;
+
+    #[test]
+    fn test_scene_shapes() {
+        // a floor, a disc of ice and a line of wood drawn over it
+        let scene = Scene::from_toml(
+            r#"
+            width = 7
+            height = 7
+            seed = 3
+
+            [[placements]]
+            pixel = "Rock"
+            x = 0
+            y = 6
+            width = 7
+            height = 1
+
+            [[placements]]
+            pixel = "Ice"
+            x = 3
+            y = 3
+            radius = 2
+
+            [[placements]]
+            pixel = "Wood"
+            from = [0, 0]
+            to = [6, 3]
+            "#,
+        )
+        .unwrap();
+        let sandbox = scene.build();
+        let rows = sandbox
+            .pixels
+            .chunks(sandbox.width)
+            .map(|row| {
+                row.iter()
+                    .map(|p| match p.pixel().name() {
+                        "Void" => '.',
+                        name => name.chars().next().unwrap(),
+                    })
+                    .collect::<String>()
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            rows,
+            [
+                "W......", //
+                ".WWI...", //
+                "..IWW..", //
+                ".IIIIWW", //
+                "..III..", //
+                "...I...", //
+                "RRRRRRR", //
+            ]
+        );
+    }
+
+    #[test]
+    fn test_scene_bounds() {
+        let scene = |size: &str, placement: &str| {
+            Scene::from_toml(&format!(
+                "{}\n[[placements]]\npixel = \"Rock\"\n{}",
+                size, placement
+            ))
+        };
+        let size = "width = 10\nheight = 10";
+        assert!(scene(size, "x = 0\ny = 0\nwidth = 10\nheight = 10").is_ok());
+        assert!(scene(size, "x = 5\ny = 5\nradius = 8").is_ok());
+        assert!(scene("width = 0\nheight = 10", "x = 0\ny = 0\nradius = 1").is_err());
+        assert!(scene("width = 10\nheight = 5000", "x = 0\ny = 0\nradius = 1").is_err());
+        assert!(scene(size, "x = 5\ny = 5\nwidth = 6\nheight = 1").is_err());
+        assert!(scene(size, "x = 5\ny = 5\nradius = 4000000000").is_err());
+        assert!(scene(size, "x = 10\ny = 5\nradius = 1").is_err());
+        assert!(scene(size, "from = [0, 0]\nto = [0, 10]").is_err());
+    }
+}
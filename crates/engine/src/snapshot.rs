@@ -0,0 +1,523 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use rand::rngs::SmallRng;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use toml::{Table, Value};
+
+use crate::catalog;
+use crate::config::SandboxConfig;
+use crate::entity::Entity;
+use crate::palette::Rgb;
+use crate::pixel::{Pixel, PixelFundamental, PixelKind};
+use crate::sandbox::Sandbox;
+use crate::versioned::Versioned;
+
+/// Hotkeys of the elements when snapshots were saved without a legend.
+const V1_HOTKEYS: &[(char, &str)] = &[
+    ('0', "Void"),
+    ('1', "Sand"),
+    ('2', "Rock"),
+    ('3', "Water"),
+    ('4', "Steam"),
+    ('5', "Ice"),
+    ('6', "Fire"),
+    ('7', "Eternal fire"),
+    ('8', "Wood"),
+    ('9', "Lava"),
+    ('b', "Battery"),
+    ('c', "Switch"),
+    ('e', "Wire"),
+    ('g', "Gravel"),
+    ('h', "Levitation field"),
+    ('i', "Vine"),
+    ('j', "Salt"),
+    ('k', "Dust"),
+    ('m', "Mud"),
+    ('n', "Foam"),
+    ('o', "Obsidian"),
+    ('p', "Poison"),
+    ('q', "Pump"),
+    ('r', "Grass"),
+    ('s', "Soil"),
+    ('t', "Timer"),
+    ('u', "Fungus"),
+    ('w', "Poisoned water"),
+    ('x', "Toxic gas"),
+    ('y', "Pipe"),
+    ('z', "Spawner"),
+];
+
+/// What the scene of a snapshot is and the rules it plays by, restored along with the cells.
+#[derive(Debug, Default, Clone, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SceneMeta {
+    pub name: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    /// Settings of the sandbox, like the ambient temperature and the air drag
+    pub config: SandboxConfig,
+    /// Names of the elements that may be placed, every one if unset
+    pub elements: Option<Vec<String>>,
+}
+
+/// The elements on a grid at a point in time, saved as `.rfs` files.
+///
+/// Only the element of each cell is kept along with where the composites and entities are, so a
+/// snapshot can't be resumed exactly, but it is small and readable enough to compare two runs.
+/// Restoring one brings back the pixels with their default state and no heat.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub width: usize,
+    pub height: usize,
+    /// Ticks that had passed when the snapshot was taken
+    pub ticks: u64,
+    /// Top to bottom, with a character of the legend for every cell
+    pub rows: Vec<String>,
+    /// Element name of every character used in the rows
+    pub legend: BTreeMap<String, String>,
+    /// Top left cell of every whole composite, which the rows can't tell apart when they touch
+    #[serde(default)]
+    pub composites: Vec<(usize, usize)>,
+    #[serde(default)]
+    pub entities: Vec<Entity>,
+    #[serde(default)]
+    pub scene: SceneMeta,
+}
+
+impl Versioned for Snapshot {
+    const KIND: &'static str = "snapshot";
+    const VERSION: u32 = 2;
+
+    fn migrate(table: &mut Table, version: u32) -> anyhow::Result<()> {
+        match version {
+            // rows used the hotkeys of the time, which may have been reassigned since
+            1 => {
+                let legend = V1_HOTKEYS
+                    .iter()
+                    .map(|(hotkey, name)| (hotkey.to_string(), Value::from(*name)))
+                    .collect::<Table>();
+                table.insert("legend".to_string(), Value::Table(legend));
+                Ok(())
+            }
+            _ => Err(anyhow!("unknown snapshot version {}", version)),
+        }
+    }
+}
+
+/// How a cell differs between two snapshots.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum CellChange {
+    Unchanged(PixelKind),
+    /// Empty before
+    Added(PixelKind),
+    /// Empty after
+    Removed(PixelKind),
+    Changed {
+        from: PixelKind,
+        to: PixelKind,
+    },
+}
+
+/// Cell by cell comparison of two snapshots of the same size.
+#[derive(Debug, Clone)]
+pub struct SnapshotDiff {
+    pub width: usize,
+    pub height: usize,
+    /// Row major
+    pub cells: Vec<CellChange>,
+}
+
+impl Snapshot {
+    pub fn capture<R: Rng>(sandbox: &Sandbox<R>) -> Self {
+        let mut legend = BTreeMap::new();
+        let rows = sandbox
+            .pixels
+            .chunks(sandbox.width)
+            .map(|row| {
+                row.iter()
+                    .map(|container| {
+                        let element = catalog::element(&container.pixel());
+                        legend
+                            .entry(element.hotkey.to_string())
+                            .or_insert_with(|| element.name.to_string());
+                        element.hotkey
+                    })
+                    .collect()
+            })
+            .collect();
+        let composites = (0..sandbox.pixels.len())
+            .filter(|&idx| {
+                let pixel = sandbox.pixels[idx].pixel();
+                pixel.footprint().is_some()
+                    && pixel.part() == 0
+                    && sandbox.composite_cells(idx).is_some()
+            })
+            .map(|idx| sandbox.index_to_coordinates(idx))
+            .collect();
+        Self {
+            version: Self::VERSION,
+            width: sandbox.width,
+            height: sandbox.height,
+            ticks: sandbox.tick_count(),
+            rows,
+            legend,
+            composites,
+            entities: sandbox.entities().to_vec(),
+            scene: SceneMeta {
+                config: sandbox.config(),
+                elements: sandbox.allowed_elements().map(|elements| {
+                    elements
+                        .iter()
+                        .map(|kind| kind.pixel().name().to_string())
+                        .collect()
+                }),
+                ..SceneMeta::default()
+            },
+        }
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read snapshot {}", path.display()))?;
+        let snapshot = Self::from_versioned_toml(&s)
+            .with_context(|| format!("failed to parse snapshot {}", path.display()))?;
+        snapshot
+            .kinds()
+            .with_context(|| format!("invalid snapshot {}", path.display()))?;
+        Ok(snapshot)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        std::fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("failed to write snapshot {}", path.display()))
+    }
+
+    /// Element of every cell, row major.
+    pub fn kinds(&self) -> anyhow::Result<Vec<PixelKind>> {
+        if self.rows.len() != self.height {
+            return Err(anyhow!(
+                "expected {} rows, got {}",
+                self.height,
+                self.rows.len()
+            ));
+        }
+        let mut kinds = Vec::with_capacity(self.width * self.height);
+        for (y, row) in self.rows.iter().enumerate() {
+            if row.chars().count() != self.width {
+                return Err(anyhow!("row {} is not {} cells wide", y, self.width));
+            }
+            for c in row.chars() {
+                let name = self
+                    .legend
+                    .get(c.encode_utf8(&mut [0; 4]) as &str)
+                    .ok_or_else(|| anyhow!("{:?} on row {} is not in the legend", c, y))?;
+                let pixel = Pixel::from_name(name)
+                    .ok_or_else(|| anyhow!("unknown element {} on row {}", name, y))?;
+                kinds.push((&pixel).into());
+            }
+        }
+        Ok(kinds)
+    }
+
+    /// Creates a sandbox holding the elements and entities of the snapshot, each element in its
+    /// default state, playing by the settings and allowed elements of its scene.
+    pub fn restore(&self) -> anyhow::Result<Sandbox<SmallRng>> {
+        let mut sandbox = Sandbox::<SmallRng>::new(self.width, self.height);
+        sandbox.set_config(self.scene.config);
+        if let Some(names) = &self.scene.elements {
+            let elements = names
+                .iter()
+                .map(|name| {
+                    Pixel::from_name(name)
+                        .map(|pixel| PixelKind::from(&pixel))
+                        .ok_or_else(|| anyhow!("unknown allowed element {}", name))
+                })
+                .collect::<anyhow::Result<_>>()?;
+            sandbox.set_allowed_elements(Some(elements));
+        }
+        let kinds = self.kinds()?;
+        for (idx, kind) in kinds.iter().enumerate() {
+            if *kind != PixelKind::Void {
+                let (x, y) = sandbox.index_to_coordinates(idx);
+                sandbox.place_pixel_force(kind.pixel(), x, y);
+            }
+        }
+        for &(x, y) in &self.composites {
+            let kind = kinds
+                .get(y * self.width + x)
+                .filter(|_| x < self.width)
+                .ok_or_else(|| anyhow!("composite at {}, {} is out of bounds", x, y))?;
+            sandbox
+                .place_composite(kind.pixel(), x, y, true)
+                .map_err(|e| anyhow!("can't restore the composite at {}, {}: {:?}", x, y, e))?;
+        }
+        sandbox.set_entities(self.entities.clone());
+        Ok(sandbox)
+    }
+
+    /// The scene shrunk to `width` by `height` cells, row by row, e.g. to preview it in a menu.
+    ///
+    /// Every cell has the catalog colour of the element filling most of the block it covers,
+    /// and is only empty when the whole block is.
+    pub fn thumbnail(&self, width: usize, height: usize) -> anyhow::Result<Vec<Vec<Rgb>>> {
+        let kinds = self.kinds()?;
+        // cells of the snapshot covered by the `i`th of `count` thumbnail cells along `size`
+        let block = |i: usize, count: usize, size: usize| {
+            let start = (i * size / count).min(size.saturating_sub(1));
+            start..((i + 1) * size / count).clamp(start + 1, size.max(start + 1))
+        };
+        let void = catalog::element(&Pixel::default()).color;
+        let rows = (0..height)
+            .map(|ty| {
+                (0..width)
+                    .map(|tx| {
+                        let mut counts: Vec<(PixelKind, usize)> = Vec::new();
+                        for y in block(ty, height, self.height) {
+                            for x in block(tx, width, self.width) {
+                                let Some(&kind) = kinds.get(y * self.width + x) else {
+                                    continue;
+                                };
+                                if kind == PixelKind::Void {
+                                    continue;
+                                }
+                                match counts.iter_mut().find(|(k, _)| *k == kind) {
+                                    Some((_, count)) => *count += 1,
+                                    None => counts.push((kind, 1)),
+                                }
+                            }
+                        }
+                        // the first element found wins a tie
+                        counts
+                            .iter()
+                            .rev()
+                            .max_by_key(|(_, count)| *count)
+                            .map_or(void, |(kind, _)| catalog::element(&kind.pixel()).color)
+                    })
+                    .collect()
+            })
+            .collect();
+        Ok(rows)
+    }
+
+    /// Compares the cells of `self` with the ones of `after`.
+    pub fn diff(&self, after: &Snapshot) -> anyhow::Result<SnapshotDiff> {
+        if (self.width, self.height) != (after.width, after.height) {
+            return Err(anyhow!(
+                "can't compare a {}x{} snapshot with a {}x{} one",
+                self.width,
+                self.height,
+                after.width,
+                after.height
+            ));
+        }
+        let is_void = |kind: PixelKind| matches!(kind.pixel(), Pixel::Void(_));
+        let cells = self
+            .kinds()?
+            .into_iter()
+            .zip(after.kinds()?)
+            .map(|(from, to)| match (from, to) {
+                (from, to) if from == to => CellChange::Unchanged(to),
+                (from, to) if is_void(from) => CellChange::Added(to),
+                (from, to) if is_void(to) => CellChange::Removed(from),
+                (from, to) => CellChange::Changed { from, to },
+            })
+            .collect();
+        Ok(SnapshotDiff {
+            width: self.width,
+            height: self.height,
+            cells,
+        })
+    }
+}
+
+impl SnapshotDiff {
+    pub fn added(&self) -> usize {
+        self.count(|change| matches!(change, CellChange::Added(_)))
+    }
+
+    pub fn removed(&self) -> usize {
+        self.count(|change| matches!(change, CellChange::Removed(_)))
+    }
+
+    pub fn changed(&self) -> usize {
+        self.count(|change| matches!(change, CellChange::Changed { .. }))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells
+            .iter()
+            .all(|change| matches!(change, CellChange::Unchanged(_)))
+    }
+
+    fn count(&self, f: impl Fn(&CellChange) -> bool) -> usize {
+        self.cells.iter().filter(|change| f(change)).count()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+
+    use crate::catalog;
+    use crate::command::Command;
+    use crate::entity::EntityKind;
+    use crate::pixel::boulder::Boulder;
+    use crate::pixel::rock::Rock;
+    use crate::pixel::sand::Sand;
+    use crate::pixel::void::Void;
+    use crate::pixel::water::Water;
+    use crate::pixel::{Pixel, PixelKind};
+    use crate::sandbox::Sandbox;
+    use crate::snapshot::{CellChange, Snapshot};
+    use crate::versioned::Versioned;
+
+    #[test]
+    fn test_snapshot_diff() {
+        let mut sandbox = Sandbox::<SmallRng>::new(3, 2);
+        sandbox.place_pixel_force(Sand.into(), 0, 0);
+        sandbox.place_pixel_force(Rock.into(), 1, 1);
+        let before =
+            Snapshot::from_versioned_toml(&toml::to_string(&Snapshot::capture(&sandbox)).unwrap())
+                .unwrap();
+
+        sandbox.place_pixel_force(Water::default().into(), 2, 0);
+        sandbox.place_pixel_force(Sand.into(), 1, 1);
+        sandbox.place_pixel_force(Void::default().into(), 0, 0);
+        let diff = before.diff(&Snapshot::capture(&sandbox)).unwrap();
+
+        assert_eq!(diff.cells[0], CellChange::Removed(PixelKind::Sand));
+        assert_eq!(diff.cells[2], CellChange::Added(PixelKind::Water));
+        assert_eq!(
+            diff.cells[4],
+            CellChange::Changed {
+                from: PixelKind::Rock,
+                to: PixelKind::Sand
+            }
+        );
+        assert_eq!((diff.added(), diff.removed(), diff.changed()), (1, 1, 1));
+        assert!(before.diff(&before).unwrap().is_empty());
+        assert!(before
+            .diff(&Snapshot::capture(&Sandbox::<SmallRng>::new(2, 2)))
+            .is_err());
+    }
+
+    #[test]
+    fn test_snapshot_restore() {
+        let mut sandbox = Sandbox::<SmallRng>::new(3, 2);
+        sandbox.place_pixel_force(Sand.into(), 0, 1);
+        sandbox.place_pixel_force(Water::default().into(), 2, 0);
+        let snapshot = Snapshot::capture(&sandbox);
+
+        let restored = snapshot.restore().unwrap();
+        assert_eq!((restored.width, restored.height), (3, 2));
+        assert!(snapshot
+            .diff(&Snapshot::capture(&restored))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_restore_composites() {
+        // two boulders side by side look the same as one wide boulder in the rows
+        let mut sandbox = Sandbox::<SmallRng>::new(6, 4);
+        for x in 0..4 {
+            sandbox.place_pixel_force(Rock.into(), x, 3);
+        }
+        for x in [0, 2] {
+            sandbox
+                .place_composite(Boulder::default().into(), x, 1, false)
+                .unwrap();
+        }
+        sandbox.queue_command(Command::SpawnEntity {
+            entity: EntityKind::Ball,
+            x: 5,
+            y: 0,
+        });
+        sandbox.tick();
+        let snapshot =
+            Snapshot::from_versioned_toml(&toml::to_string(&Snapshot::capture(&sandbox)).unwrap())
+                .unwrap();
+        assert_eq!(snapshot.composites, [(0, 1), (2, 1)]);
+
+        let mut restored = snapshot.restore().unwrap();
+        assert_eq!(restored.entities(), sandbox.entities());
+        // snapshots leave the temperatures out
+        let pixels = |sandbox: &Sandbox<SmallRng>| {
+            sandbox.pixels.iter().map(|p| p.pixel()).collect::<Vec<_>>()
+        };
+        assert_eq!(pixels(&restored), pixels(&sandbox));
+        // the boulders hold together rather than breaking into gravel
+        restored.tick();
+        assert!(snapshot
+            .diff(&Snapshot::capture(&restored))
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn test_snapshot_thumbnail() {
+        let mut sandbox = Sandbox::<SmallRng>::new(4, 4);
+        sandbox.place_pixel_force(Sand.into(), 0, 0);
+        for x in 0..4 {
+            sandbox.place_pixel_force(Water::default().into(), x, 3);
+        }
+        let thumbnail = Snapshot::capture(&sandbox).thumbnail(2, 2).unwrap();
+
+        let color = |pixel: Pixel| catalog::element(&pixel).color;
+        assert_eq!(
+            thumbnail,
+            [
+                [color(Sand.into()), color(Void::default().into())],
+                [color(Water::default().into()); 2],
+            ]
+        );
+        // a thumbnail larger than the scene repeats its cells
+        let thumbnail = Snapshot::capture(&sandbox).thumbnail(8, 1).unwrap();
+        assert_eq!(thumbnail[0].len(), 8);
+    }
+
+    #[test]
+    fn test_snapshot_scene() {
+        let mut sandbox = Sandbox::<SmallRng>::new(3, 3);
+        sandbox.set_ambient_temperature(Some(40));
+        sandbox.set_allowed_elements(Some(vec![PixelKind::Sand, PixelKind::EternalFire]));
+        let mut snapshot = Snapshot::capture(&sandbox);
+        assert_eq!(
+            snapshot.scene.elements.as_deref(),
+            Some(&["Sand".to_string(), "Eternal fire".to_string()][..])
+        );
+
+        snapshot.scene.author = Some("someone".to_string());
+        let snapshot = Snapshot::from_versioned_toml(&toml::to_string(&snapshot).unwrap()).unwrap();
+        assert_eq!(snapshot.scene.author.as_deref(), Some("someone"));
+        let restored = snapshot.restore().unwrap();
+        assert_eq!(restored.config(), sandbox.config());
+        assert_eq!(restored.allowed_elements(), sandbox.allowed_elements());
+    }
+
+    #[test]
+    fn test_snapshot_versions() {
+        let v1 = r#"
+            width = 2
+            height = 1
+            ticks = 0
+            rows = ["13"]
+        "#;
+        let snapshot = Snapshot::from_versioned_toml(v1).unwrap();
+        assert_eq!(snapshot.version, Snapshot::VERSION);
+        assert_eq!(
+            snapshot.kinds().unwrap(),
+            [PixelKind::Sand, PixelKind::Water]
+        );
+
+        let newer = format!("version = {}\n{}", Snapshot::VERSION + 1, v1);
+        let error = Snapshot::from_versioned_toml(&newer).unwrap_err();
+        assert!(error.to_string().contains("newer release"));
+    }
+}
@@ -0,0 +1,223 @@
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::Duration;
+
+use anyhow::Context;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::command::Command;
+use crate::event::EventKind;
+use crate::pixel::{Pixel, PixelFundamental};
+use crate::sandbox::Sandbox;
+
+/// Cumulative statistics kept across sessions.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Stats {
+    /// Pixels placed per element name
+    pub placed: BTreeMap<String, u64>,
+    /// Events seen per event kind
+    pub events: BTreeMap<String, u64>,
+    /// Most pixels of an element present at the same time
+    pub peak_census: BTreeMap<String, u64>,
+    pub total_ticks: u64,
+    pub longest_session_ticks: u64,
+    /// Fastest completion per level name
+    pub best_times: BTreeMap<String, BestTime>,
+    #[serde(skip)]
+    session_ticks: u64,
+}
+
+/// Fastest completion of a level, ranked by ticks so runs compare whatever the frame rate.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub struct BestTime {
+    pub ticks: u64,
+    pub millis: u64,
+}
+
+pub struct Achievement {
+    pub name: &'static str,
+    pub description: &'static str,
+    is_unlocked: fn(&Stats) -> bool,
+}
+
+impl Achievement {
+    pub fn is_unlocked(&self, stats: &Stats) -> bool {
+        (self.is_unlocked)(stats)
+    }
+}
+
+impl Stats {
+    /// How often the census is sampled, it is too expensive to take every tick.
+    const CENSUS_INTERVAL: u64 = 60;
+
+    /// Loads stats from `path`, starting fresh if the file doesn't exist yet.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read stats {}", path.display()))?;
+        toml::from_str(&s).with_context(|| format!("failed to parse stats {}", path.display()))
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("failed to write stats {}", path.display()))
+    }
+
+    fn placed_of(&self, pixel: Pixel) -> u64 {
+        self.placed.get(pixel.name()).copied().unwrap_or(0)
+    }
+
+    fn events_of(&self, kind: EventKind) -> u64 {
+        self.events
+            .get(&format!("{:?}", kind))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    fn peak_of(&self, pixel: Pixel) -> u64 {
+        self.peak_census.get(pixel.name()).copied().unwrap_or(0)
+    }
+
+    /// Starts counting a new sandbox session.
+    pub fn start_session(&mut self) {
+        self.session_ticks = 0;
+    }
+
+    /// Records `command` before `sandbox` applies it.
+    pub fn record_command<R: Rng>(&mut self, sandbox: &Sandbox<R>, command: &Command) {
+        if let Some((pixel, _)) = command.placement() {
+            if matches!(pixel, Pixel::Void(_)) {
+                return;
+            }
+            *self.placed.entry(pixel.name().to_string()).or_insert(0) +=
+                sandbox.placement_count(command) as u64;
+        }
+    }
+
+    /// Records a completion of the level called `name`, returns whether it is a new best.
+    pub fn record_level_time(&mut self, name: &str, ticks: u64, time: Duration) -> bool {
+        let time = BestTime {
+            ticks,
+            millis: time.as_millis() as u64,
+        };
+        let best = self.best_times.entry(name.to_string()).or_insert(time);
+        let is_best = (time.ticks, time.millis) <= (best.ticks, best.millis);
+        if is_best {
+            *best = time;
+        }
+        is_best
+    }
+
+    /// Records the outcome of a tick of `sandbox`.
+    pub fn record_tick<R: Rng>(&mut self, sandbox: &Sandbox<R>) {
+        self.total_ticks += 1;
+        self.session_ticks += 1;
+        self.longest_session_ticks = self.longest_session_ticks.max(self.session_ticks);
+
+        for e in sandbox.events() {
+            *self.events.entry(format!("{:?}", e.kind)).or_insert(0) += 1;
+        }
+
+        if self.session_ticks.is_multiple_of(Self::CENSUS_INTERVAL) {
+            for (name, count) in sandbox.census() {
+                let peak = self.peak_census.entry(name.to_string()).or_insert(0);
+                *peak = (*peak).max(count as u64);
+            }
+        }
+    }
+
+    pub fn achievements() -> &'static [Achievement] {
+        use crate::pixel::sand::Sand;
+        use crate::pixel::water::Water;
+
+        &[
+            Achievement {
+                name: "First grain",
+                description: "Place your first pixel",
+                is_unlocked: |s| s.placed.values().any(|&count| count > 0),
+            },
+            Achievement {
+                name: "Sandman",
+                description: "Place 10000 sand pixels",
+                is_unlocked: |s| s.placed_of(Sand.into()) >= 10_000,
+            },
+            Achievement {
+                name: "Firestarter",
+                description: "Set 1000 things on fire",
+                is_unlocked: |s| s.events_of(EventKind::Ignition) >= 1_000,
+            },
+            Achievement {
+                name: "Deep freeze",
+                description: "Freeze 1000 water pixels",
+                is_unlocked: |s| s.events_of(EventKind::Freeze) >= 1_000,
+            },
+            Achievement {
+                name: "Cannonball",
+                description: "Make 1000 splashes",
+                is_unlocked: |s| s.events_of(EventKind::Splash) >= 1_000,
+            },
+            Achievement {
+                name: "Ocean",
+                description: "Have 5000 water pixels at once",
+                is_unlocked: |s| s.peak_of(Water::default().into()) >= 5_000,
+            },
+            Achievement {
+                name: "Marathon",
+                description: "Keep a sandbox running for 100000 ticks",
+                is_unlocked: |s| s.longest_session_ticks >= 100_000,
+            },
+        ]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use rand::rngs::SmallRng;
+
+    use crate::command::Command;
+    use crate::pixel::sand::Sand;
+    use crate::sandbox::Sandbox;
+    use crate::stats::Stats;
+
+    #[test]
+    fn test_stats_roundtrip() {
+        let mut sandbox = Sandbox::<SmallRng>::new(3, 3);
+        let mut stats = Stats::default();
+        let command = Command::PlaceBrush {
+            pixel: Sand.into(),
+            x: 0,
+            y: 0,
+            width: 2,
+            height: 2,
+            force: false,
+        };
+        stats.record_command(&sandbox, &command);
+        sandbox.queue_command(command);
+        sandbox.tick();
+        stats.record_tick(&sandbox);
+
+        assert_eq!(stats.placed.get("Sand"), Some(&4));
+        assert!(Stats::achievements()[0].is_unlocked(&stats));
+
+        assert!(stats.record_level_time("Basin", 100, Duration::from_secs(2)));
+        assert!(!stats.record_level_time("Basin", 120, Duration::from_secs(1)));
+        assert!(stats.record_level_time("Basin", 90, Duration::from_secs(3)));
+
+        let s = toml::to_string(&stats).unwrap();
+        let loaded: Stats = toml::from_str(&s).unwrap();
+        assert_eq!(loaded.placed, stats.placed);
+        assert_eq!(loaded.longest_session_ticks, 1);
+        assert_eq!(loaded.best_times, stats.best_times);
+    }
+}
@@ -0,0 +1,173 @@
+use anyhow::anyhow;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::entity::Entity;
+use crate::pixel::Pixel;
+use crate::sandbox::{PixelContainer, Sandbox};
+
+/// Cells that changed between two states of a sandbox.
+#[derive(Debug, Clone, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GridDiff {
+    pub width: usize,
+    pub height: usize,
+    /// Changed cells as (index, new pixel, temperature), every cell when the size changed
+    pub changes: Vec<(u32, Pixel, u8)>,
+    /// Every entity of the source, if any of them changed
+    #[serde(default)]
+    pub entities: Option<Vec<Entity>>,
+    /// [`Sandbox::grid_hash`] of the source once the diff is applied
+    pub hash: u64,
+}
+
+impl GridDiff {
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.entities.is_none()
+    }
+
+    /// Applies the diff to `mirror`, failing if the mirror ends up diverging from the source.
+    pub fn apply<R: Rng>(&self, mirror: &mut Sandbox<R>) -> anyhow::Result<()> {
+        if (mirror.width, mirror.height) != (self.width, self.height) {
+            mirror.width = self.width;
+            mirror.height = self.height;
+            mirror.pixels = vec![PixelContainer::default(); self.width * self.height];
+        }
+
+        for &(idx, pixel, temp) in &self.changes {
+            let (x, y) = mirror.index_to_coordinates(idx as usize);
+            mirror.place_pixel_force(pixel, x, y);
+            mirror.set_temperature(x, y, temp);
+        }
+        if let Some(entities) = &self.entities {
+            mirror.set_entities(entities.clone());
+        }
+
+        match mirror.grid_hash() == self.hash {
+            true => Ok(()),
+            false => Err(anyhow!("mirror diverged from the source sandbox")),
+        }
+    }
+}
+
+/// Remembers the last state of a sandbox to compute [`GridDiff`]s against it.
+#[derive(Debug, Default)]
+pub struct DiffTracker {
+    width: usize,
+    height: usize,
+    previous: Vec<(Pixel, u8)>,
+    entities: Vec<Entity>,
+}
+
+impl DiffTracker {
+    /// Diffs `sandbox` against the state seen on the previous call.
+    ///
+    /// The first diff holds the whole grid, so a mirror can start from an empty sandbox.
+    pub fn diff<R: Rng>(&mut self, sandbox: &Sandbox<R>) -> GridDiff {
+        let resized = (self.width, self.height) != (sandbox.width, sandbox.height);
+        if resized {
+            self.width = sandbox.width;
+            self.height = sandbox.height;
+            self.previous.clear();
+        }
+
+        let changes = sandbox
+            .pixels
+            .iter()
+            .enumerate()
+            .filter(|(idx, p)| resized || self.previous[*idx] != (p.pixel(), p.temperature()))
+            .map(|(idx, p)| (idx as u32, p.pixel(), p.temperature()))
+            .collect();
+
+        self.previous = sandbox
+            .pixels
+            .iter()
+            .map(|p| (p.pixel(), p.temperature()))
+            .collect();
+
+        let entities = (resized || self.entities != sandbox.entities()).then(|| {
+            self.entities = sandbox.entities().to_vec();
+            self.entities.clone()
+        });
+
+        GridDiff {
+            width: sandbox.width,
+            height: sandbox.height,
+            changes,
+            entities,
+            hash: sandbox.grid_hash(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::rngs::SmallRng;
+
+    use crate::command::Command;
+    use crate::entity::EntityKind;
+    use crate::pixel::eternal_fire::EternalFire;
+    use crate::pixel::sand::Sand;
+    use crate::pixel::water::Water;
+    use crate::sandbox::Sandbox;
+    use crate::sync::DiffTracker;
+
+    #[test]
+    fn test_diff_mirror() {
+        let mut source = Sandbox::<SmallRng>::new_with_seed(10, 10, 1);
+        let mut mirror = Sandbox::<SmallRng>::new_with_seed(1, 1, 2);
+        let mut tracker = DiffTracker::default();
+
+        for tick in 0..30 {
+            source.queue_command(Command::PlaceBrush {
+                pixel: match tick % 2 {
+                    0 => Sand.into(),
+                    _ => Water::default().into(),
+                },
+                x: tick % 10,
+                y: 0,
+                width: 1,
+                height: 1,
+                force: false,
+            });
+            if tick == 15 {
+                source.queue_command(Command::Resize {
+                    width: 12,
+                    height: 8,
+                });
+            }
+            source.tick();
+
+            let diff = tracker.diff(&source);
+            diff.apply(&mut mirror).unwrap();
+            assert!(diff.changes.len() <= source.pixels.len());
+        }
+
+        // nothing changes without a tick
+        assert!(tracker.diff(&source).is_empty());
+        assert_eq!(mirror.grid_hash(), source.grid_hash());
+    }
+
+    #[test]
+    fn test_diff_mirror_heat_and_entities() {
+        // the fire heats the cells around it, and the ball moves without changing any pixel
+        let mut source = Sandbox::<SmallRng>::new_with_seed(10, 10, 1);
+        let mut mirror = Sandbox::<SmallRng>::new_with_seed(1, 1, 2);
+        let mut tracker = DiffTracker::default();
+        source.place_pixel_force(EternalFire.into(), 0, 9);
+        source.queue_command(Command::SpawnEntity {
+            entity: EntityKind::Ball,
+            x: 6,
+            y: 0,
+        });
+
+        for _ in 0..10 {
+            source.tick();
+            tracker.diff(&source).apply(&mut mirror).unwrap();
+            assert_eq!(mirror.entities(), source.entities());
+        }
+        assert!((0..10).any(|x| source.temperature_at(x, 8) != source.temperature_at(0, 0)));
+        for (mirrored, source) in mirror.pixels.iter().zip(&source.pixels) {
+            assert_eq!(mirrored.temperature(), source.temperature());
+        }
+    }
+}
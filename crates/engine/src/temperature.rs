@@ -0,0 +1,61 @@
+//! Conversions between the engine's compact temperature scale and degrees Celsius.
+//!
+//! Every cell of the sandbox keeps its temperature in a byte where water freezes at 10, sits at
+//! 20 at room temperature and boils at 30. Frontends and config files use degrees Celsius instead.
+
+/// Water pixels freeze at or below this temperature.
+pub const FREEZING: u8 = 10;
+/// Temperature of water placed by the player.
+pub const ROOM: u8 = 20;
+/// Water pixels turn into steam at or above this temperature.
+pub const BOILING: u8 = 30;
+/// Steam turns back into water below this temperature, a little under boiling so it doesn't
+/// flicker between the two.
+pub const CONDENSING: u8 = 25;
+/// Temperature of placed steam.
+pub const STEAM: u8 = 50;
+/// Temperature of placed ice.
+pub const ICE: u8 = 5;
+/// Wood and oil catch fire at or above this temperature.
+pub const IGNITION: u8 = 60;
+/// Temperature of flames and of whatever burns.
+pub const FLAME: u8 = 90;
+/// Temperature of lava.
+pub const MOLTEN: u8 = 150;
+
+/// Degrees Celsius of the engine temperature `temp`.
+///
+/// Each step is worth 2°C between freezing and room temperature, and 8°C above it, so water
+/// still boils at 100°C.
+pub fn to_celsius(temp: u8) -> i16 {
+    let temp = temp as i16;
+    match temp <= ROOM as i16 {
+        true => (temp - FREEZING as i16) * 2,
+        false => 20 + (temp - ROOM as i16) * 8,
+    }
+}
+
+/// Closest engine temperature to `celsius`.
+pub fn from_celsius(celsius: i16) -> u8 {
+    let temp = match celsius <= 20 {
+        true => FREEZING as i16 + celsius.div_euclid(2),
+        false => ROOM as i16 + (celsius - 20) / 8,
+    };
+    temp.clamp(0, u8::MAX as i16) as u8
+}
+
+#[cfg(test)]
+mod test {
+    use crate::temperature::{from_celsius, to_celsius, BOILING, FREEZING, ROOM};
+
+    #[test]
+    fn test_temperature_celsius() {
+        assert_eq!(to_celsius(FREEZING), 0);
+        assert_eq!(to_celsius(ROOM), 20);
+        assert_eq!(to_celsius(BOILING), 100);
+        for temp in 0..=40 {
+            assert_eq!(from_celsius(to_celsius(temp)), temp);
+        }
+        assert_eq!(from_celsius(-1000), 0);
+    }
+}
@@ -0,0 +1,52 @@
+use anyhow::{anyhow, Context};
+use serde::de::DeserializeOwned;
+use toml::{Table, Value};
+
+/// A file format carrying a `version` header, upgraded on load when saved by an older release.
+///
+/// Files from before the header existed are taken as version 1.
+pub trait Versioned: DeserializeOwned {
+    /// What the file holds, for error messages
+    const KIND: &'static str;
+    /// Version written by this release
+    const VERSION: u32;
+
+    /// Upgrades `table`, saved with `version`, to the next version.
+    fn migrate(_table: &mut Table, version: u32) -> anyhow::Result<()> {
+        Err(anyhow!(
+            "no migration from {} version {}",
+            Self::KIND,
+            version
+        ))
+    }
+
+    /// Parses `s`, migrating it from the version it was saved with.
+    fn from_versioned_toml(s: &str) -> anyhow::Result<Self> {
+        let mut table: Table = toml::from_str(s)?;
+        let version = match table.get("version") {
+            None => 1,
+            Some(Value::Integer(version)) => u32::try_from(*version)
+                .map_err(|_| anyhow!("invalid {} version {}", Self::KIND, version))?,
+            Some(_) => return Err(anyhow!("{} version must be a number", Self::KIND)),
+        };
+        if version > Self::VERSION {
+            return Err(anyhow!(
+                "{} version {} was saved by a newer release, this one reads up to version {}",
+                Self::KIND,
+                version,
+                Self::VERSION
+            ));
+        }
+        if version == 0 {
+            return Err(anyhow!("invalid {} version 0", Self::KIND));
+        }
+
+        for version in version..Self::VERSION {
+            Self::migrate(&mut table, version).with_context(|| {
+                format!("failed to migrate {} from version {}", Self::KIND, version)
+            })?;
+        }
+        table.insert("version".to_string(), Value::Integer(Self::VERSION as i64));
+        Ok(Value::Table(table).try_into()?)
+    }
+}
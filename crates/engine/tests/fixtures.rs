@@ -0,0 +1,22 @@
+use std::path::Path;
+
+use rustfall_engine::fixture::Fixture;
+
+/// Replays every fixture recorded from the TUI, see `Fixture` for how they are made.
+#[test]
+fn test_replay_fixtures() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures");
+    let mut count = 0;
+    for entry in std::fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_none_or(|ext| ext != "toml") {
+            continue;
+        }
+        let fixture = Fixture::load(&path).unwrap();
+        if let Err(e) = fixture.verify() {
+            panic!("{}: {}", path.display(), e);
+        }
+        count += 1;
+    }
+    assert!(count > 0, "no fixtures found");
+}
@@ -0,0 +1,93 @@
+use rand::rngs::SmallRng;
+use rustfall_engine::catalog::{self, Chord};
+use rustfall_engine::palette::Rgb;
+use rustfall_engine::pixel::{Pixel, PixelFundamental, PixelType};
+use rustfall_engine::registry::{Behaviour, Descriptor, PixelRegistry, Reaction};
+use rustfall_engine::sandbox::Sandbox;
+
+/// Registers an element from outside the engine and plays with it. The registry is global to
+/// the process, hence a test binary of its own.
+#[test]
+fn test_registered_element() {
+    let mut registry = PixelRegistry::default();
+    let slime = registry
+        .register(Descriptor {
+            name: "Slime".to_string(),
+            behaviour: Behaviour::Liquid,
+            density: 40,
+            color: Rgb(0x5f, 0xd7, 0x00),
+            hotkey: Some('Z'),
+            chord: None,
+            viscosity: 60,
+            reactions: vec![Reaction {
+                touching: "Lava".to_string(),
+                becomes: "Steam".to_string(),
+            }],
+        })
+        .unwrap();
+    // past the single keys, typed as `g` then `u`
+    let gum = registry
+        .register(Descriptor {
+            name: "Gum".to_string(),
+            behaviour: Behaviour::Powder,
+            density: 10,
+            color: Rgb(0xff, 0x87, 0xd7),
+            hotkey: None,
+            chord: Some(Chord('g', 'u')),
+            viscosity: 0,
+            reactions: vec![],
+        })
+        .unwrap();
+    registry.install().unwrap();
+    assert!(PixelRegistry::default().install().is_err());
+
+    // the catalog and lookups by name pick it up like any builtin element
+    let element = catalog::by_hotkey('Z').unwrap();
+    assert_eq!(element.name, "Slime");
+    assert_eq!(element.pixel(), slime);
+    assert_eq!(catalog::element(&slime).color, Rgb(0x5f, 0xd7, 0x00));
+    assert_eq!(Pixel::from_name("slime"), Some(slime));
+    assert_eq!(slime.pixel_type(), PixelType::Liquid(40));
+    let element = catalog::by_chord(Chord('g', 'u')).unwrap();
+    assert_eq!(element.pixel(), gum);
+    assert_eq!(element.keys(), "gu");
+    assert_eq!(catalog::by_hotkey(element.hotkey).unwrap().pixel(), gum);
+    let completions = catalog::completions('g');
+    assert_eq!(completions.len(), 1);
+    assert_eq!(completions[0].name, "Gum");
+    assert!(catalog::completions('u').is_empty());
+
+    // it flows down like a liquid, and boils off touching lava
+    let mut sandbox = Sandbox::<SmallRng>::new_with_seed(2, 4, 7);
+    sandbox.place_pixel_force(slime, 0, 0);
+    for _ in 0..10 {
+        sandbox.tick();
+    }
+    let names = |sandbox: &Sandbox<_>| {
+        sandbox
+            .pixels
+            .iter()
+            .map(|p| p.pixel().name())
+            .collect::<Vec<_>>()
+    };
+    let bottom = names(&sandbox)[6..].to_vec();
+    assert!(
+        bottom.contains(&"Slime") && bottom.contains(&"Void"),
+        "{:?}",
+        bottom
+    );
+    let beside = bottom.iter().position(|&name| name == "Void").unwrap();
+    sandbox.place_pixel_force(Pixel::from_name("Lava").unwrap(), beside, 3);
+    sandbox.tick();
+    assert!(!names(&sandbox).contains(&"Slime"));
+
+    // and survives a trip through a save file
+    let saved = toml::to_string(&Snapshot { pixel: slime }).unwrap();
+    let loaded: Snapshot = toml::from_str(&saved).unwrap();
+    assert_eq!(loaded.pixel, slime);
+}
+
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Snapshot {
+    pixel: Pixel,
+}
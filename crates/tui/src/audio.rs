@@ -0,0 +1,53 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use rodio::source::{SineWave, Source};
+use rodio::{OutputStream, OutputStreamHandle};
+
+use engine::event::{EventKind, SandboxEvent};
+
+/// Plays simple synthesized cues for sandbox events.
+pub struct Audio {
+    // the stream must be kept alive for the handle to keep working
+    _stream: OutputStream,
+    handle: OutputStreamHandle,
+}
+
+impl Audio {
+    pub fn try_new() -> anyhow::Result<Self> {
+        let (stream, handle) = OutputStream::try_default()?;
+        Ok(Self {
+            _stream: stream,
+            handle,
+        })
+    }
+
+    fn tone(kind: EventKind) -> (f32, Duration) {
+        match kind {
+            EventKind::Ignition => (110.0, Duration::from_millis(120)),
+            EventKind::Freeze => (1760.0, Duration::from_millis(60)),
+            EventKind::Splash => (440.0, Duration::from_millis(80)),
+            EventKind::Impact => (220.0, Duration::from_millis(40)),
+            EventKind::Thunder => (41.0, Duration::from_millis(400)),
+            EventKind::Explosion => (55.0, Duration::from_millis(300)),
+            EventKind::Torque => (82.0, Duration::from_millis(30)),
+            EventKind::InvariantViolation(_) => (55.0, Duration::from_millis(200)),
+        }
+    }
+
+    /// Plays one cue per event kind, louder the more happened during the tick.
+    pub fn play(&self, events: &[SandboxEvent]) {
+        let mut magnitudes = HashMap::new();
+        for e in events {
+            *magnitudes.entry(e.kind).or_insert(0u32) += e.magnitude;
+        }
+
+        for (kind, magnitude) in magnitudes {
+            let (freq, duration) = Self::tone(kind);
+            let volume = (magnitude as f32 / 200.0).min(1.0) * 0.3;
+            let source = SineWave::new(freq).take_duration(duration).amplify(volume);
+            // a missing or busy output device is not worth interrupting the game for
+            let _ = self.handle.play_raw(source);
+        }
+    }
+}
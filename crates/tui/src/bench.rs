@@ -0,0 +1,87 @@
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context};
+use engine::sandbox::Sandbox;
+use engine::scenario::{Scenario, ScenarioReport};
+use engine::snapshot::Snapshot;
+use rand::rngs::SmallRng;
+
+const USAGE: &str = "usage: rustfall bench [<scenario.toml|scene.rfs>] [--ticks <n>]";
+
+/// Options of a benchmark run.
+#[derive(Debug)]
+pub struct BenchOptions {
+    /// Scenario, or snapshot saved with `F9`, every demo is run if unset
+    scenario: Option<PathBuf>,
+    ticks: u64,
+}
+
+impl BenchOptions {
+    pub fn parse(args: &[String]) -> anyhow::Result<Self> {
+        let mut options = Self {
+            scenario: None,
+            ticks: 1000,
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| anyhow!(USAGE));
+            match arg.as_str() {
+                "--ticks" => options.ticks = value()?.parse().context(USAGE)?,
+                s if !s.starts_with("--") && options.scenario.is_none() => {
+                    options.scenario = Some(PathBuf::from(s))
+                }
+                _ => anyhow::bail!(USAGE),
+            }
+        }
+        Ok(options)
+    }
+}
+
+/// Runs scenarios or a snapshot headless and prints where the time of their ticks went, and
+/// what the sandbox holds at the end.
+pub fn run(options: BenchOptions) -> anyhow::Result<()> {
+    let scenario = |scenario: Scenario| {
+        let name = scenario.name.clone();
+        (
+            name,
+            Sandbox::<SmallRng>::run_scenario(scenario, options.ticks),
+        )
+    };
+    let reports = match &options.scenario {
+        Some(path) if path.extension().is_some_and(|ext| ext == "rfs") => {
+            let mut sandbox = Snapshot::load(path)?.restore()?;
+            vec![(path.display().to_string(), sandbox.run_timed(options.ticks))]
+        }
+        Some(path) => vec![scenario(Scenario::load(path)?)],
+        None => Scenario::demos().into_iter().map(scenario).collect(),
+    };
+
+    for (name, report) in reports {
+        print_report(&name, &report);
+    }
+    Ok(())
+}
+
+fn print_report(name: &str, report: &ScenarioReport) {
+    let total = report.total();
+    println!(
+        "{}: {} ticks in {:.2?} ({:.1} tps)",
+        name,
+        report.ticks,
+        total,
+        report.ticks_per_second()
+    );
+    for (phase, time) in [
+        ("movement", report.movement),
+        ("interaction", report.interaction),
+        ("bookkeeping", report.bookkeeping),
+    ] {
+        let share = time.as_secs_f64() / total.as_secs_f64().max(f64::EPSILON) * 100.0;
+        println!("  {:<12} {:>10.2?} {:>5.1}%", phase, time, share);
+    }
+    println!("  pixels at the end");
+    for (name, count) in report.census.iter().filter(|(name, _)| **name != "Void") {
+        println!("    {:<16} {:>8}", name, count);
+    }
+}
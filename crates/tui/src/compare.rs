@@ -0,0 +1,171 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use engine::catalog;
+use engine::compare::Comparison;
+use engine::config::SandboxConfig;
+use engine::palette::Rgb;
+use engine::sandbox::Sandbox;
+use engine::scenario::Scenario;
+use rand::Rng;
+
+const USAGE: &str = "usage: rustfall compare <scenario.toml> --a <settings> --b <settings> [--ticks <n>] [--every <n>] [--seed <n>] [--strip <file.ppm>] [--scale <n>]";
+
+/// Options of a run of the same scenario under two sandbox configs.
+#[derive(Debug)]
+pub struct CompareOptions {
+    scenario: PathBuf,
+    /// Settings of each run, written like the `[sandbox]` section of the config file
+    configs: [SandboxConfig; 2],
+    ticks: u64,
+    /// Ticks between two samples
+    every: u64,
+    seed: u64,
+    /// Image of both runs side by side at every sample, one sample below the other
+    strip: Option<PathBuf>,
+    /// Pixels of the strip for every cell along each side
+    scale: usize,
+}
+
+impl CompareOptions {
+    pub fn parse(args: &[String]) -> anyhow::Result<Self> {
+        let mut scenario = None;
+        let mut configs = [None, None];
+        let mut options = Self {
+            scenario: PathBuf::new(),
+            configs: Default::default(),
+            ticks: 600,
+            every: 60,
+            seed: 0,
+            strip: None,
+            scale: 2,
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| anyhow!(USAGE));
+            match arg.as_str() {
+                "--a" => configs[0] = Some(Self::parse_config(value()?)?),
+                "--b" => configs[1] = Some(Self::parse_config(value()?)?),
+                "--ticks" => options.ticks = value()?.parse().context(USAGE)?,
+                "--every" => options.every = value()?.parse().context(USAGE)?,
+                "--seed" => options.seed = value()?.parse().context(USAGE)?,
+                "--strip" => options.strip = Some(PathBuf::from(value()?)),
+                "--scale" => options.scale = value()?.parse().context(USAGE)?,
+                s if !s.starts_with("--") && scenario.is_none() => {
+                    scenario = Some(PathBuf::from(s))
+                }
+                _ => anyhow::bail!(USAGE),
+            }
+        }
+        options.scenario = scenario.ok_or_else(|| anyhow!(USAGE))?;
+        let [Some(a), Some(b)] = configs else {
+            anyhow::bail!(USAGE);
+        };
+        options.configs = [a, b];
+        if options.every == 0 || options.scale == 0 {
+            anyhow::bail!(USAGE);
+        }
+        Ok(options)
+    }
+
+    /// Parses settings like `splash = true`, on top of the defaults.
+    fn parse_config(s: &str) -> anyhow::Result<SandboxConfig> {
+        toml::from_str(s).with_context(|| format!("invalid sandbox settings {:?}", s))
+    }
+}
+
+/// Colour of every cell of `sandbox`, row major.
+fn colors<R: Rng>(sandbox: &Sandbox<R>) -> Vec<Rgb> {
+    sandbox
+        .pixels
+        .iter()
+        .map(|cell| catalog::element(&cell.pixel()).color)
+        .collect()
+}
+
+/// Writes the samples of both runs as a binary PPM image, each sample a band of the two runs
+/// side by side, split and separated by grey lines one cell thick.
+fn write_strip(
+    samples: &[[Vec<Rgb>; 2]],
+    (width, height): (usize, usize),
+    scale: usize,
+    path: &Path,
+) -> anyhow::Result<()> {
+    const LINE: Rgb = Rgb(0x80, 0x80, 0x80);
+
+    let file =
+        File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+    let mut out = BufWriter::new(file);
+    let strip_width = 2 * width + 1;
+    let strip_height = samples.len() * (height + 1) - 1;
+    write!(
+        out,
+        "P6\n{} {}\n255\n",
+        strip_width * scale,
+        strip_height * scale
+    )?;
+    for (i, [a, b]) in samples.iter().enumerate() {
+        let mut rows = a.chunks(width).zip(b.chunks(width)).map(|(a, b)| {
+            a.iter()
+                .chain([&LINE])
+                .chain(b)
+                .copied()
+                .collect::<Vec<_>>()
+        });
+        let separator = (i > 0).then(|| vec![LINE; strip_width]);
+        for row in separator.into_iter().chain(&mut rows) {
+            let line = row
+                .iter()
+                .flat_map(|color| [color.0, color.1, color.2].repeat(scale))
+                .collect::<Vec<_>>();
+            for _ in 0..scale {
+                out.write_all(&line)?;
+            }
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Plays a scenario from the same seed under two sandbox configs and prints how far apart the
+/// runs drift over time.
+pub fn run(options: CompareOptions) -> anyhow::Result<()> {
+    let scenario = Scenario::load(&options.scenario)?;
+    if options.configs[0] == options.configs[1] {
+        eprintln!("both runs have the same settings, they won't diverge");
+    }
+    let mut comparison = Comparison::new(&scenario, options.seed, options.configs);
+    let size = (scenario.width, scenario.height);
+
+    println!(
+        "{:>8} {:>10} {:>8} {:>8}",
+        "tick", "cells", "share", "census"
+    );
+    let mut samples = Vec::new();
+    for tick in 1..=options.ticks {
+        comparison.tick();
+        if !tick.is_multiple_of(options.every) && tick != options.ticks {
+            continue;
+        }
+        let divergence = comparison.divergence();
+        println!(
+            "{:>8} {:>10} {:>7.2}% {:>8}",
+            divergence.tick,
+            divergence.cells,
+            divergence.ratio * 100.0,
+            divergence.census
+        );
+        if options.strip.is_some() {
+            samples.push(comparison.sandboxes().map(colors));
+        }
+    }
+
+    if let Some(path) = options.strip.as_ref().filter(|_| !samples.is_empty()) {
+        write_strip(&samples, size, options.scale, path)?;
+        println!("{} samples drawn to {}", samples.len(), path.display());
+    }
+    Ok(())
+}
@@ -0,0 +1,416 @@
+use std::path::Path;
+
+use anyhow::{anyhow, Context};
+use serde::{Deserialize, Serialize};
+
+use engine::config::SandboxConfig;
+use engine::palette::Palette;
+use engine::pixel::PixelKind;
+use engine::registry::{Descriptor, PixelRegistry};
+use engine::scene::Scene;
+
+use crate::locale::Locale;
+use crate::state::State;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BrushShape {
+    #[default]
+    Square,
+    Circle,
+    /// One sandbox cell under the cursor whatever the size, for fine work in braille mode
+    Single,
+}
+
+/// How much of the sandbox around the cursor a stroke covers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Brush {
+    /// Width and height in terminal cells
+    pub size: u8,
+    pub shape: BrushShape,
+    /// Percentage of the covered cells that get painted
+    pub density: u8,
+}
+
+impl Brush {
+    pub const MAX_SIZE: u8 = 9;
+
+    pub fn resize(&mut self, delta: i8) {
+        self.size = self
+            .size
+            .saturating_add_signed(delta)
+            .clamp(1, Self::MAX_SIZE);
+    }
+
+    pub fn thin(&mut self, delta: i8) {
+        self.density = self.density.saturating_add_signed(delta).clamp(10, 100);
+    }
+
+    pub fn toggle_shape(&mut self) {
+        self.shape = match self.shape {
+            BrushShape::Square => BrushShape::Circle,
+            BrushShape::Circle => BrushShape::Single,
+            BrushShape::Single => BrushShape::Square,
+        };
+    }
+}
+
+impl Default for Brush {
+    fn default() -> Self {
+        Self {
+            size: 1,
+            shape: BrushShape::Square,
+            density: 100,
+        }
+    }
+}
+
+/// An element and brush bound to Shift and a digit.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct Favorite {
+    /// Digit the favorite is bound to, from 1 to 9
+    pub slot: u8,
+    pub element: PixelKind,
+    pub brush: Brush,
+}
+
+/// What the simulation does while the terminal is in the background.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Background {
+    /// Keeps running as if the terminal were focused
+    #[default]
+    Run,
+    /// Runs and draws a tick out of every [`Background::SLOW_FACTOR`]
+    Slow,
+    /// Stops ticking and drawing until the terminal is focused again
+    Pause,
+}
+
+impl Background {
+    pub const SLOW_FACTOR: u32 = 8;
+}
+
+/// How the sandbox is drawn in the terminal.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RenderMode {
+    /// Eight cells in every terminal character, as braille dots
+    #[default]
+    Braille,
+    /// A cell for every terminal character, for fonts without braille
+    Block,
+}
+
+/// An element bound to a key instead of its usual hotkey.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct KeyBinding {
+    pub key: char,
+    pub element: PixelKind,
+}
+
+/// Settings read once at launch, which environment variables and command line flags override,
+/// in that order.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchConfig {
+    pub render: RenderMode,
+    /// Palette file the sandbox is drawn with instead of the terminal colours, `powder_toy`
+    /// names the builtin one
+    pub theme: Option<String>,
+    /// Size of the free play sandbox in cells, fitted to the terminal if unset
+    pub width: Option<usize>,
+    pub height: Option<usize>,
+    /// Seed of the free play sandboxes, random if unset
+    pub seed: Option<u64>,
+    /// Frames drawn per second
+    pub tick_rate: Option<u32>,
+    /// Element hotkeys overriding the builtin ones
+    pub keys: Vec<KeyBinding>,
+    /// Seconds between two saves of the scene offered for restoring on the next launch, which
+    /// is otherwise only saved when rustfall crashes
+    pub autosave: Option<u64>,
+    /// Language of the interface, a bundled one like `fr` or a locale file, the one of `$LANG`
+    /// if it is bundled and English otherwise
+    pub lang: Option<String>,
+}
+
+impl LaunchConfig {
+    /// Settings the environment and the command line can set, and the flag setting them.
+    pub const OVERRIDES: [(&'static str, &'static str); 7] = [
+        ("render", "--render"),
+        ("theme", "--theme"),
+        ("size", "--size"),
+        ("seed", "--seed"),
+        ("tick_rate", "--tick-rate"),
+        ("autosave", "--autosave"),
+        ("lang", "--lang"),
+    ];
+    /// Frames per second when the tick rate isn't set
+    const DEFAULT_TICK_RATE: u32 = 60;
+    /// Most cells along either side of the sandbox, as for scenes
+    pub const MAX_SIZE: usize = Scene::MAX_SIZE;
+
+    /// Sets the setting named `key` from its textual `value`, as given in an environment
+    /// variable or on the command line.
+    pub fn set(&mut self, key: &str, value: &str) -> anyhow::Result<()> {
+        match key {
+            "render" => {
+                self.render = match value {
+                    "braille" => RenderMode::Braille,
+                    "block" => RenderMode::Block,
+                    _ => anyhow::bail!("expected braille or block, got {}", value),
+                }
+            }
+            "theme" => self.theme = Some(value.to_string()),
+            "lang" => self.lang = Some(value.to_string()),
+            "size" => {
+                let (width, height) = value
+                    .split_once('x')
+                    .ok_or_else(|| anyhow!("expected a size like 200x100, got {}", value))?;
+                self.width = Some(
+                    width
+                        .parse()
+                        .with_context(|| format!("invalid size {}", value))?,
+                );
+                self.height = Some(
+                    height
+                        .parse()
+                        .with_context(|| format!("invalid size {}", value))?,
+                );
+                self.check_size()?;
+            }
+            "seed" => {
+                self.seed = Some(
+                    value
+                        .parse()
+                        .with_context(|| format!("invalid seed {}", value))?,
+                )
+            }
+            "tick_rate" => {
+                self.tick_rate = Some(
+                    value
+                        .parse()
+                        .ok()
+                        .filter(|&rate| rate > 0)
+                        .ok_or_else(|| anyhow!("invalid tick rate {}", value))?,
+                )
+            }
+            "autosave" => {
+                self.autosave = Some(
+                    value
+                        .parse()
+                        .with_context(|| format!("invalid autosave period {}", value))?,
+                )
+            }
+            _ => anyhow::bail!("unknown setting {}", key),
+        }
+        Ok(())
+    }
+
+    /// Applies the `RUSTFALL_*` environment variables, e.g. `RUSTFALL_TICK_RATE=30`.
+    pub fn apply_env(&mut self) -> anyhow::Result<()> {
+        for (key, _) in Self::OVERRIDES {
+            let var = format!("RUSTFALL_{}", key.to_uppercase());
+            if let Ok(value) = std::env::var(&var) {
+                self.set(key, &value)
+                    .with_context(|| format!("invalid {}", var))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Fails if a side of the sandbox size is set outside of 1 to [`LaunchConfig::MAX_SIZE`],
+    /// as the config file may set it too.
+    pub fn check_size(&self) -> anyhow::Result<()> {
+        for side in [self.width, self.height].into_iter().flatten() {
+            if !(1..=Self::MAX_SIZE).contains(&side) {
+                anyhow::bail!(
+                    "sandbox sides must be from 1 to {} cells, got {}",
+                    Self::MAX_SIZE,
+                    side
+                );
+            }
+        }
+        Ok(())
+    }
+
+    pub fn no_braille(&self) -> bool {
+        self.render == RenderMode::Block
+    }
+
+    /// Size the free play sandbox is kept at whatever the terminal size, if both sides are set.
+    pub fn size(&self) -> Option<(usize, usize)> {
+        self.width.zip(self.height)
+    }
+
+    /// Frames drawn per second.
+    pub fn frame_rate(&self) -> u32 {
+        self.tick_rate.unwrap_or(Self::DEFAULT_TICK_RATE).max(1)
+    }
+
+    /// Milliseconds between two frames.
+    pub fn frame_millis(&self) -> u64 {
+        1000 / self.frame_rate() as u64
+    }
+
+    /// Loads the theme palette, if one is set.
+    pub fn palette(&self) -> anyhow::Result<Option<Palette>> {
+        match self.theme.as_deref() {
+            None => Ok(None),
+            Some("powder_toy") => Ok(Some(Palette::powder_toy())),
+            Some(path) => Palette::load(path).map(Some),
+        }
+    }
+
+    /// Loads the locale of the interface.
+    pub fn locale(&self) -> anyhow::Result<Locale> {
+        match self.lang.as_deref() {
+            Some(lang) => Locale::load(lang),
+            None => Ok(std::env::var("LANG")
+                .ok()
+                .and_then(|lang| Locale::bundled(lang.split(['_', '.']).next()?))
+                .unwrap_or_default()),
+        }
+    }
+
+    /// Element bound to `key`, if the user rebound it.
+    pub fn element(&self, key: char) -> Option<PixelKind> {
+        self.keys
+            .iter()
+            .find(|binding| binding.key == key)
+            .map(|binding| binding.element)
+    }
+}
+
+/// User settings kept across sessions.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    #[serde(flatten)]
+    pub launch: LaunchConfig,
+    pub favorites: Vec<Favorite>,
+    /// Given to every new sandbox
+    pub sandbox: SandboxConfig,
+    /// Saves battery during long sessions left in the background, terminals that don't report
+    /// focus changes always count as focused
+    pub background: Background,
+    /// Ticks of the simulation run per second whatever the frame rate, from 1 to
+    /// [`Config::MAX_TPS`], 60 if neither it nor `substeps` is set. More of them speed the
+    /// simulation up without drawing more, but each costs as much CPU time as a tick alone does
+    pub tps: Option<u32>,
+    /// Ticks run for every frame drawn instead, from 1 to [`Config::MAX_SUBSTEPS`], so the
+    /// speed follows the frame rate. Ignored when `tps` is set
+    pub substeps: Option<u8>,
+    /// Elements added to the builtin ones, see [`PixelRegistry`]
+    pub elements: Vec<Descriptor>,
+}
+
+impl Config {
+    /// Loads the config from `path`, using the defaults if the file doesn't exist yet.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read config {}", path.display()))?;
+        Self::from_toml(&s).with_context(|| format!("failed to parse config {}", path.display()))
+    }
+
+    pub fn from_toml(s: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(s)?)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, toml::to_string(self)?)
+            .with_context(|| format!("failed to write config {}", path.display()))
+    }
+
+    /// Makes the elements of the config part of the game, before anything looks at the catalog.
+    pub fn register_elements(&self) -> anyhow::Result<()> {
+        let mut registry = PixelRegistry::default();
+        registry.reserve_keys(State::RESERVED_KEYS.chars());
+        for descriptor in &self.elements {
+            registry.register(descriptor.clone())?;
+        }
+        registry.install()
+    }
+
+    /// Speeds the simulation can be set to with the keys, in ticks per second
+    pub const TPS_STEPS: [u32; 13] = [1, 2, 5, 10, 15, 20, 30, 45, 60, 90, 120, 180, 240];
+    pub const MAX_TPS: u32 = 240;
+    const DEFAULT_TPS: u32 = 60;
+
+    /// Most ticks run per frame with `substeps`
+    pub const MAX_SUBSTEPS: u8 = 8;
+
+    /// Ticks run per second, at the frame rate set when the speed is given in substeps.
+    pub fn tps(&self) -> u32 {
+        let tps = match (self.tps, self.substeps) {
+            (Some(tps), _) => tps,
+            (None, Some(substeps)) => {
+                u32::from(substeps.clamp(1, Self::MAX_SUBSTEPS)) * self.launch.frame_rate()
+            }
+            (None, None) => Self::DEFAULT_TPS,
+        };
+        tps.clamp(1, Self::MAX_TPS)
+    }
+
+    /// Runs `tps` ticks per second, in place of any substeps.
+    pub fn set_tps(&mut self, tps: u32) {
+        self.tps = Some(tps);
+        self.substeps = None;
+    }
+
+    pub fn favorite(&self, slot: u8) -> Option<Favorite> {
+        self.favorites.iter().find(|f| f.slot == slot).copied()
+    }
+
+    /// Binds `favorite` to its slot, replacing what was bound there.
+    pub fn set_favorite(&mut self, favorite: Favorite) {
+        self.favorites.retain(|f| f.slot != favorite.slot);
+        self.favorites.push(favorite);
+        self.favorites.sort_by_key(|f| f.slot);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::config::{Config, LaunchConfig};
+
+    #[test]
+    fn test_launch_size() {
+        let mut launch = LaunchConfig::default();
+        launch.set("size", "200x100").unwrap();
+        assert_eq!(launch.size(), Some((200, 100)));
+        assert!(launch.set("size", "0x100").is_err());
+        assert!(launch.set("size", "100x100000").is_err());
+
+        // the config file isn't checked by `set`
+        let config: Config = toml::from_str("width = 0\nheight = 10").unwrap();
+        assert!(config.launch.check_size().is_err());
+    }
+
+    #[test]
+    fn test_substeps() {
+        let config = Config::from_toml("substeps = 2\ntick_rate = 30").unwrap();
+        assert_eq!(config.tps(), 60);
+        assert!(toml::to_string(&config).unwrap().contains("substeps = 2"));
+
+        // too many ticks for the speeds the keys reach
+        let config = Config::from_toml("substeps = 8").unwrap();
+        assert_eq!(config.tps(), Config::MAX_TPS);
+
+        let mut config = Config::from_toml("substeps = 2\ntps = 30").unwrap();
+        assert_eq!(config.tps(), 30);
+
+        // setting the speed drops the substeps
+        config.set_tps(90);
+        assert_eq!((config.tps, config.substeps), (Some(90), None));
+    }
+}
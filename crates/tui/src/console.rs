@@ -0,0 +1,363 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use rhai::{Dynamic, Engine, EvalAltResult, Map, Scope};
+
+use engine::command::Command;
+use engine::pixel::{Pixel, PixelFundamental};
+use engine::sandbox::Sandbox;
+use engine::temperature;
+
+use crate::config::Config;
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+/// The sandbox as the scripts see it, copied before every snippet, and the commands they queue.
+#[derive(Debug, Default)]
+struct World {
+    width: usize,
+    height: usize,
+    tick: u64,
+    /// Pixel and temperature of every cell, row major
+    cells: Vec<(Pixel, u8)>,
+    census: BTreeMap<&'static str, usize>,
+    /// Speed settings, which the snippet may change
+    config: Config,
+    commands: Vec<Command>,
+    /// Lines printed by the snippet
+    output: Vec<String>,
+    /// When the snippet started running, to stop it once it runs too long
+    started: Option<Instant>,
+}
+
+impl World {
+    fn capture<R: Rng>(sandbox: &Sandbox<R>, config: &Config) -> Self {
+        Self {
+            width: sandbox.width,
+            height: sandbox.height,
+            tick: sandbox.tick_count(),
+            cells: sandbox
+                .pixels
+                .iter()
+                .map(|cell| (cell.pixel(), cell.temperature()))
+                .collect(),
+            census: sandbox.census(),
+            config: config.clone(),
+            commands: Vec::new(),
+            output: Vec::new(),
+            started: Some(Instant::now()),
+        }
+    }
+
+    fn cell(&self, x: i64, y: i64) -> ScriptResult<(Pixel, u8)> {
+        match (usize::try_from(x), usize::try_from(y)) {
+            (Ok(x), Ok(y)) if x < self.width && y < self.height => {
+                Ok(self.cells[y * self.width + x])
+            }
+            _ => Err(format!("({}, {}) is outside of the sandbox", x, y).into()),
+        }
+    }
+
+    /// The part of the `width` by `height` rectangle starting at (`x`, `y`) inside the sandbox,
+    /// so scripts can't queue commands covering more cells than there are.
+    fn clamp(&self, x: i64, y: i64, width: i64, height: i64) -> ScriptResult<[usize; 4]> {
+        let (x, y) = (coordinate(x)?, coordinate(y)?);
+        Ok([
+            x,
+            y,
+            coordinate(width)?.min(self.width.saturating_sub(x)),
+            coordinate(height)?.min(self.height.saturating_sub(y)),
+        ])
+    }
+}
+
+fn element(name: &str) -> ScriptResult<Pixel> {
+    Pixel::from_name(name).ok_or_else(|| format!("no element named {}", name).into())
+}
+
+fn coordinate(value: i64) -> ScriptResult<usize> {
+    usize::try_from(value).map_err(|_| format!("{} is outside of the sandbox", value).into())
+}
+
+/// Developer console evaluating Rhai snippets against the live sandbox, to reproduce bugs by
+/// hand.
+///
+/// Snippets read the sandbox as it was when they were entered, and change it through commands
+/// applied at the start of the next tick, like the mouse does. Variables carry over from one
+/// snippet to the next.
+#[derive(Debug)]
+pub struct Console {
+    engine: Engine,
+    scope: Scope<'static>,
+    world: Rc<RefCell<World>>,
+    /// Snippet being typed
+    pub input: String,
+    /// Snippets entered and what they printed or returned, oldest first
+    pub log: Vec<String>,
+    /// Snippets entered, recalled with `Up` and `Down`
+    history: Vec<String>,
+    /// Entry of the history shown in the input
+    recalled: Option<usize>,
+}
+
+impl Default for Console {
+    fn default() -> Self {
+        let world = Rc::new(RefCell::new(World::default()));
+        let mut engine = Engine::new();
+        // snippets run on the game thread, a runaway loop would freeze the game
+        engine
+            .set_max_operations(Self::MAX_OPERATIONS)
+            .set_max_call_levels(Self::MAX_CALL_LEVELS);
+        let w = world.clone();
+        engine.on_progress(move |_| {
+            let started = w.try_borrow().ok()?.started?;
+            (started.elapsed() > Self::TIME_LIMIT).then(|| "the snippet ran too long".into())
+        });
+
+        let w = world.clone();
+        engine.on_print(move |line| w.borrow_mut().output.push(line.to_string()));
+        let w = world.clone();
+        engine.on_debug(move |line, _, _| w.borrow_mut().output.push(line.to_string()));
+
+        let w = world.clone();
+        engine.register_fn("width", move || w.borrow().width as i64);
+        let w = world.clone();
+        engine.register_fn("height", move || w.borrow().height as i64);
+        let w = world.clone();
+        engine.register_fn("tick", move || w.borrow().tick as i64);
+        let w = world.clone();
+        engine.register_fn("tps", move || w.borrow().config.tps() as i64);
+        let w = world.clone();
+        engine.register_fn("set_tps", move |tps: i64| -> ScriptResult<()> {
+            let tps = u32::try_from(tps)
+                .ok()
+                .filter(|tps| (1..=Config::MAX_TPS).contains(tps))
+                .ok_or_else(|| format!("the speed goes from 1 to {} tps", Config::MAX_TPS))?;
+            w.borrow_mut().config.set_tps(tps);
+            Ok(())
+        });
+        let w = world.clone();
+        engine.register_fn("substeps", move || {
+            w.borrow().config.substeps.map_or(0, i64::from)
+        });
+        let w = world.clone();
+        engine.register_fn("set_substeps", move |substeps: i64| -> ScriptResult<()> {
+            let substeps = u8::try_from(substeps)
+                .ok()
+                .filter(|substeps| (1..=Config::MAX_SUBSTEPS).contains(substeps))
+                .ok_or_else(|| format!("substeps go from 1 to {} a frame", Config::MAX_SUBSTEPS))?;
+            let config = &mut w.borrow_mut().config;
+            (config.tps, config.substeps) = (None, Some(substeps));
+            Ok(())
+        });
+        let w = world.clone();
+        engine.register_fn("pixel", move |x: i64, y: i64| -> ScriptResult<String> {
+            let (pixel, _) = w.borrow().cell(x, y)?;
+            Ok(pixel.name().to_string())
+        });
+        let w = world.clone();
+        engine.register_fn("temperature", move |x: i64, y: i64| -> ScriptResult<i64> {
+            let (_, temp) = w.borrow().cell(x, y)?;
+            Ok(temperature::to_celsius(temp) as i64)
+        });
+        let w = world.clone();
+        engine.register_fn("census", move || {
+            w.borrow()
+                .census
+                .iter()
+                .map(|(&name, &count)| (name.into(), Dynamic::from(count as i64)))
+                .collect::<Map>()
+        });
+        let w = world.clone();
+        engine.register_fn("count", move |name: &str| -> ScriptResult<i64> {
+            let name = element(name)?.name();
+            Ok(w.borrow().census.get(name).copied().unwrap_or(0) as i64)
+        });
+
+        let w = world.clone();
+        engine.register_fn(
+            "fill",
+            move |name: &str, x: i64, y: i64, width: i64, height: i64| -> ScriptResult<()> {
+                let [x, y, width, height] = w.borrow().clamp(x, y, width, height)?;
+                let command = Command::PlaceBrush {
+                    pixel: element(name)?,
+                    x,
+                    y,
+                    width,
+                    height,
+                    force: true,
+                };
+                w.borrow_mut().commands.push(command);
+                Ok(())
+            },
+        );
+        let w = world.clone();
+        engine.register_fn(
+            "place",
+            move |name: &str, x: i64, y: i64| -> ScriptResult<()> {
+                let command = Command::PlaceBrush {
+                    pixel: element(name)?,
+                    x: coordinate(x)?,
+                    y: coordinate(y)?,
+                    width: 1,
+                    height: 1,
+                    force: true,
+                };
+                w.borrow_mut().commands.push(command);
+                Ok(())
+            },
+        );
+        let w = world.clone();
+        engine.register_fn(
+            "trigger",
+            move |source: &str, x: i64, y: i64| -> ScriptResult<()> {
+                let command = Command::TriggerEvent {
+                    source: element(source)?,
+                    x: coordinate(x)?,
+                    y: coordinate(y)?,
+                };
+                w.borrow_mut().commands.push(command);
+                Ok(())
+            },
+        );
+
+        Self {
+            engine,
+            scope: Scope::new(),
+            world,
+            input: String::new(),
+            log: vec![Self::HELP.to_string()],
+            history: Vec::new(),
+            recalled: None,
+        }
+    }
+}
+
+impl Console {
+    const HELP: &'static str = "place(element, x, y), fill(element, x, y, width, height)\n\
+        trigger(source, x, y), pixel(x, y), temperature(x, y)\n\
+        census(), count(element), tick(), width(), height()\n\
+        tps(), set_tps(tps), substeps(), set_substeps(substeps)";
+    /// Lines of the log kept, the oldest ones are dropped first
+    const LOG_LINES: usize = 200;
+    /// Operations a snippet may run, enough for loops over every cell of a large sandbox
+    const MAX_OPERATIONS: u64 = 50_000_000;
+    const MAX_CALL_LEVELS: usize = 64;
+    /// Time a snippet may run, well under what makes the game look stuck
+    const TIME_LIMIT: Duration = Duration::from_secs(2);
+
+    /// Evaluates the snippet typed so far against `sandbox`, queueing the commands it issued and
+    /// keeping the speed it set in `config`.
+    pub fn submit<R: Rng>(&mut self, sandbox: &mut Sandbox<R>, config: &mut Config) {
+        let snippet = std::mem::take(&mut self.input);
+        self.recalled = None;
+        if snippet.trim().is_empty() {
+            return;
+        }
+        self.log.push(format!("> {}", snippet));
+        if self.history.last() != Some(&snippet) {
+            self.history.push(snippet.clone());
+        }
+
+        *self.world.borrow_mut() = World::capture(sandbox, config);
+        let result = self
+            .engine
+            .eval_with_scope::<Dynamic>(&mut self.scope, &snippet);
+        let world = self.world.take();
+        self.log.extend(world.output);
+        match result {
+            Ok(value) if value.is_unit() => {}
+            Ok(value) => self.log.push(value.to_string()),
+            Err(e) => self.log.push(format!("error: {}", e)),
+        }
+        for command in world.commands {
+            sandbox.queue_command(command);
+        }
+        config.tps = world.config.tps;
+        config.substeps = world.config.substeps;
+
+        let overflow = self.log.len().saturating_sub(Self::LOG_LINES);
+        self.log.drain(..overflow);
+    }
+
+    /// Shows the snippet entered `delta` entries away from the one shown, newer for positive
+    /// ones, going back to an empty input past the newest.
+    pub fn recall(&mut self, delta: isize) {
+        let next = match self.recalled {
+            Some(idx) => idx.checked_add_signed(delta),
+            None if delta < 0 => self.history.len().checked_sub(1),
+            None => None,
+        };
+        match next.filter(|&idx| idx < self.history.len()) {
+            Some(idx) => {
+                self.recalled = Some(idx);
+                self.input = self.history[idx].clone();
+            }
+            // stays on the oldest entry rather than wrapping around
+            None if delta < 0 && self.recalled.is_some() => {}
+            None => {
+                self.recalled = None;
+                self.input.clear();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use engine::sandbox::Sandbox;
+    use rand::rngs::SmallRng;
+
+    use crate::config::Config;
+    use crate::console::Console;
+
+    #[test]
+    fn test_console_tps() {
+        let mut console = Console::default();
+        let mut sandbox = Sandbox::<SmallRng>::new(4, 4);
+        let mut config = Config::default();
+        console.input = "set_tps(tps() * 2)".to_string();
+        console.submit(&mut sandbox, &mut config);
+        assert_eq!(config.tps, Some(120));
+
+        console.input = "set_tps(0)".to_string();
+        console.submit(&mut sandbox, &mut config);
+        assert!(console.log.last().unwrap().starts_with("error"));
+        assert_eq!(config.tps, Some(120));
+
+        console.input = "set_substeps(2); substeps()".to_string();
+        console.submit(&mut sandbox, &mut config);
+        assert_eq!(console.log.last().unwrap(), "2");
+        assert_eq!((config.tps, config.substeps), (None, Some(2)));
+    }
+
+    #[test]
+    fn test_console_limits() {
+        let mut console = Console::default();
+        let mut sandbox = Sandbox::<SmallRng>::new(4, 4);
+        let mut config = Config::default();
+        console.input = "loop {}".to_string();
+        console.submit(&mut sandbox, &mut config);
+        assert!(console.log.last().unwrap().starts_with("error"));
+
+        // only the cells inside of the sandbox are queued
+        console.input = "fill(\"sand\", 2, 1, 1000000000000, 1000000000000)".to_string();
+        console.submit(&mut sandbox, &mut config);
+        sandbox.apply_commands();
+        assert_eq!(sandbox.census()["Sand"], 6);
+        assert_eq!(
+            sandbox
+                .input_log()
+                .unwrap()
+                .last()
+                .unwrap()
+                .1
+                .cells()
+                .count(),
+            6
+        );
+    }
+}
@@ -0,0 +1,79 @@
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use crossterm::style::Stylize;
+
+use engine::catalog;
+use engine::pixel::PixelKind;
+use engine::snapshot::{CellChange, Snapshot};
+
+const USAGE: &str = "usage: rustfall diff <before.rfs> <after.rfs> [--summary]";
+
+/// Options of a snapshot comparison.
+#[derive(Debug)]
+pub struct DiffOptions {
+    before: PathBuf,
+    after: PathBuf,
+    /// Only print the counts, for grids wider than the terminal
+    summary: bool,
+}
+
+impl DiffOptions {
+    pub fn parse(args: &[String]) -> anyhow::Result<Self> {
+        let mut paths = Vec::new();
+        let mut summary = false;
+        for arg in args {
+            match arg.as_str() {
+                "--summary" => summary = true,
+                s if !s.starts_with("--") => paths.push(PathBuf::from(s)),
+                _ => anyhow::bail!(USAGE),
+            }
+        }
+        let [before, after] = <[PathBuf; 2]>::try_from(paths).map_err(|_| anyhow!(USAGE))?;
+        Ok(Self {
+            before,
+            after,
+            summary,
+        })
+    }
+}
+
+/// Prints the cells that differ between two snapshots, coloured by how they changed.
+pub fn run(options: DiffOptions) -> anyhow::Result<()> {
+    let before = Snapshot::load(&options.before)?;
+    let after = Snapshot::load(&options.after)?;
+    let diff = before.diff(&after)?;
+
+    if !options.summary {
+        let hotkey = |kind: PixelKind| catalog::element(&kind.pixel()).hotkey;
+        for row in diff.cells.chunks(diff.width) {
+            let line = row
+                .iter()
+                .map(|&change| match change {
+                    CellChange::Unchanged(PixelKind::Void) => " ".to_string(),
+                    CellChange::Unchanged(kind) => hotkey(kind).dark_grey().to_string(),
+                    CellChange::Added(kind) => hotkey(kind).green().to_string(),
+                    CellChange::Removed(kind) => hotkey(kind).red().to_string(),
+                    CellChange::Changed { to, .. } => hotkey(to).yellow().to_string(),
+                })
+                .collect::<String>();
+            println!("{}", line);
+        }
+        println!();
+    }
+
+    println!(
+        "{}x{} cells, tick {} to {}",
+        diff.width, diff.height, before.ticks, after.ticks
+    );
+    match diff.is_empty() {
+        true => println!("identical"),
+        false => println!(
+            "{} added, {} removed, {} changed",
+            diff.added().to_string().green(),
+            diff.removed().to_string().red(),
+            diff.changed().to_string().yellow()
+        ),
+    }
+    Ok(())
+}
@@ -18,6 +18,10 @@ pub enum Event {
     Mouse(MouseEvent),
     /// Terminal resize.
     Resize(u16, u16),
+    /// Terminal window focused.
+    FocusGained,
+    /// Terminal window sent to the background.
+    FocusLost,
 }
 
 /// Terminal event handler.
@@ -58,6 +62,8 @@ impl EventHandler {
                             }
                             CrosstermEvent::Mouse(e) => sender.send(Event::Mouse(e)),
                             CrosstermEvent::Resize(w, h) => sender.send(Event::Resize(w, h)),
+                            CrosstermEvent::FocusGained => sender.send(Event::FocusGained),
+                            CrosstermEvent::FocusLost => sender.send(Event::FocusLost),
                             _ => unimplemented!(),
                         }
                         .expect("failed to send terminal event")
@@ -0,0 +1,128 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::Path;
+use std::sync::OnceLock;
+
+use anyhow::{anyhow, Context};
+use serde::Deserialize;
+
+/// Languages shipped with rustfall, by code.
+const BUNDLED: [(&str, &str); 2] = [
+    ("en", include_str!("../locales/en.toml")),
+    ("fr", include_str!("../locales/fr.toml")),
+];
+
+/// Text of the interface in one language, anything it leaves out is shown in English.
+///
+/// Elements keep their engine name as the identifier used in files and on the command line,
+/// only the name shown to the player is translated.
+#[derive(Debug, Default, Clone, Deserialize)]
+#[serde(default)]
+pub struct Locale {
+    /// Shown names by engine name
+    elements: HashMap<String, String>,
+    /// UI strings by key, with `{name}` placeholders for the values put in
+    strings: HashMap<String, String>,
+}
+
+static LOCALE: OnceLock<Locale> = OnceLock::new();
+
+impl Locale {
+    pub fn from_toml(s: &str) -> anyhow::Result<Self> {
+        Ok(toml::from_str(s)?)
+    }
+
+    /// Locale bundled under `code`, like `fr`.
+    pub fn bundled(code: &str) -> Option<Self> {
+        let (_, s) = BUNDLED.iter().find(|(c, _)| c.eq_ignore_ascii_case(code))?;
+        Some(Self::from_toml(s).expect("bundled locales are valid"))
+    }
+
+    /// The bundled locale `lang` names, or else the locale file at that path.
+    pub fn load(lang: &str) -> anyhow::Result<Self> {
+        if let Some(locale) = Self::bundled(lang) {
+            return Ok(locale);
+        }
+        let path = Path::new(lang);
+        if !path.exists() {
+            let codes = BUNDLED.map(|(code, _)| code).join(", ");
+            anyhow::bail!("no language {}, expected one of {} or a file", lang, codes);
+        }
+        let s = std::fs::read_to_string(path)
+            .with_context(|| format!("failed to read locale {}", path.display()))?;
+        Self::from_toml(&s).with_context(|| format!("failed to parse locale {}", path.display()))
+    }
+
+    /// Makes the locale the one every string is shown in, before any is.
+    pub fn install(self) -> anyhow::Result<()> {
+        LOCALE
+            .set(self)
+            .map_err(|_| anyhow!("the language can only be set once"))
+    }
+}
+
+/// The installed locale, with nothing translated if none was installed.
+fn installed() -> &'static Locale {
+    LOCALE.get_or_init(Locale::default)
+}
+
+fn english() -> &'static Locale {
+    static ENGLISH: OnceLock<Locale> = OnceLock::new();
+    ENGLISH.get_or_init(|| Locale::bundled("en").expect("English is bundled"))
+}
+
+/// UI string `key` in the installed language, the key itself if no locale has it.
+pub fn text(key: &'static str) -> &'static str {
+    [installed(), english()]
+        .into_iter()
+        .find_map(|locale| locale.strings.get(key))
+        .map_or(key, String::as_str)
+}
+
+/// UI string `key` with each of its placeholders replaced by the value of the same name.
+pub fn format(key: &'static str, values: &[(&str, &dyn Display)]) -> String {
+    values
+        .iter()
+        .fold(text(key).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), &value.to_string())
+        })
+}
+
+/// Name shown for the element named `name` in the engine.
+pub fn element(name: &'static str) -> &'static str {
+    installed().elements.get(name).map_or(name, String::as_str)
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use crate::locale::{Locale, BUNDLED};
+    use engine::catalog;
+
+    #[test]
+    fn test_bundled_keys() {
+        let english = Locale::bundled("en").unwrap();
+        let keys: BTreeSet<_> = english.strings.keys().collect();
+        for (code, _) in BUNDLED {
+            let locale = Locale::bundled(code).unwrap();
+            assert_eq!(
+                locale.strings.keys().collect::<BTreeSet<_>>(),
+                keys,
+                "strings of {}",
+                code
+            );
+            // English names the elements as the engine does
+            if code != "en" {
+                for element in catalog::catalog() {
+                    assert!(
+                        locale.elements.contains_key(element.name),
+                        "{} has no name for {}",
+                        code,
+                        element.name
+                    );
+                }
+            }
+        }
+    }
+}
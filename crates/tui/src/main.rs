@@ -1,10 +1,148 @@
+#[cfg(feature = "audio")]
+mod audio;
+mod bench;
+mod compare;
+mod config;
+#[cfg(feature = "console")]
+mod console;
+mod diff;
 mod event;
+mod locale;
+mod metrics;
+mod paths;
 mod render;
+mod soak;
+mod spectate;
 mod state;
 mod tui;
+mod video;
+
+use std::path::PathBuf;
+
+use anyhow::{anyhow, Context};
+
+use engine::pixel::Pixel;
+
+use crate::config::{Config, LaunchConfig};
+
+const USAGE: &str = "usage: rustfall [--serve <addr>] [--metrics <addr>] [--paused] [--ticks <n>] [--scene <file.toml>] [--place <x>,<y>,<element>]... [--render braille|block] [--theme <palette>] [--size <w>x<h>] [--seed <n>] [--tick-rate <fps>] [--autosave <secs>] [--lang <code|file.toml>] [--dump-config] | rustfall attach <addr> | rustfall soak [<options>] | rustfall bench [<options>] | rustfall diff <before.rfs> <after.rfs> | rustfall compare [<options>] | rustfall render <scene.rfs> [<options>]";
+
+/// Options of an interactive session, handy to reproduce a situation from a shell.
+#[derive(Debug, Default)]
+pub struct PlayOptions {
+    serve: Option<String>,
+    /// Address serving the simulation metrics at `/metrics`
+    metrics: Option<String>,
+    /// Start with the simulation paused
+    paused: bool,
+    /// Exit after this many ticks of the simulation
+    ticks: Option<u64>,
+    /// Scene file the sandbox starts from, see [`engine::scene::Scene`]
+    scene: Option<PathBuf>,
+    /// Pixels placed before the first tick
+    place: Vec<(usize, usize, Pixel)>,
+    /// Launch settings overriding the config file and the environment, with their values
+    launch: Vec<(&'static str, String)>,
+    /// Print the config in effect and exit
+    dump_config: bool,
+}
+
+impl PlayOptions {
+    pub fn parse(args: &[String]) -> anyhow::Result<Self> {
+        let mut options = Self::default();
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| anyhow!(USAGE));
+            match arg.as_str() {
+                "--serve" => options.serve = Some(value()?.clone()),
+                "--metrics" => options.metrics = Some(value()?.clone()),
+                "--paused" => options.paused = true,
+                "--ticks" => options.ticks = Some(value()?.parse().context(USAGE)?),
+                "--scene" => options.scene = Some(PathBuf::from(value()?)),
+                "--place" => options.place.push(Self::parse_placement(value()?)?),
+                "--dump-config" => options.dump_config = true,
+                flag => match LaunchConfig::OVERRIDES.iter().find(|(_, f)| *f == flag) {
+                    Some(&(key, _)) => options.launch.push((key, value()?.clone())),
+                    None => anyhow::bail!(USAGE),
+                },
+            }
+        }
+        Ok(options)
+    }
+
+    /// The launch settings in effect: the defaults, overridden by the config file, then by the
+    /// `RUSTFALL_*` environment variables, then by the command line.
+    fn launch_config(&self, config: &Config) -> anyhow::Result<LaunchConfig> {
+        let mut launch = config.launch.clone();
+        launch.apply_env()?;
+        for (key, value) in &self.launch {
+            launch.set(key, value).context(USAGE)?;
+        }
+        launch.check_size()?;
+        Ok(launch)
+    }
+
+    /// Parses `x,y,element`.
+    fn parse_placement(s: &str) -> anyhow::Result<(usize, usize, Pixel)> {
+        let [x, y, element] = s.splitn(3, ',').collect::<Vec<_>>()[..] else {
+            anyhow::bail!("expected a placement like 10,20,sand, got {}", s);
+        };
+        let pixel = Pixel::from_name(element.trim())
+            .ok_or_else(|| anyhow!("unknown element {}", element))?;
+        Ok((
+            x.trim().parse().context(USAGE)?,
+            y.trim().parse().context(USAGE)?,
+            pixel,
+        ))
+    }
+}
 
 fn main() -> anyhow::Result<()> {
-    let mut tui = tui::Tui::try_new(false)?;
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let config = match paths::config_file() {
+        Some(path) => Config::load(path)?,
+        None => Config::default(),
+    };
+    // every command may come across the elements of the config, in scenes or snapshots
+    config.register_elements()?;
+    match args.as_slice() {
+        [command, addr] if command == "attach" => return spectate::attach(addr, false),
+        [command, options @ ..] if command == "soak" => {
+            return soak::run(soak::SoakOptions::parse(options)?)
+        }
+        [command, options @ ..] if command == "bench" => {
+            return bench::run(bench::BenchOptions::parse(options)?)
+        }
+        [command, options @ ..] if command == "compare" => {
+            return compare::run(compare::CompareOptions::parse(options)?)
+        }
+        [command, options @ ..] if command == "diff" => {
+            return diff::run(diff::DiffOptions::parse(options)?)
+        }
+        [command, options @ ..] if command == "render" => {
+            return video::run(video::VideoOptions::parse(options)?)
+        }
+        _ => {}
+    }
+    let options = PlayOptions::parse(&args)?;
+    let launch = options.launch_config(&config)?;
+    if options.dump_config {
+        let effective = Config { launch, ..config };
+        print!("{}", toml::to_string(&effective)?);
+        return Ok(());
+    }
+    launch.locale()?.install()?;
+
+    let mut tui = tui::Tui::try_new(config, launch)?;
+    if let Some(addr) = &options.serve {
+        tui.serve(addr)?;
+    }
+    if let Some(addr) = &options.metrics {
+        tui.serve_metrics(addr)?;
+    }
+    tui.offer_recovery()?;
+    tui.configure(&options)?;
     tui.enter()?;
     tui.run()?;
     tui.exit()?;
@@ -0,0 +1,214 @@
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use rand::Rng;
+use serde::Serialize;
+
+use engine::sandbox::{Sandbox, TickTimings};
+
+/// Figures of a running simulation, sampled for monitoring.
+#[derive(Debug, Clone, Serialize)]
+pub struct Metrics {
+    pub tick: u64,
+    pub tps: f64,
+    pub rss_bytes: Option<u64>,
+    /// Cells holding something else than void
+    pub active_pixels: usize,
+    pub census: BTreeMap<&'static str, usize>,
+    /// Average time of each phase of the ticks since the previous sample, in seconds
+    pub movement_seconds: f64,
+    pub interaction_seconds: f64,
+    pub bookkeeping_seconds: f64,
+}
+
+impl Metrics {
+    /// The metrics in the Prometheus text exposition format.
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, f64)]| {
+            let _ = writeln!(out, "# HELP rustfall_{} {}", name, help);
+            let _ = writeln!(out, "# TYPE rustfall_{} {}", name, kind);
+            for (labels, value) in samples {
+                let _ = writeln!(out, "rustfall_{}{} {}", name, labels, value);
+            }
+        };
+
+        metric(
+            "ticks_total",
+            "counter",
+            "Ticks run since the start.",
+            &[(String::new(), self.tick as f64)],
+        );
+        metric(
+            "ticks_per_second",
+            "gauge",
+            "Ticks run per second since the previous sample.",
+            &[(String::new(), self.tps)],
+        );
+        if let Some(rss) = self.rss_bytes {
+            metric(
+                "resident_memory_bytes",
+                "gauge",
+                "Resident memory of the process.",
+                &[(String::new(), rss as f64)],
+            );
+        }
+        metric(
+            "active_pixels",
+            "gauge",
+            "Cells holding something else than void.",
+            &[(String::new(), self.active_pixels as f64)],
+        );
+        let census = self
+            .census
+            .iter()
+            .map(|(name, count)| (format!("{{element=\"{}\"}}", name), *count as f64))
+            .collect::<Vec<_>>();
+        metric("pixels", "gauge", "Cells holding each element.", &census);
+        let phases = [
+            ("movement", self.movement_seconds),
+            ("interaction", self.interaction_seconds),
+            ("bookkeeping", self.bookkeeping_seconds),
+        ]
+        .map(|(phase, seconds)| (format!("{{phase=\"{}\"}}", phase), seconds));
+        metric(
+            "tick_phase_seconds",
+            "gauge",
+            "Average time of each phase of the ticks since the previous sample.",
+            &phases,
+        );
+        out
+    }
+}
+
+/// Resident memory of the process in bytes, where the platform tells.
+fn resident_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kb = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+    Some(kb * 1024)
+}
+
+/// Adds up the ticks of a sandbox between two samples.
+#[derive(Debug)]
+pub struct MetricsSampler {
+    since: Instant,
+    /// Ticks run since the previous sample
+    ticks: u64,
+    timings: TickTimings,
+    /// Tick count of the sandbox when last recorded, so paused frames aren't counted
+    last_tick: Option<u64>,
+}
+
+impl Default for MetricsSampler {
+    fn default() -> Self {
+        Self {
+            since: Instant::now(),
+            ticks: 0,
+            timings: TickTimings::default(),
+            last_tick: None,
+        }
+    }
+}
+
+impl MetricsSampler {
+    /// Counts the ticks `sandbox` ran since the previous call.
+    pub fn record<R: Rng>(&mut self, sandbox: &Sandbox<R>) {
+        let tick = sandbox.tick_count();
+        if self.last_tick == Some(tick) {
+            return;
+        }
+        // several ticks may have run since, taken to be as long as the last one
+        let ticks = self.last_tick.map_or(1, |last| tick.saturating_sub(last)) as u32;
+        self.last_tick = Some(tick);
+        self.ticks += ticks as u64;
+        let timings = sandbox.last_tick_timings();
+        self.timings.movement += timings.movement * ticks;
+        self.timings.interaction += timings.interaction * ticks;
+        self.timings.bookkeeping += timings.bookkeeping * ticks;
+    }
+
+    /// Ticks recorded since the previous sample.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Takes a sample of `sandbox` and starts the next one.
+    pub fn sample<R: Rng>(&mut self, sandbox: &Sandbox<R>) -> Metrics {
+        let elapsed = self.since.elapsed().as_secs_f64();
+        let ticks = self.ticks.max(1) as f64;
+        let per_tick = |time: Duration| time.as_secs_f64() / ticks;
+        let census = sandbox.census();
+        let metrics = Metrics {
+            tick: sandbox.tick_count(),
+            tps: self.ticks as f64 / elapsed.max(f64::EPSILON),
+            rss_bytes: resident_memory(),
+            active_pixels: sandbox.pixels.len() - census.get("Void").copied().unwrap_or(0),
+            census,
+            movement_seconds: per_tick(self.timings.movement),
+            interaction_seconds: per_tick(self.timings.interaction),
+            bookkeeping_seconds: per_tick(self.timings.bookkeeping),
+        };
+        *self = Self {
+            last_tick: self.last_tick,
+            ..Self::default()
+        };
+        metrics
+    }
+}
+
+/// Serves the latest sample in the Prometheus text format at `/metrics`.
+pub struct MetricsServer {
+    latest: Arc<Mutex<String>>,
+}
+
+impl MetricsServer {
+    pub fn bind(addr: impl ToSocketAddrs) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let latest = Arc::new(Mutex::new(String::new()));
+        let served = latest.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                // a scraper that went away doesn't stop the others
+                let _ = Self::respond(stream, &served);
+            }
+        });
+        Ok(Self { latest })
+    }
+
+    fn respond(mut stream: TcpStream, latest: &Mutex<String>) -> anyhow::Result<()> {
+        // a scraper that stops reading or writing would hold up every other one
+        stream.set_read_timeout(Some(Duration::from_secs(1)))?;
+        stream.set_write_timeout(Some(Duration::from_secs(1)))?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+        let mut request = String::new();
+        reader.read_line(&mut request)?;
+        // the headers aren't needed, but the client expects them to be read
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+
+        let (status, body) = match request.split_whitespace().nth(1) {
+            Some("/metrics") => ("200 OK", latest.lock().unwrap().clone()),
+            _ => ("404 Not Found", "not found, try /metrics\n".to_string()),
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {}\r\nContent-Type: text/plain; version=0.0.4\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )?;
+        Ok(())
+    }
+
+    pub fn publish(&self, metrics: &Metrics) {
+        *self.latest.lock().unwrap() = metrics.to_prometheus();
+    }
+}
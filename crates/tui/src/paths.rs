@@ -0,0 +1,42 @@
+use std::env;
+use std::path::PathBuf;
+
+/// Directory holding data kept across sessions, following the XDG base directory spec.
+pub fn data_dir() -> Option<PathBuf> {
+    env::var_os("XDG_DATA_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")))
+        .map(|dir| dir.join("rustfall"))
+}
+
+pub fn stats_file() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("stats.toml"))
+}
+
+/// Scene saved when the last session crashed or autosaved, offered for restoring on the next
+/// launch.
+pub fn recovery_file() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("recovery.rfs"))
+}
+
+/// Directory of the `.rfs` scenes listed in the level menu after the levels.
+pub fn scenes_dir() -> Option<PathBuf> {
+    data_dir().map(|dir| dir.join("scenes"))
+}
+
+/// Directory holding user settings, following the XDG base directory spec.
+pub fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        .map(|dir| dir.join("rustfall"))
+}
+
+pub fn config_file() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("config.toml"))
+}
+
+/// Reaction rates overriding the builtin ones, if the user tuned them.
+pub fn reactions_file() -> Option<PathBuf> {
+    config_dir().map(|dir| dir.join("reactions.toml"))
+}
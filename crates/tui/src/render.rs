@@ -1,15 +1,20 @@
+use std::cmp::Ordering;
 use std::ops::Deref;
 use std::sync::OnceLock;
+use std::time::Duration;
 
+use engine::effect::{EffectKind, Effects};
 use engine::fps_tracker::FpsTracker;
-use itertools::Itertools;
 use rand::Rng;
-use ratatui::layout::{Alignment, Constraint, Direction, Layout};
+use ratatui::layout::{Alignment, Constraint, Direction, Layout, Margin, Rect};
 use ratatui::prelude::Marker;
 use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
 use ratatui::widgets::block::{Position, Title};
 use ratatui::widgets::canvas::{Canvas, Painter, Shape};
-use ratatui::widgets::{List, ListItem, ListState};
+use ratatui::widgets::{
+    Bar, BarChart, BarGroup, Clear, List, ListItem, ListState, Paragraph, Wrap,
+};
 use ratatui::{
     prelude::Frame,
     style::Color,
@@ -18,18 +23,30 @@ use ratatui::{
 };
 use strum::IntoEnumIterator;
 
-use crate::state::{PixelHotkey, State};
-use engine::pixel::{Pixel, PixelFundamental};
+use crate::config::BrushShape;
+use crate::locale;
+use crate::state::{Overlay, State, Tool};
+use engine::annotation::MoveRule;
+use engine::catalog;
+use engine::entity::EntityKind;
+use engine::level::{LevelSession, LevelStatus};
+use engine::palette::{Palette, Rgb};
+use engine::pixel::spawner::Spawner;
+use engine::pixel::{Gravity, Pixel, PixelFundamental};
 use engine::sandbox::Sandbox;
+use engine::stats::Stats;
 
 pub struct Renderer {
     no_braille: bool,
+    /// Colours the sandbox is drawn with instead of the terminal ones
+    theme: Option<Palette>,
     fps_tracker: FpsTracker,
 }
 impl Renderer {
-    pub fn new(no_braille: bool) -> Self {
+    pub fn new(no_braille: bool, theme: Option<Palette>) -> Self {
         Self {
             no_braille,
+            theme,
             fps_tracker: Default::default(),
         }
     }
@@ -40,15 +57,551 @@ impl Renderer {
     fn list_items() -> &'static [ListItem<'static>] {
         static CELL: OnceLock<Vec<ListItem<'static>>> = OnceLock::new();
         CELL.get_or_init(|| {
-            Pixel::iter()
-                .sorted_by_key(|pixel| pixel.hotkey())
-                .map(|pixel| ListItem::new(format!("[{}]{}", pixel.hotkey(), pixel.name())))
+            catalog::catalog()
+                .iter()
+                .map(|e| ListItem::new(format!("[{}]{}", e.keys(), locale::element(e.name))))
                 .collect::<Vec<_>>()
         })
     }
 
+    /// Palette of a level or a restricted sandbox, with disallowed elements greyed out and the
+    /// level's budgets next to the elements.
+    fn restricted_list_items(state: &State) -> Vec<ListItem<'static>> {
+        catalog::catalog()
+            .iter()
+            .map(|e| {
+                let budget = state
+                    .level
+                    .as_ref()
+                    .and_then(|level| level.remaining_budget(e.pixel()));
+                let item = ListItem::new(match budget {
+                    Some(budget) => {
+                        format!("[{}]{} ({})", e.keys(), locale::element(e.name), budget)
+                    }
+                    None => format!("[{}]{}", e.keys(), locale::element(e.name)),
+                });
+                match state.is_pixel_allowed(e.pixel()) {
+                    true => item,
+                    false => item.style(Style::default().fg(Color::DarkGray)),
+                }
+            })
+            .collect()
+    }
+
+    fn format_time(ticks: u64, time: Duration) -> String {
+        let secs = time.as_secs();
+        let time = format!(
+            "{}:{:02}.{}",
+            secs / 60,
+            secs % 60,
+            time.subsec_millis() / 100
+        );
+        locale::format("level_time", &[("ticks", &ticks), ("time", &time)])
+    }
+
+    fn level_title(level: &LevelSession, stats: &Stats) -> String {
+        let progress = level.progress();
+        let status = match progress.status {
+            LevelStatus::InProgress => match progress.ticks_left {
+                Some(ticks) => locale::format("level_ticks_left", &[("ticks", &ticks)]),
+                None => "".to_string(),
+            },
+            LevelStatus::Won => locale::text("level_won").to_string(),
+            LevelStatus::Lost => locale::text("level_lost").to_string(),
+        };
+        let (ticks, time) = level.timer();
+        let best = match stats.best_times.get(&level.level().name) {
+            Some(best) => locale::format(
+                "level_best",
+                &[(
+                    "time",
+                    &Self::format_time(best.ticks, Duration::from_millis(best.millis)),
+                )],
+            ),
+            None => "".to_string(),
+        };
+        format!(
+            "{} [{}/{}] {} ({}{})",
+            level.level().name,
+            progress.current,
+            progress.target,
+            status,
+            Self::format_time(ticks, time),
+            best
+        )
+    }
+
+    fn centered_rect(width: u16, height: u16, f: &Frame) -> Rect {
+        let size = f.size();
+        let width = width.min(size.width);
+        let height = height.min(size.height);
+        Rect::new(
+            (size.width - width) / 2,
+            (size.height - height) / 2,
+            width,
+            height,
+        )
+    }
+
+    fn render_caption(caption: &str, canvas: Rect, f: &mut Frame) {
+        let width = canvas.width.saturating_sub(4);
+        let height = 3.min(canvas.height);
+        let area = Rect::new(
+            canvas.x + 2,
+            (canvas.y + canvas.height).saturating_sub(height + 1),
+            width,
+            height,
+        );
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(caption)
+                .wrap(Wrap { trim: true })
+                .alignment(Alignment::Center)
+                .block(
+                    Block::default()
+                        .border_set(symbols::border::PLAIN)
+                        .borders(Borders::ALL),
+                ),
+            area,
+        );
+    }
+
+    /// Stacks the toasts in the top right corner of the canvas, newest at the bottom.
+    fn render_toasts(toasts: &[(String, u32)], canvas: Rect, f: &mut Frame) {
+        let width = canvas.width.saturating_sub(4).min(40);
+        let mut y = canvas.y + 1;
+        for (message, _) in toasts {
+            let lines = (message.len() as u16).div_ceil(width.saturating_sub(2).max(1));
+            let height = (lines + 2).min((canvas.y + canvas.height).saturating_sub(y));
+            if height < 3 {
+                break;
+            }
+            let area = Rect::new(
+                (canvas.x + canvas.width).saturating_sub(width + 2),
+                y,
+                width,
+                height,
+            );
+            f.render_widget(Clear, area);
+            f.render_widget(
+                Paragraph::new(message.as_str())
+                    .wrap(Wrap { trim: true })
+                    .style(Style::default().fg(Color::Yellow))
+                    .block(
+                        Block::default()
+                            .border_set(symbols::border::PLAIN)
+                            .borders(Borders::ALL),
+                    ),
+                area,
+            );
+            y += height;
+        }
+    }
+
+    /// Lists the elements completing the chord started with `key`, in the bottom left corner
+    /// of the canvas.
+    fn render_chord(key: char, canvas: Rect, f: &mut Frame) {
+        let completions = catalog::completions(key);
+        let width = completions
+            .iter()
+            .map(|e| locale::element(e.name).chars().count() as u16 + 5)
+            .chain([16])
+            .max()
+            .unwrap_or_default();
+        let height = completions.len() as u16 + 2;
+        if canvas.width < width + 2 || canvas.height < height + 2 {
+            return;
+        }
+        let area = Rect::new(
+            canvas.x + 1,
+            canvas.y + canvas.height - height - 1,
+            width,
+            height,
+        );
+        let items = completions
+            .iter()
+            .filter_map(|e| Some(format!("[{}]{}", e.chord?.1, locale::element(e.name))))
+            .map(ListItem::new)
+            .collect::<Vec<_>>();
+        f.render_widget(Clear, area);
+        f.render_widget(
+            List::new(items).block(
+                Block::default()
+                    .border_set(symbols::border::PLAIN)
+                    .borders(Borders::ALL)
+                    .title(locale::format("chord", &[("key", &key)])),
+            ),
+            area,
+        );
+    }
+
+    /// Bar chart of the time spent in each phase of the last frame, in the bottom left corner
+    /// of the canvas.
+    fn render_timings(state: &State, canvas: Rect, f: &mut Frame) {
+        let (width, height) = (40, 7);
+        if canvas.width < width + 2 || canvas.height < height + 2 {
+            return;
+        }
+        let tick = state.sandbox.last_tick_timings();
+        let frame = state.frame_timings;
+        let bars = [
+            ("movement", tick.movement),
+            ("interaction", tick.interaction),
+            ("bookkeeping", tick.bookkeeping),
+            ("events", frame.events),
+            ("render", frame.render),
+        ]
+        .map(|(phase, time)| {
+            Bar::default()
+                .label(Line::from(phase))
+                .value(time.as_micros() as u64)
+                .text_value(format!("{:.2?}", time))
+        });
+
+        let area = Rect::new(
+            canvas.x + 1,
+            canvas.y + canvas.height - height - 1,
+            width,
+            height,
+        );
+        f.render_widget(Clear, area);
+        f.render_widget(
+            BarChart::default()
+                .direction(Direction::Horizontal)
+                .bar_width(1)
+                .bar_gap(0)
+                .bar_style(Style::default().fg(Color::Cyan))
+                .value_style(Style::default().fg(Color::Black).bg(Color::Cyan))
+                .data(BarGroup::default().bars(&bars))
+                .block(
+                    Block::default()
+                        .border_set(symbols::border::PLAIN)
+                        .borders(Borders::ALL)
+                        .title(locale::text("timings"))
+                        .title(
+                            Title::from(locale::text("timings_keys"))
+                                .position(Position::Bottom)
+                                .alignment(Alignment::Center),
+                        ),
+                ),
+            area,
+        );
+    }
+
+    /// Sparklines of the temperature and speed of the tracked pixel over its last ticks, in the
+    /// bottom right corner of the canvas.
+    #[cfg(feature = "debug")]
+    fn render_inspector(state: &State, canvas: Rect, f: &mut Frame) {
+        use engine::temperature;
+        use ratatui::widgets::Sparkline;
+
+        let (width, height) = (Sandbox::<rand::rngs::SmallRng>::HISTORY as u16 + 2, 8);
+        if canvas.width < width + 2 || canvas.height < height + 2 {
+            return;
+        }
+        let area = Rect::new(
+            canvas.x + canvas.width - width - 1,
+            canvas.y + canvas.height - height - 1,
+            width,
+            height,
+        );
+        let sandbox = &state.sandbox;
+        let tracked = sandbox.tracked().and_then(|id| {
+            let (x, y) = sandbox.find_pixel(id)?;
+            Some((
+                id,
+                sandbox.pixels[sandbox.coordinates_to_index(x, y)].pixel(),
+            ))
+        });
+        let title = match tracked {
+            Some((id, pixel)) => format!("{} #{}", locale::element(pixel.name()), id),
+            None => locale::text("inspector").to_string(),
+        };
+        let block = Block::default()
+            .border_set(symbols::border::PLAIN)
+            .borders(Borders::ALL)
+            .title(title)
+            .title(
+                Title::from(locale::text("inspector_keys"))
+                    .position(Position::Bottom)
+                    .alignment(Alignment::Center),
+            );
+        let inner = block.inner(area);
+        f.render_widget(Clear, area);
+        f.render_widget(block, area);
+        if tracked.is_none() {
+            f.render_widget(
+                Paragraph::new(locale::text("inspector_hover")).wrap(Wrap { trim: true }),
+                inner,
+            );
+            return;
+        }
+
+        let history = sandbox.history();
+        let last = history.back();
+        let temperatures = history
+            .iter()
+            .map(|sample| sample.temperature as u64)
+            .collect::<Vec<_>>();
+        let speeds = history
+            .iter()
+            .map(|sample| {
+                let (dx, dy) = sample.velocity;
+                dx.unsigned_abs().max(dy.unsigned_abs()).max(sample.fall) as u64
+            })
+            .collect::<Vec<_>>();
+        let (temperature_label, temperature_color) = match last.map(|sample| sample.temperature) {
+            Some(temp) => {
+                let celsius = temperature::to_celsius(temp);
+                let color = match celsius {
+                    ..=0 => Color::LightCyan,
+                    1..=30 => Color::LightGreen,
+                    31..=90 => Color::Yellow,
+                    _ => Color::LightRed,
+                };
+                (
+                    locale::format("inspector_temperature", &[("celsius", &celsius)]),
+                    color,
+                )
+            }
+            None => (
+                locale::text("inspector_no_temperature").to_string(),
+                Color::DarkGray,
+            ),
+        };
+        let speed = speeds.last().copied().unwrap_or(0);
+
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Length(1), Constraint::Length(2)].repeat(2))
+            .split(inner);
+        f.render_widget(Paragraph::new(temperature_label), rows[0]);
+        f.render_widget(
+            Sparkline::default()
+                .data(&temperatures)
+                .style(Style::default().fg(temperature_color)),
+            rows[1],
+        );
+        f.render_widget(
+            Paragraph::new(locale::format("inspector_speed", &[("speed", &speed)])),
+            rows[2],
+        );
+        f.render_widget(
+            Sparkline::default()
+                .data(&speeds)
+                .style(Style::default().fg(Color::Cyan)),
+            rows[3],
+        );
+    }
+
+    /// The log of the scripting console over the bottom of the canvas, newest at the bottom
+    /// above the snippet being typed.
+    #[cfg(feature = "console")]
+    fn render_console(state: &State, canvas: Rect, f: &mut Frame) {
+        let height = 12.min(canvas.height);
+        let area = Rect::new(
+            canvas.x,
+            (canvas.y + canvas.height).saturating_sub(height),
+            canvas.width,
+            height,
+        );
+        let console = &state.console;
+        let shown = (height as usize).saturating_sub(3);
+        let lines = console
+            .log
+            .iter()
+            .flat_map(|entry| entry.lines())
+            .collect::<Vec<_>>();
+        let mut text = lines[lines.len().saturating_sub(shown)..]
+            .iter()
+            .map(|line| Line::from(Span::styled(*line, Style::default().fg(Color::Gray))))
+            .collect::<Vec<_>>();
+        text.push(Line::from(format!("> {}_", console.input)));
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(text).block(
+                Block::default()
+                    .border_set(symbols::border::PLAIN)
+                    .borders(Borders::ALL)
+                    .title(locale::text("console"))
+                    .title(
+                        Title::from(locale::text("console_keys"))
+                            .position(Position::Bottom)
+                            .alignment(Alignment::Center),
+                    ),
+            ),
+            area,
+        );
+    }
+
+    fn render_achievements(state: &State, f: &mut Frame) {
+        let achievements = Stats::achievements();
+        let items = achievements
+            .iter()
+            .map(|a| {
+                let (mark, style) = match a.is_unlocked(&state.stats) {
+                    true => ("[x]", Style::default().fg(Color::Yellow)),
+                    false => ("[ ]", Style::default().fg(Color::DarkGray)),
+                };
+                ListItem::new(format!("{} {}: {}", mark, a.name, a.description)).style(style)
+            })
+            .collect::<Vec<_>>();
+
+        let area = Self::centered_rect(60, achievements.len() as u16 + 2, f);
+        f.render_widget(Clear, area);
+        f.render_widget(
+            List::new(items).block(
+                Block::default()
+                    .border_set(symbols::border::PLAIN)
+                    .borders(Borders::ALL)
+                    .title(locale::text("achievements"))
+                    .title(
+                        Title::from(locale::text("achievements_keys"))
+                            .position(Position::Bottom)
+                            .alignment(Alignment::Center),
+                    ),
+            ),
+            area,
+        );
+    }
+
+    fn render_level_menu(state: &State, selected: usize, f: &mut Frame) {
+        // two thumbnail rows per line, with the upper half block in front of the lower one
+        let (thumbnail_width, thumbnail_height) = State::THUMBNAIL_SIZE;
+        let lines = thumbnail_height.div_ceil(2);
+        let thumbnail = |rows: &[Vec<Rgb>], line: usize| -> Vec<Span<'static>> {
+            let color = |y: usize, x: usize| {
+                rows.get(y)
+                    .and_then(|row| row.get(x))
+                    .map_or(Color::Reset, |&Rgb(r, g, b)| Color::Rgb(r, g, b))
+            };
+            (0..thumbnail_width)
+                .map(|x| {
+                    Span::styled(
+                        "▀",
+                        Style::default()
+                            .fg(color(line * 2, x))
+                            .bg(color(line * 2 + 1, x)),
+                    )
+                })
+                .collect()
+        };
+        // saved scenes also tell who made them and what they play by
+        let scenes = state
+            .scenes
+            .iter()
+            .zip(&state.scene_thumbnails)
+            .map(|(scene, rows)| {
+                let meta = &scene.scene;
+                let author = meta.author.as_ref().map_or(String::new(), |author| {
+                    locale::format("demo_author", &[("author", author)])
+                });
+                let name = format!("{}{}", meta.name.as_deref().unwrap_or_default(), author);
+                let details =
+                    meta.description
+                        .clone()
+                        .into_iter()
+                        .chain(meta.elements.as_ref().map(|elements| {
+                            locale::format("scene_elements", &[("count", &elements.len())])
+                        }))
+                        .chain(meta.config.ambient.map(|celsius| {
+                            locale::format("scene_ambient", &[("celsius", &celsius)])
+                        }))
+                        .collect::<Vec<_>>()
+                        .join(", ");
+                (name, Some(rows), details)
+            });
+        let items = std::iter::once((locale::text("free_play").to_string(), None, String::new()))
+            .chain(
+                state
+                    .levels
+                    .iter()
+                    .zip(&state.level_thumbnails)
+                    .map(|(level, rows)| (level.name.clone(), Some(rows), String::new())),
+            )
+            .chain(scenes)
+            .map(|(name, rows, details)| {
+                let text = (0..lines)
+                    .map(|line| {
+                        let mut spans = match rows {
+                            Some(rows) => thumbnail(rows, line),
+                            None => vec![Span::raw(" ".repeat(thumbnail_width))],
+                        };
+                        if line == lines / 2 {
+                            spans.push(Span::raw(format!(" {}", name)));
+                        } else if line == lines / 2 + 1 && !details.is_empty() {
+                            spans.push(Span::raw(format!(" {}", details)));
+                        }
+                        Line::from(spans)
+                    })
+                    .collect::<Vec<_>>();
+                ListItem::new(text)
+            })
+            .collect::<Vec<_>>();
+
+        let area = Self::centered_rect(40, (items.len() * lines) as u16 + 2, f);
+
+        f.render_widget(Clear, area);
+        f.render_stateful_widget(
+            List::new(items)
+                .block(
+                    Block::default()
+                        .border_set(symbols::border::PLAIN)
+                        .borders(Borders::ALL)
+                        .title(locale::text("levels"))
+                        .title(
+                            Title::from(locale::text("levels_keys"))
+                                .position(Position::Bottom)
+                                .alignment(Alignment::Center),
+                        ),
+                )
+                .highlight_style(Style::default().bg(Color::DarkGray))
+                .highlight_symbol("> "),
+            area,
+            &mut ListState::default().with_selected(Some(selected)),
+        );
+    }
+
+    fn render_spawner_prompt(spawner: Spawner, f: &mut Frame) {
+        let element = spawner.element().pixel();
+        let lines = [
+            locale::format(
+                "spawner_element",
+                &[
+                    ("keys", &catalog::element(&element).keys()),
+                    ("element", &locale::element(element.name())),
+                ],
+            ),
+            locale::format("spawner_period", &[("period", &spawner.period())]),
+        ]
+        .join("\n");
+
+        let area = Self::centered_rect(60, 4, f);
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .border_set(symbols::border::PLAIN)
+                    .borders(Borders::ALL)
+                    .title(locale::text("spawner"))
+                    .title(
+                        Title::from(locale::text("spawner_keys"))
+                            .position(Position::Bottom)
+                            .alignment(Alignment::Center),
+                    ),
+            ),
+            area,
+        );
+    }
+
     pub fn render(&mut self, state: &State, f: &mut Frame) {
         self.fps_tracker.track_fps();
+        if state.is_too_small() {
+            Self::render_too_small(state.terminal_size(), f);
+            return;
+        }
 
         let layout = Layout::default()
             .direction(Direction::Horizontal)
@@ -58,6 +611,12 @@ impl Renderer {
             ])
             .split(f.size());
 
+        let view = TuiSandbox {
+            sandbox: &state.sandbox,
+            camera: state.camera,
+            size: state.viewport(),
+            theme: self.theme.as_ref(),
+        };
         f.render_widget(
             Canvas::default()
                 .block(
@@ -65,10 +624,50 @@ impl Renderer {
                         .border_set(symbols::border::PLAIN)
                         .borders(Borders::ALL)
                         .title("Rustfall")
+                        .title(match (&state.level, &state.demo) {
+                            (Some(level), _) => Self::level_title(level, &state.stats),
+                            (None, Some((idx, player))) => locale::format(
+                                "demo_title",
+                                &[
+                                    ("index", &(idx + 1)),
+                                    ("count", &state.demos.len()),
+                                    ("name", &player.scenario().name),
+                                    (
+                                        "author",
+                                        &match &player.scenario().author {
+                                            Some(author) => {
+                                                locale::format("demo_author", &[("author", author)])
+                                            }
+                                            None => String::new(),
+                                        },
+                                    ),
+                                ],
+                            ),
+                            (None, None) => locale::text("free_play_title").to_string(),
+                        })
                         .title(
                             Title::from(format!(
-                                "({} * {})",
-                                state.sandbox.width, state.sandbox.height
+                                "({} * {}) {}{}{}",
+                                state.sandbox.width,
+                                state.sandbox.height,
+                                match state.sandbox.world_time() {
+                                    Some(time) => time.to_string(),
+                                    None => locale::format(
+                                        "world_tick",
+                                        &[("tick", &state.sandbox.tick_count())]
+                                    ),
+                                },
+                                match state.turbo {
+                                    true => locale::text("turbo").to_string(),
+                                    false =>
+                                        locale::format("speed", &[("tps", &state.config.tps())]),
+                                },
+                                match state.sandbox.ambient_temperature() {
+                                    Some(celsius) => {
+                                        locale::format("ambient", &[("celsius", &celsius)])
+                                    }
+                                    None => String::new(),
+                                }
                             ))
                             .alignment(Alignment::Center),
                         )
@@ -76,13 +675,70 @@ impl Renderer {
                             Title::from(format!("{:.2} fps", self.fps_tracker.fps()))
                                 .alignment(Alignment::Right),
                         )
+                        .title(
+                            Title::from(state.notice.clone().unwrap_or_default())
+                                .position(Position::Bottom)
+                                .alignment(Alignment::Left),
+                        )
                         .title(
                             Title::from(match state.pause {
-                                true => "Paused",
-                                false => "Press `Space` to pause",
+                                true => locale::text("paused"),
+                                false => locale::text("running"),
                             })
                             .position(Position::Bottom)
                             .alignment(Alignment::Center),
+                        )
+                        .title(
+                            Title::from(match state.tool {
+                                _ if state.player_mode => {
+                                    match state.player.and_then(|id| state.sandbox.entity(id)) {
+                                        Some(player) => locale::format(
+                                            "player_health",
+                                            &[("health", &player.health)],
+                                        ),
+                                        None => locale::text("player_spawning").to_string(),
+                                    }
+                                }
+                                Tool::Brush => locale::format(
+                                    "tool_brush",
+                                    &[
+                                        ("size", &state.brush.size),
+                                        (
+                                            "shape",
+                                            &locale::text(match state.brush.shape {
+                                                BrushShape::Square => "shape_square",
+                                                BrushShape::Circle => "shape_circle",
+                                                BrushShape::Single => "shape_single",
+                                            }),
+                                        ),
+                                        ("density", &state.brush.density),
+                                    ],
+                                ),
+                                Tool::Erase => locale::text("tool_erase").to_string(),
+                                Tool::Flick => locale::text("tool_flick").to_string(),
+                                Tool::Vacuum => locale::format(
+                                    "tool_vacuum",
+                                    &[("stored", &state.canister.len())],
+                                ),
+                                Tool::Freeze => locale::text("tool_freeze").to_string(),
+                                Tool::Entity(entity) => locale::format(
+                                    "tool_entity",
+                                    &[(
+                                        "entity",
+                                        &locale::text(match entity {
+                                            EntityKind::Ball => "entity_ball",
+                                            EntityKind::Boat => "entity_boat",
+                                            EntityKind::Player => "entity_player",
+                                        }),
+                                    )],
+                                ),
+                                Tool::Transmute => locale::format(
+                                    "tool_transmute",
+                                    &[("element", &locale::element(state.active_pixel.name()))],
+                                ),
+                            })
+                            .position(Position::Bottom)
+                            .alignment(Alignment::Right),
                         ),
                 )
                 .marker(match self.no_braille {
@@ -90,16 +746,27 @@ impl Renderer {
                     true => Marker::Block,
                 })
                 .paint(|ctx| {
-                    ctx.draw(&TuiSandbox(&state.sandbox));
+                    if state.overlay == Overlay::Grid {
+                        ctx.draw(&TuiGrid(&view));
+                    }
+                    ctx.draw(&view);
+                    ctx.draw(&TuiEffects(&state.effects, &view));
                 }),
             layout[0],
         );
 
-        let list_items = Self::list_items();
+        let palette = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Min(3), Constraint::Length(3)])
+            .split(layout[1]);
+        let list_items = match state.level.is_some() || state.sandbox.allowed_elements().is_some() {
+            true => Self::restricted_list_items(state),
+            false => Self::list_items().to_vec(),
+        };
         let mut list_state = ListState::default().with_selected(
-            Pixel::iter()
-                .sorted_by_key(|pixel| pixel.hotkey())
-                .position(|p| p == state.active_pixel),
+            catalog::catalog()
+                .iter()
+                .position(|e| e.name == state.active_pixel.name()),
         );
 
         f.render_stateful_widget(
@@ -108,7 +775,14 @@ impl Renderer {
                     Block::default()
                         .border_set(symbols::border::PLAIN)
                         .borders(Borders::TOP | Borders::RIGHT | Borders::BOTTOM)
-                        .title("Pixels"),
+                        .title(match state.active_pixel {
+                            // what the spout pours, pressing its hotkey again changes it
+                            Pixel::Spout(spout) => locale::format(
+                                "pixels_spout",
+                                &[("element", &locale::element(spout.element().pixel().name()))],
+                            ),
+                            _ => locale::text("pixels").to_string(),
+                        }),
                 )
                 .style(Style::default().fg(Color::White))
                 .highlight_style(
@@ -117,19 +791,337 @@ impl Renderer {
                         .bg(Color::DarkGray),
                 )
                 .highlight_symbol("[x]"),
-            layout[1],
+            palette[0],
             &mut list_state,
         );
+        Self::render_favorites(state, palette[1], f);
+        if state.overlay != Overlay::Off {
+            self.render_ruler(state, layout[0], f);
+        }
+        if state.annotating {
+            self.render_annotations(state, layout[0], f);
+        }
+
+        if let Some(area) = state.minimap_area() {
+            self.render_minimap(state, area, f);
+        }
+
+        if let Some(caption) = state.demo.as_ref().and_then(|(_, player)| player.caption()) {
+            Self::render_caption(caption, layout[0], f);
+        }
+        Self::render_toasts(&state.toasts, layout[0], f);
+        if let Some((key, _)) = state.chord {
+            Self::render_chord(key, layout[0], f);
+        }
+        if state.show_timings {
+            Self::render_timings(state, layout[0], f);
+        }
+        #[cfg(feature = "debug")]
+        if state.show_inspector {
+            Self::render_inspector(state, layout[0], f);
+        }
+
+        #[cfg(feature = "console")]
+        if state.show_console {
+            Self::render_console(state, layout[0], f);
+        }
+
+        if state.show_achievements {
+            Self::render_achievements(state, f);
+        }
+
+        if let Some(selected) = state.level_menu {
+            Self::render_level_menu(state, selected, f);
+        }
+
+        if let Some(spawner) = state.spawner_prompt {
+            Self::render_spawner_prompt(spawner, f);
+        }
+    }
+
+    /// Marks every tenth cell on the top and left borders of the sandbox, labelled where there
+    /// is room, and shows the coordinates of the cell under the mouse.
+    fn render_ruler(&self, state: &State, canvas: Rect, f: &mut Frame) {
+        const STEP: usize = 10;
+        let (cell_width, cell_height) = match self.no_braille {
+            false => (2, 4),
+            true => (1, 1),
+        };
+        let (camera_x, camera_y) = state.camera;
+        // first multiple of the step in the `size` cells from `start`, if any
+        let mark = |start: usize, size: usize, end: usize| {
+            let at = start.next_multiple_of(STEP);
+            (at < start + size && at < end).then_some(at)
+        };
+        let style = Style::default().fg(Color::Yellow);
+        let buf = f.buffer_mut();
+
+        let spacing = STEP / cell_width;
+        for column in 1..canvas.width.saturating_sub(1) {
+            let start = camera_x + (column as usize - 1) * cell_width;
+            let Some(x) = mark(start, cell_width, state.sandbox.width) else {
+                continue;
+            };
+            let (column, row) = (canvas.x + column, canvas.y);
+            buf.get_mut(column, row).set_symbol("┬").set_style(style);
+            let label = x.to_string();
+            if label.len() < spacing && column + (label.len() as u16) < canvas.right() - 1 {
+                buf.set_string(column + 1, row, label, style);
+            }
+        }
+        for row in 1..canvas.height.saturating_sub(1) {
+            let start = camera_y + (row as usize - 1) * cell_height;
+            if mark(start, cell_height, state.sandbox.height).is_some() {
+                buf.get_mut(canvas.x, canvas.y + row)
+                    .set_symbol("├")
+                    .set_style(style);
+            }
+        }
+
+        let Some((x, y)) = state
+            .cursor
+            .filter(|&(x, y)| state.sandbox.is_coordinate_in_bound(x, y))
+        else {
+            return;
+        };
+        let readout = format!(" x {}, y {} ", x, y);
+        let area = Rect::new(canvas.x + 1, canvas.y + 1, readout.len() as u16, 1)
+            .intersection(canvas.inner(&Margin::new(1, 1)));
+        f.render_widget(Clear, area);
+        f.render_widget(Paragraph::new(readout).style(style), area);
+    }
+
+    fn move_rule_color(rule: MoveRule) -> Color {
+        match rule {
+            MoveRule::Gravity => Color::Yellow,
+            MoveRule::DensitySwap => Color::LightCyan,
+            MoveRule::Dispersion => Color::LightGreen,
+            MoveRule::Thrown => Color::LightRed,
+        }
+    }
+
+    /// Draws an arrow where every annotated pixel moved from, pointing where it went and
+    /// coloured by the rule that moved it, with a legend counting the moves of each rule.
+    fn render_annotations(&self, state: &State, canvas: Rect, f: &mut Frame) {
+        let (cell_width, cell_height) = match self.no_braille {
+            false => (2, 4),
+            true => (1, 1),
+        };
+        let (camera_x, camera_y) = state.camera;
+        let inner = canvas.inner(&Margin::new(1, 1));
+        let annotations = state.sandbox.annotations();
+        let buf = f.buffer_mut();
+        for annotation in annotations {
+            let (x, y) = annotation.from;
+            let (Some(column), Some(row)) = (
+                x.checked_sub(camera_x).map(|dx| dx / cell_width),
+                y.checked_sub(camera_y).map(|dy| dy / cell_height),
+            ) else {
+                continue;
+            };
+            if column >= inner.width as usize || row >= inner.height as usize {
+                continue;
+            }
+            let (to_x, to_y) = annotation.to;
+            let arrow = match (to_x.cmp(&x), to_y.cmp(&y)) {
+                (Ordering::Less, Ordering::Less) => "↖",
+                (Ordering::Equal, Ordering::Less) => "↑",
+                (Ordering::Greater, Ordering::Less) => "↗",
+                (Ordering::Less, Ordering::Equal) => "←",
+                (Ordering::Greater, Ordering::Equal) => "→",
+                (Ordering::Less, Ordering::Greater) => "↙",
+                (Ordering::Greater, Ordering::Greater) => "↘",
+                _ => "↓",
+            };
+            buf.get_mut(inner.x + column as u16, inner.y + row as u16)
+                .set_symbol(arrow)
+                .set_style(Style::default().fg(Self::move_rule_color(annotation.rule)));
+        }
+
+        let lines = MoveRule::iter()
+            .map(|rule| {
+                let count = annotations.iter().filter(|a| a.rule == rule).count();
+                Line::styled(
+                    format!("{:>3} {}", count, rule.name()),
+                    Style::default().fg(Self::move_rule_color(rule)),
+                )
+            })
+            .collect::<Vec<_>>();
+        let (width, height) = (18, lines.len() as u16 + 2);
+        if canvas.width < width + 2 || canvas.height < height + 2 {
+            return;
+        }
+        let area = Rect::new(
+            canvas.right() - width - 1,
+            canvas.bottom() - height - 1,
+            width,
+            height,
+        );
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Paragraph::new(lines).block(
+                Block::default()
+                    .border_set(symbols::border::PLAIN)
+                    .borders(Borders::ALL)
+                    .title(locale::text("moves")),
+            ),
+            area,
+        );
     }
 
+    /// One digit per favorite slot, in the colour of the element bound to it.
+    fn render_favorites(state: &State, area: Rect, f: &mut Frame) {
+        let digits = (1..=9)
+            .map(|slot| match state.config.favorite(slot) {
+                Some(favorite) => {
+                    let Rgb(r, g, b) = catalog::element(&favorite.element.pixel()).color;
+                    Span::styled(slot.to_string(), Style::default().fg(Color::Rgb(r, g, b)))
+                }
+                None => Span::styled(slot.to_string(), Style::default().fg(Color::DarkGray)),
+            })
+            .collect::<Vec<_>>();
+
+        f.render_widget(
+            Paragraph::new(Line::from(digits)).block(
+                Block::default()
+                    .border_set(symbols::border::PLAIN)
+                    .borders(Borders::RIGHT | Borders::BOTTOM)
+                    .title(locale::text("favorites")),
+            ),
+            area,
+        );
+    }
+
+    /// Renders only the sandbox, for read-only spectators.
+    pub fn render_spectator<R: Rng>(&mut self, sandbox: &Sandbox<R>, status: &str, f: &mut Frame) {
+        self.fps_tracker.track_fps();
+
+        f.render_widget(
+            Canvas::default()
+                .block(
+                    Block::default()
+                        .border_set(symbols::border::PLAIN)
+                        .borders(Borders::ALL)
+                        .title("Rustfall")
+                        .title(
+                            Title::from(format!("({} * {})", sandbox.width, sandbox.height))
+                                .alignment(Alignment::Center),
+                        )
+                        .title(
+                            Title::from(format!("{:.2} fps", self.fps_tracker.fps()))
+                                .alignment(Alignment::Right),
+                        )
+                        .title(
+                            Title::from(status)
+                                .position(Position::Bottom)
+                                .alignment(Alignment::Center),
+                        ),
+                )
+                .marker(match self.no_braille {
+                    false => Marker::Braille,
+                    true => Marker::Block,
+                })
+                .paint(|ctx| {
+                    ctx.draw(&TuiSandbox::whole(sandbox, self.theme.as_ref()));
+                }),
+            f.size(),
+        );
+    }
+
+    /// Where the minimap goes, in the top right corner of the canvas, when a sandbox of
+    /// `sandbox` size doesn't fit in the `viewport` of a terminal `width` wide.
+    pub fn minimap_area(
+        width: usize,
+        sandbox: (usize, usize),
+        viewport: (usize, usize),
+    ) -> Option<Rect> {
+        const WIDTH: usize = 22;
+
+        if sandbox.0 <= viewport.0 && sandbox.1 <= viewport.1 {
+            return None;
+        }
+        // terminal cells are about twice as high as they are wide
+        let height = ((WIDTH - 2) * sandbox.1 / sandbox.0 / 2).clamp(2, 10) + 2;
+        let right = width.checked_sub(Self::pixel_bar_width() as usize + 1)?;
+        Some(Rect::new(
+            right.checked_sub(WIDTH)? as u16,
+            1,
+            WIDTH as u16,
+            height as u16,
+        ))
+    }
+
+    fn render_minimap(&self, state: &State, area: Rect, f: &mut Frame) {
+        let (width, height) = match self.no_braille {
+            false => (2, 4),
+            true => (1, 1),
+        };
+        let minimap = Minimap {
+            view: TuiSandbox {
+                sandbox: &state.sandbox,
+                camera: state.camera,
+                size: state.viewport(),
+                theme: self.theme.as_ref(),
+            },
+            resolution: (
+                area.width.saturating_sub(2) as usize * width,
+                area.height.saturating_sub(2) as usize * height,
+            ),
+        };
+
+        f.render_widget(Clear, area);
+        f.render_widget(
+            Canvas::default()
+                .block(
+                    Block::default()
+                        .border_set(symbols::border::PLAIN)
+                        .borders(Borders::ALL)
+                        .title(locale::text("map")),
+                )
+                .marker(match self.no_braille {
+                    false => Marker::Braille,
+                    true => Marker::Block,
+                })
+                .paint(|ctx| ctx.draw(&minimap)),
+            area,
+        );
+    }
+
+    /// Size of the canvas in a terminal of `width` by `height`, at least one cell even when
+    /// the terminal is too small for the layout.
     pub fn sandbox_size(width: usize, height: usize) -> (usize, usize) {
-        let width = width - Self::pixel_bar_width() as usize;
-        let height = height;
-        let canvas_width = width - 2;
-        let canvas_height = height - 2;
+        let width = width.saturating_sub(Self::pixel_bar_width() as usize);
+        let canvas_width = width.saturating_sub(2).max(1);
+        let canvas_height = height.saturating_sub(2).max(1);
 
         (canvas_width, canvas_height)
     }
+
+    /// Smallest terminal the layout fits in: the sidebar, the borders and a bit of canvas.
+    pub fn min_size() -> (usize, usize) {
+        (Self::pixel_bar_width() as usize + 12, 10)
+    }
+
+    fn render_too_small(size: (usize, usize), f: &mut Frame) {
+        let (width, height) = Self::min_size();
+        let area = f.size();
+        let text = locale::format(
+            "too_small",
+            &[
+                ("width", &size.0),
+                ("height", &size.1),
+                ("min_width", &width),
+                ("min_height", &height),
+            ],
+        );
+        let top = area.height.saturating_sub(3) / 2;
+        f.render_widget(
+            Paragraph::new(text)
+                .wrap(Wrap { trim: true })
+                .alignment(Alignment::Center),
+            Rect::new(area.x, area.y + top, area.width, area.height - top),
+        );
+    }
 }
 
 pub trait PixelDisplay {
@@ -145,28 +1137,198 @@ impl PixelDisplay for Pixel {
             Pixel::Sand(_) => Color::Indexed(214),
             // grey
             Pixel::Rock(_) => Color::Indexed(254),
-            Pixel::Water(_) => Color::Blue,
+            Pixel::Water(val) => {
+                if val.solution().solute().is_some() {
+                    // lighter blue
+                    Color::Indexed(33)
+                } else {
+                    Color::Blue
+                }
+            }
+            // off white
+            Pixel::Salt(_) => Color::Indexed(253),
+            Pixel::Pipe(_) => Color::Indexed(240),
+            Pixel::Pump(_) => Color::Indexed(25),
+            Pixel::Sieve(_) => Color::Indexed(245),
+            Pixel::Foam(_) => Color::White,
+            // purple
+            Pixel::Poison(_) => Color::Indexed(93),
+            // murky green
+            Pixel::PoisonedWater(_) => Color::Indexed(71),
+            // sickly yellow
+            Pixel::ToxicGas(_) => Color::Indexed(148),
+            Pixel::Cloud(val) => {
+                if val.is_charged() {
+                    // dark grey
+                    Color::Indexed(243)
+                } else {
+                    Color::Indexed(250)
+                }
+            }
+            Pixel::Wire(val) => {
+                if val.is_sparking() {
+                    Color::LightYellow
+                } else {
+                    // copper
+                    Color::Indexed(130)
+                }
+            }
+            Pixel::Battery(_) => Color::LightRed,
+            Pixel::Spawner(val) => {
+                if val.is_spawning() {
+                    val.element().pixel().display()
+                } else {
+                    Color::Magenta
+                }
+            }
+            Pixel::Timer(val) => {
+                if val.is_sparking() {
+                    Color::LightYellow
+                } else {
+                    Color::Cyan
+                }
+            }
+            Pixel::Turbine(val) => {
+                if val.is_sparking() {
+                    Color::LightYellow
+                } else {
+                    // steel blue
+                    Color::Indexed(110)
+                }
+            }
+            // dark grey
+            Pixel::Boulder(_) => Color::Indexed(242),
+            // light brown
+            Pixel::Plank(_) => Color::Indexed(137),
+            Pixel::WaterWheel(val) => {
+                if val.is_sparking() {
+                    Color::LightYellow
+                } else if val.is_spoke() {
+                    // dark brown
+                    Color::Indexed(94)
+                } else {
+                    // light wood
+                    Color::Indexed(180)
+                }
+            }
+            Pixel::Switch(val) => {
+                if val.on {
+                    Color::LightGreen
+                } else {
+                    Color::DarkGray
+                }
+            }
+            Pixel::LevitationField(val) => match val.gravity() {
+                Gravity::Inverted => Color::LightMagenta,
+                // pale violet
+                _ => Color::Indexed(183),
+            },
             Pixel::Void(_) => Color::Black,
             Pixel::Fire(_) => Color::Red,
             Pixel::EternalFire(_) => Color::Indexed(52),
             Pixel::Wood(val) => {
                 if val.is_burning() {
                     Color::Indexed(202)
+                } else if val.is_wet() {
+                    // dark yellow
+                    Color::Indexed(136)
                 } else {
                     Color::Yellow
                 }
             }
             Pixel::Ice(_) => Color::Indexed(195),
+            // greyish pink
+            Pixel::Fungus(_) => Color::Indexed(139),
+            // tan
+            Pixel::Dust(_) => Color::Indexed(180),
+            // dark orange
+            Pixel::Lava(_) => Color::Indexed(166),
+            // dark purple
+            Pixel::Obsidian(_) => Color::Indexed(54),
+            // dark grey
+            Pixel::Gravel(_) => Color::Indexed(244),
+            // brown
+            Pixel::Soil(_) => Color::Indexed(94),
+            Pixel::Grass(_) => Color::Green,
+            // dark olive
+            Pixel::Mud(_) => Color::Indexed(58),
+            Pixel::Oil(val) => {
+                if val.is_burning() {
+                    Color::Indexed(202)
+                } else {
+                    // olive
+                    Color::Indexed(100)
+                }
+            }
+            Pixel::WetSand(_) => Color::Indexed(172),
+            // charcoal
+            Pixel::Gunpowder(_) => Color::Indexed(239),
+            Pixel::Tnt(_) => Color::Indexed(161),
+            // steel blue
+            Pixel::Spout(_) => Color::Indexed(67),
+            Pixel::Drain(_) => Color::Indexed(235),
+            // pink
+            Pixel::Rubber(_) => Color::Indexed(168),
+            // registered elements bring their own colour
+            Pixel::Custom(_) => {
+                let Rgb(r, g, b) = catalog::element(self).color;
+                Color::Rgb(r, g, b)
+            }
+            Pixel::Fog(_) => Color::Indexed(188),
+            Pixel::Vine(val) => {
+                if val.is_burning() {
+                    Color::Indexed(202)
+                } else {
+                    // dark green
+                    Color::Indexed(28)
+                }
+            }
+        }
+    }
+}
+
+/// Part of a sandbox showing through a viewport of `size` whose top left corner is at `camera`.
+struct TuiSandbox<'a, R: Rng> {
+    sandbox: &'a Sandbox<R>,
+    camera: (usize, usize),
+    size: (usize, usize),
+    theme: Option<&'a Palette>,
+}
+
+impl<'a, R: Rng> TuiSandbox<'a, R> {
+    fn whole(sandbox: &'a Sandbox<R>, theme: Option<&'a Palette>) -> Self {
+        Self {
+            sandbox,
+            camera: (0, 0),
+            size: (sandbox.width, sandbox.height),
+            theme,
         }
     }
+
+    /// Colour of `pixel` in the theme, falling back to the terminal colours for the elements
+    /// it leaves out.
+    fn color(&self, pixel: Pixel) -> Color {
+        match self.theme.and_then(|theme| theme.color(pixel)) {
+            Some(Rgb(r, g, b)) => Color::Rgb(r, g, b),
+            None => pixel.display(),
+        }
+    }
+
+    /// Viewport coordinates of the sandbox cell at (`x`, `y`), if it shows.
+    fn project(&self, x: isize, y: isize) -> Option<(usize, usize)> {
+        let x = x - self.camera.0 as isize;
+        let y = y - self.camera.1 as isize;
+        let in_view =
+            (0..self.size.0 as isize).contains(&x) && (0..self.size.1 as isize).contains(&y);
+        in_view.then_some((x as usize, y as usize))
+    }
 }
 
-struct TuiSandbox<'a, R: Rng>(&'a Sandbox<R>);
 impl<R: Rng> Deref for TuiSandbox<'_, R> {
     type Target = Sandbox<R>;
 
     fn deref(&self) -> &Self::Target {
-        self.0
+        self.sandbox
     }
 }
 
@@ -177,7 +1339,110 @@ impl<R: Rng> Shape for TuiSandbox<'_, R> {
                 continue;
             }
             let (x, y) = self.index_to_coordinates(idx);
-            painter.paint(x, y, pixel.pixel().display());
+            let color = match self.is_frozen(x, y) {
+                true => {
+                    let Rgb(r, g, b) = catalog::element(&pixel.pixel()).color;
+                    let dim = |c: u8| (c as u16 * 3 / 5) as u8;
+                    Color::Rgb(dim(r), dim(g), dim(b))
+                }
+                false => self.color(pixel.pixel()),
+            };
+            if let Some((x, y)) = self.project(x as isize, y as isize) {
+                painter.paint(x, y, color);
+            }
+        }
+
+        for entity in self.entities() {
+            let color = match entity.kind {
+                EntityKind::Ball => Color::White,
+                // brown
+                EntityKind::Boat => Color::Indexed(130),
+                EntityKind::Player => Color::LightMagenta,
+            };
+            for (x, y) in entity.cells() {
+                if let Some((x, y)) = self.project(x as isize, y as isize) {
+                    painter.paint(x, y, color);
+                }
+            }
+        }
+    }
+}
+
+/// Lines along the boundaries of the far field's chunks, drawn below the sandbox.
+struct TuiGrid<'a, R: Rng>(&'a TuiSandbox<'a, R>);
+
+impl<R: Rng> Shape for TuiGrid<'_, R> {
+    fn draw(&self, painter: &mut Painter) {
+        let (camera_x, camera_y) = self.0.camera;
+        let (width, height) = self.0.size;
+        let on_line = |at: usize| at.is_multiple_of(Sandbox::<R>::FAR_FIELD_CHUNK);
+        for y in camera_y..(camera_y + height).min(self.0.height) {
+            for x in camera_x..(camera_x + width).min(self.0.width) {
+                if on_line(x) || on_line(y) {
+                    painter.paint(x - camera_x, y - camera_y, Color::DarkGray);
+                }
+            }
+        }
+    }
+}
+
+/// Effects drawn above the sandbox they decorate.
+struct TuiEffects<'a, R: Rng>(&'a Effects, &'a TuiSandbox<'a, R>);
+
+impl<R: Rng> Shape for TuiEffects<'_, R> {
+    fn draw(&self, painter: &mut Painter) {
+        for (x, y, kind) in self.0.cells() {
+            if x < 0 || y < 0 || !self.1.is_coordinate_in_bound(x as usize, y as usize) {
+                continue;
+            }
+            let Some((x, y)) = self.1.project(x, y) else {
+                continue;
+            };
+            let color = match kind {
+                EffectKind::Puff => Color::DarkGray,
+                EffectKind::Ring => Color::LightCyan,
+            };
+            painter.paint(x, y, color);
+        }
+    }
+}
+
+/// Downsampled view of a whole sandbox, framing the part the viewport shows.
+struct Minimap<'a, R: Rng> {
+    view: TuiSandbox<'a, R>,
+    /// Dots the minimap is drawn with
+    resolution: (usize, usize),
+}
+
+impl<R: Rng> Shape for Minimap<'_, R> {
+    fn draw(&self, painter: &mut Painter) {
+        let (width, height) = self.resolution;
+        let (sandbox_width, sandbox_height) = (self.view.width, self.view.height);
+        let to_x = |x: usize| x * width / sandbox_width;
+        let to_y = |y: usize| y * height / sandbox_height;
+
+        for y in 0..height {
+            for x in 0..width {
+                let idx = self
+                    .view
+                    .coordinates_to_index(x * sandbox_width / width, y * sandbox_height / height);
+                let pixel = self.view.pixels[idx].pixel();
+                if !matches!(pixel, Pixel::Void(_)) {
+                    painter.paint(x, y, self.view.color(pixel));
+                }
+            }
+        }
+
+        let (left, top) = (to_x(self.view.camera.0), to_y(self.view.camera.1));
+        let right = to_x((self.view.camera.0 + self.view.size.0).min(sandbox_width) - 1);
+        let bottom = to_y((self.view.camera.1 + self.view.size.1).min(sandbox_height) - 1);
+        for x in left..=right {
+            painter.paint(x, top, Color::White);
+            painter.paint(x, bottom, Color::White);
+        }
+        for y in top..=bottom {
+            painter.paint(left, y, Color::White);
+            painter.paint(right, y, Color::White);
         }
     }
 }
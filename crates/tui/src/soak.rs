@@ -0,0 +1,174 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context};
+use engine::heightmap::Heightmap;
+use engine::pixel::{Pixel, PixelFundamental};
+use engine::scenario::{Scenario, ScenarioPlayer};
+
+use crate::metrics::{MetricsSampler, MetricsServer};
+
+const USAGE: &str =
+    "usage: rustfall soak [<scenario.toml>] [--ticks <n>] [--every <n>] [--out <file.csv|file.jsonl>] [--heightmap <file.csv|file.json>] [--metrics <addr>]";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Csv,
+    JsonLines,
+}
+
+/// Options of a headless endurance run.
+#[derive(Debug)]
+pub struct SoakOptions {
+    scenario: Option<PathBuf>,
+    /// Stop after this many ticks, run until killed otherwise
+    ticks: Option<u64>,
+    /// Ticks between two samples
+    every: u64,
+    /// Where samples go, stdout if unset
+    out: Option<PathBuf>,
+    /// Overwritten with the heightmap of the sandbox on every sample
+    heightmap: Option<PathBuf>,
+    /// Address serving the latest sample at `/metrics`
+    metrics: Option<String>,
+}
+
+impl SoakOptions {
+    pub fn parse(args: &[String]) -> anyhow::Result<Self> {
+        let mut options = Self {
+            scenario: None,
+            ticks: None,
+            every: 10_000,
+            out: None,
+            heightmap: None,
+            metrics: None,
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| anyhow!(USAGE));
+            match arg.as_str() {
+                "--ticks" => options.ticks = Some(value()?.parse().context(USAGE)?),
+                "--every" => options.every = value()?.parse().context(USAGE)?,
+                "--out" => options.out = Some(PathBuf::from(value()?)),
+                "--heightmap" => options.heightmap = Some(PathBuf::from(value()?)),
+                "--metrics" => options.metrics = Some(value()?.clone()),
+                s if !s.starts_with("--") && options.scenario.is_none() => {
+                    options.scenario = Some(PathBuf::from(s))
+                }
+                _ => anyhow::bail!(USAGE),
+            }
+        }
+        if options.every == 0 {
+            anyhow::bail!(USAGE);
+        }
+        Ok(options)
+    }
+
+    fn format(&self) -> Format {
+        match &self.out {
+            Some(path) if is_csv(path) => Format::Csv,
+            _ => Format::JsonLines,
+        }
+    }
+}
+
+fn is_csv(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "csv")
+}
+
+fn write_heightmap(path: &Path, heightmap: &Heightmap) -> anyhow::Result<()> {
+    let contents = match is_csv(path) {
+        true => heightmap.to_csv(),
+        false => serde_json::to_string(heightmap)?,
+    };
+    std::fs::write(path, contents).with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Ticks a scenario without a terminal, sampling tps, memory and census to spot slow leaks or
+/// performance decay over long runs.
+///
+/// The timeline plays as usual, pause steps are ignored, and the sandbox keeps ticking once the
+/// scenario is over.
+pub fn run(options: SoakOptions) -> anyhow::Result<()> {
+    let scenario = match &options.scenario {
+        Some(path) => Scenario::load(path)?,
+        None => Scenario::demos().remove(0),
+    };
+    let mut player = ScenarioPlayer::new(scenario);
+    let mut sandbox = player.create_sandbox();
+
+    let mut out: Box<dyn Write> = match &options.out {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).with_context(|| {
+                format!("failed to create {}", path.display())
+            })?))
+        }
+        None => Box::new(io::stdout().lock()),
+    };
+    let server = options
+        .metrics
+        .as_ref()
+        .map(MetricsServer::bind)
+        .transpose()?;
+    let format = options.format();
+    let names = Pixel::all().map(|p| p.name()).collect::<Vec<_>>();
+    if format == Format::Csv {
+        // columns added later go last, so scripts reading older files by position still work
+        writeln!(
+            out,
+            "tick,tps,rss_bytes,{},active_pixels,movement_seconds,interaction_seconds,bookkeeping_seconds",
+            names.join(",")
+        )?;
+    }
+
+    let mut ticks = 0;
+    let mut sampler = MetricsSampler::default();
+    while options.ticks.is_none_or(|limit| ticks < limit) {
+        if player.is_finished() {
+            sandbox.tick();
+        } else {
+            while player.tick(&mut sandbox) {}
+        }
+        ticks += 1;
+        sampler.record(&sandbox);
+
+        if ticks.is_multiple_of(options.every) || options.ticks == Some(ticks) {
+            let metrics = sampler.sample(&sandbox);
+            match format {
+                Format::Csv => {
+                    let counts = names
+                        .iter()
+                        .map(|name| metrics.census.get(name).copied().unwrap_or(0).to_string())
+                        .collect::<Vec<_>>();
+                    writeln!(
+                        out,
+                        "{},{:.1},{},{},{},{:.6},{:.6},{:.6}",
+                        metrics.tick,
+                        metrics.tps,
+                        metrics
+                            .rss_bytes
+                            .map(|rss| rss.to_string())
+                            .unwrap_or_default(),
+                        counts.join(","),
+                        metrics.active_pixels,
+                        metrics.movement_seconds,
+                        metrics.interaction_seconds,
+                        metrics.bookkeeping_seconds,
+                    )?;
+                }
+                Format::JsonLines => writeln!(out, "{}", serde_json::to_string(&metrics)?)?,
+            }
+            // samples should survive the run being killed
+            out.flush()?;
+            if let Some(server) = &server {
+                server.publish(&metrics);
+            }
+            if let Some(path) = &options.heightmap {
+                write_heightmap(path, &Heightmap::new(&sandbox))?;
+            }
+        }
+    }
+    Ok(())
+}
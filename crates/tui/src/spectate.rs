@@ -0,0 +1,192 @@
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{self, SyncSender, TryRecvError};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use crossterm::event::{KeyCode, KeyModifiers};
+use rand::rngs::SmallRng;
+use rand::Rng;
+use ratatui::backend::CrosstermBackend;
+use ratatui::Terminal;
+
+use crate::event::{Event, EventHandler};
+use crate::locale;
+use crate::render::Renderer;
+use crate::tui::Tui;
+use engine::sandbox::Sandbox;
+use engine::sync::{DiffTracker, GridDiff};
+
+/// Streams the sandbox to attached spectators, one JSON encoded [`GridDiff`] per line.
+///
+/// Every spectator is written to from a thread of its own, so a slow one never holds the game
+/// up: it is dropped once it falls [`SpectatorServer::BACKLOG`] lines behind.
+pub struct SpectatorServer {
+    listener: TcpListener,
+    /// Lines waiting to be written to each spectator
+    clients: Vec<SyncSender<Arc<Vec<u8>>>>,
+    tracker: DiffTracker,
+}
+
+impl SpectatorServer {
+    /// Lines a spectator may fall behind before it is dropped
+    const BACKLOG: usize = 64;
+
+    pub fn bind(addr: impl ToSocketAddrs) -> anyhow::Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        listener.set_nonblocking(true)?;
+        Ok(Self {
+            listener,
+            clients: Vec::new(),
+            tracker: DiffTracker::default(),
+        })
+    }
+
+    fn line(diff: &GridDiff) -> Arc<Vec<u8>> {
+        let mut line = serde_json::to_vec(diff).expect("diffs serialize");
+        line.push(b'\n');
+        Arc::new(line)
+    }
+
+    /// Starts writing the lines sent to the returned channel to `stream`, until either is gone.
+    fn spawn_writer(mut stream: TcpStream) -> io::Result<SyncSender<Arc<Vec<u8>>>> {
+        stream.set_nonblocking(false)?;
+        stream.set_write_timeout(Some(Duration::from_secs(5)))?;
+        let (sender, receiver) = mpsc::sync_channel::<Arc<Vec<u8>>>(Self::BACKLOG);
+        thread::spawn(move || {
+            for line in receiver {
+                if stream.write_all(&line).is_err() {
+                    break;
+                }
+            }
+        });
+        Ok(sender)
+    }
+
+    /// Sends what changed since the last call to every spectator, and the whole grid to new ones.
+    pub fn broadcast<R: Rng>(&mut self, sandbox: &Sandbox<R>) {
+        if !self.clients.is_empty() {
+            let diff = self.tracker.diff(sandbox);
+            if !diff.is_empty() {
+                // spectators that can't keep up or went away are dropped, a missed diff would
+                // leave them out of sync anyway
+                let line = Self::line(&diff);
+                self.clients
+                    .retain(|client| client.try_send(line.clone()).is_ok());
+            }
+        }
+
+        while let Ok((stream, _)) = self.listener.accept() {
+            let line = Self::line(&DiffTracker::default().diff(sandbox));
+            if let Ok(client) = Self::spawn_writer(stream) {
+                if client.try_send(line).is_ok() {
+                    self.clients.push(client);
+                }
+            }
+        }
+    }
+}
+
+/// Renders a sandbox streamed by a [`SpectatorServer`] at `addr`, read-only.
+pub fn attach(addr: &str, no_braille: bool) -> anyhow::Result<()> {
+    let stream = TcpStream::connect(addr)?;
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for line in BufReader::new(stream).lines() {
+            let Ok(diff) = line
+                .map_err(anyhow::Error::from)
+                .and_then(|line| Ok(serde_json::from_str::<GridDiff>(&line)?))
+            else {
+                break;
+            };
+            if sender.send(diff).is_err() {
+                break;
+            }
+        }
+    });
+
+    let mut terminal = Terminal::new(CrosstermBackend::new(io::stderr()))?;
+    Tui::setup()?;
+    terminal.clear()?;
+
+    let events = EventHandler::new(16);
+    let mut renderer = Renderer::new(no_braille, None);
+    let mut mirror = Sandbox::<SmallRng>::new(1, 1);
+    let mut connected = true;
+
+    let result = (|| loop {
+        match events.next()? {
+            Event::Key(e)
+                if e.code == KeyCode::Char('q')
+                    || e.code == KeyCode::Esc
+                    || (e.code == KeyCode::Char('c') && e.modifiers == KeyModifiers::CONTROL) =>
+            {
+                return Ok(());
+            }
+            Event::Tick => {
+                while connected {
+                    match receiver.try_recv() {
+                        Ok(diff) => diff.apply(&mut mirror)?,
+                        Err(TryRecvError::Empty) => break,
+                        Err(TryRecvError::Disconnected) => connected = false,
+                    }
+                }
+
+                let status = match connected {
+                    true => locale::format("spectating", &[("addr", &addr)]),
+                    false => locale::text("disconnected").to_string(),
+                };
+                terminal.draw(|f| renderer.render_spectator(&mirror, &status, f))?;
+            }
+            _ => {}
+        }
+    })();
+
+    Tui::reset()?;
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use std::net::TcpStream;
+    use std::time::{Duration, Instant};
+
+    use engine::pixel::rock::Rock;
+    use engine::pixel::sand::Sand;
+    use engine::pixel::Pixel;
+    use engine::sandbox::Sandbox;
+    use rand::rngs::SmallRng;
+
+    use crate::spectate::SpectatorServer;
+
+    #[test]
+    fn test_spectator_laggard() {
+        let mut server = SpectatorServer::bind("127.0.0.1:0").unwrap();
+        let mut sandbox = Sandbox::<SmallRng>::new(100, 100);
+        // never reads what it is sent
+        let _client = TcpStream::connect(server.listener.local_addr().unwrap()).unwrap();
+        while server.clients.is_empty() {
+            server.broadcast(&sandbox);
+        }
+
+        // every cell changes on every broadcast, which the client soon can't take in
+        let start = Instant::now();
+        for i in 0..1000 {
+            let pixel: Pixel = match i % 2 {
+                0 => Sand.into(),
+                _ => Rock.into(),
+            };
+            for idx in 0..sandbox.pixels.len() {
+                let (x, y) = sandbox.index_to_coordinates(idx);
+                sandbox.place_pixel_force(pixel, x, y);
+            }
+            server.broadcast(&sandbox);
+            if server.clients.is_empty() {
+                break;
+            }
+        }
+        assert!(server.clients.is_empty());
+        assert!(start.elapsed() < Duration::from_secs(5));
+    }
+}
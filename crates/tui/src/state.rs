@@ -1,11 +1,73 @@
-use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, MouseEventKind};
+use std::fs;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind};
 use rand::rngs::SmallRng;
-use strum::IntoEnumIterator;
+use rand::Rng;
+use ratatui::layout::{Margin, Rect};
 
+use crate::config::{Background, Brush, BrushShape, Config, Favorite, LaunchConfig};
+#[cfg(feature = "console")]
+use crate::console::Console;
 use crate::event::Event;
+use crate::locale;
+use crate::paths;
 use crate::render::Renderer;
-use engine::pixel::Pixel;
+use engine::catalog::{self, Chord, Element};
+use engine::command::Command;
+use engine::effect::Effects;
+use engine::entity::EntityKind;
+use engine::fixture::Fixture;
+use engine::level::{Level, LevelSession, LevelStatus};
+use engine::palette::Rgb;
+use engine::patch::Patch;
+use engine::pixel::spawner::Spawner;
+use engine::pixel::{Direction, Pixel, PixelFundamental};
+use engine::reaction::Reactions;
 use engine::sandbox::Sandbox;
+use engine::scenario::{Scenario, ScenarioPlayer};
+use engine::scene::Region;
+use engine::snapshot::Snapshot;
+use engine::stats::Stats;
+use engine::temperature;
+
+/// What dragging the mouse over the sandbox does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Tool {
+    /// Paints the active pixel with the left button, erases with the right one
+    Brush,
+    /// Empties the cells under the brush, whatever the active pixel
+    Erase,
+    /// Throws the pixels under the cursor in the direction of the drag
+    Flick,
+    /// Sucks up pixels with the left button, releases them with the right one
+    Vacuum,
+    /// Freezes cells with the left button, thaws them with the right one
+    Freeze,
+    /// Turns the element first clicked on into the active pixel, keeping its motion
+    Transmute,
+    /// Spawns an entity with every click
+    Entity(EntityKind),
+}
+
+/// Guides drawn over the sandbox to help building precise structures.
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq)]
+pub enum Overlay {
+    #[default]
+    Off,
+    /// Coordinates of the cell under the mouse, and a ruler along the border every ten cells
+    Ruler,
+    /// The ruler, and lines along the boundaries of the chunks the far field sleeps in
+    Grid,
+}
+
+/// Time the frontend spent on the last frame, next to the phases of the tick.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct FrameTimings {
+    /// Handling the input and resize events received since the previous frame
+    pub events: Duration,
+    pub render: Duration,
+}
 
 /// Application.
 #[derive(Debug)]
@@ -14,23 +76,195 @@ pub struct State {
     pub should_quit: bool,
     pub sandbox: Sandbox<SmallRng>,
     pub active_pixel: Pixel,
+    pub tool: Tool,
+    pub brush: Brush,
+    /// Settings as read from the config file, saved back on exit
+    pub config: Config,
+    /// Launch settings in effect, with the overrides of the environment and command line
+    launch: LaunchConfig,
+    /// Reaction rates given to every new sandbox
+    reactions: Reactions,
     no_braille: bool,
     mouse_down_event: Option<MouseEvent>,
+    /// Patches undoing the last brush strokes, the latest last
+    undo: Vec<Patch>,
+    /// Patches redoing the strokes undone since the last one painted, the latest last
+    redo: Vec<Patch>,
+    /// Element the transmute stroke started on
+    transmute_from: Option<Pixel>,
     pub pause: bool,
+    /// Ticks to run on the next frame while paused, queued by the step keys
+    steps: u8,
+    /// Ticks owed to the simulation in frames, run once they add up to whole ticks so the
+    /// speed doesn't depend on the frame rate
+    tick_credit: u32,
+    /// Whether the simulation runs as many ticks as fit in a frame, whatever the speed set
+    pub turbo: bool,
+    /// Ticks of the simulation left before quitting, if limited
+    pub ticks_left: Option<u64>,
+    terminal_size: (usize, usize),
+    pub levels: Vec<Level>,
+    /// Preview of the starting scene of every level, see [`State::THUMBNAIL_SIZE`]
+    pub level_thumbnails: Vec<Vec<Vec<Rgb>>>,
+    /// Scenes saved in [`paths::scenes_dir`], offered in the level menu after the levels
+    pub scenes: Vec<Snapshot>,
+    pub scene_thumbnails: Vec<Vec<Vec<Rgb>>>,
+    /// Selected entry of the level menu when it is open, `0` is free play
+    pub level_menu: Option<usize>,
+    pub level: Option<LevelSession>,
+    /// Spawner being configured before it becomes the active pixel
+    pub spawner_prompt: Option<Spawner>,
+    pub stats: Stats,
+    /// Whether the stats file couldn't be read, it is then left as it is rather than overwritten
+    stats_unreadable: bool,
+    pub show_achievements: bool,
+    pub demos: Vec<Scenario>,
+    /// Index and player of the running demo
+    pub demo: Option<(usize, ScenarioPlayer)>,
+    /// Pixels sucked up by the vacuum, released last in first out
+    pub canister: Vec<Pixel>,
+    pub effects: Effects,
+    /// Sandbox cell shown in the top left corner of the viewport
+    pub camera: (usize, usize),
+    /// Camera and viewport size last sent to the sandbox as its focus
+    focus: Option<((usize, usize), (usize, usize))>,
+    /// Whether WASD steer a player avatar, which is spawned on the next tick
+    pub player_mode: bool,
+    /// Id of the player avatar once it is spawned
+    pub player: Option<u32>,
+    /// Short message for the user, e.g. the outcome of a command
+    pub notice: Option<String>,
+    /// Hints of the running demo being shown, with the ticks they stay up for
+    pub toasts: Vec<(String, u32)>,
+    /// First key of the chord being typed, with the ticks its completions stay up for
+    pub chord: Option<(char, u32)>,
+    /// Whether the terminal window has the focus
+    focused: bool,
+    /// Tick events received since the terminal lost the focus
+    background_ticks: u32,
+    /// Whether the overlay timing every phase of the last frame is shown
+    pub show_timings: bool,
+    pub frame_timings: FrameTimings,
+    /// Whether the inspector charting the history of the pixel under the mouse is shown
+    #[cfg(feature = "debug")]
+    pub show_inspector: bool,
+    /// Whether the scripting console is open, it keeps its variables and history while closed
+    #[cfg(feature = "console")]
+    pub show_console: bool,
+    #[cfg(feature = "console")]
+    pub console: Console,
+    pub overlay: Overlay,
+    /// Sandbox cell under the mouse when it last moved
+    pub cursor: Option<(usize, usize)>,
+    /// Whether the simulation runs in slow motion, showing why the pixels around the mouse
+    /// moved
+    pub annotating: bool,
+    /// Frames since the last tick in slow motion
+    slow_frames: u32,
 }
 
 impl State {
     /// Constructs a new instance of [`State`].
-    pub fn new(width: usize, height: usize, no_braille: bool) -> Self {
-        let (width, height) = Self::calculate_sandbox_size(width, height, no_braille);
+    pub fn new(width: usize, height: usize, config: Config, launch: LaunchConfig) -> Self {
+        let no_braille = launch.no_braille();
+        let (reactions, notice) = match paths::reactions_file().filter(|path| path.exists()) {
+            Some(path) => match Reactions::load(path) {
+                Ok(reactions) => (reactions, None),
+                Err(e) => (Reactions::builtin(), Some(format!("{:#}", e))),
+            },
+            None => (Reactions::builtin(), None),
+        };
+        let (stats, stats_notice) = match paths::stats_file().map(Stats::load) {
+            Some(Err(e)) => (
+                Stats::default(),
+                Some(locale::format(
+                    "stats_unreadable",
+                    &[("error", &format!("{:#}", e))],
+                )),
+            ),
+            Some(Ok(stats)) => (stats, None),
+            None => (Stats::default(), None),
+        };
+        // both may have been set by hand
+        let speed_notice = (config.tps.is_some() && config.substeps.is_some())
+            .then(|| locale::text("substeps_ignored").to_string());
+        let mut sandbox = Self::free_play_sandbox(&launch, (width, height));
+        sandbox.set_reactions(reactions.clone());
+        sandbox.set_config(config.sandbox);
+        let levels = Level::builtin();
+        let level_thumbnails = levels.iter().map(Self::level_thumbnail).collect();
+        let scenes = Self::load_scenes();
+        let scene_thumbnails = scenes.iter().map(Self::scene_thumbnail).collect();
 
         Self {
             should_quit: false,
-            sandbox: Sandbox::<SmallRng>::new(width, height),
+            sandbox,
             active_pixel: Default::default(),
+            tool: Tool::Brush,
+            brush: Brush::default(),
+            config,
+            launch,
             no_braille,
             mouse_down_event: None,
+            undo: Vec::new(),
+            redo: Vec::new(),
+            transmute_from: None,
             pause: false,
+            steps: 0,
+            tick_credit: 0,
+            turbo: false,
+            ticks_left: None,
+            terminal_size: (width, height),
+            levels,
+            level_thumbnails,
+            scenes,
+            scene_thumbnails,
+            level_menu: None,
+            level: None,
+            spawner_prompt: None,
+            stats,
+            stats_unreadable: stats_notice.is_some(),
+            show_achievements: false,
+            demos: Scenario::demos(),
+            demo: None,
+            canister: Vec::new(),
+            effects: Effects::default(),
+            camera: (0, 0),
+            focus: None,
+            player_mode: false,
+            player: None,
+            notice: notice.or(stats_notice).or(speed_notice),
+            toasts: Vec::new(),
+            chord: None,
+            focused: true,
+            background_ticks: 0,
+            show_timings: false,
+            frame_timings: FrameTimings::default(),
+            #[cfg(feature = "debug")]
+            show_inspector: false,
+            #[cfg(feature = "console")]
+            show_console: false,
+            #[cfg(feature = "console")]
+            console: Console::default(),
+            overlay: Overlay::Off,
+            cursor: None,
+            annotating: false,
+            slow_frames: 0,
+            reactions,
+        }
+    }
+
+    pub fn save_stats(&self) -> anyhow::Result<()> {
+        match paths::stats_file().filter(|_| !self.stats_unreadable) {
+            Some(path) => self.stats.save(path),
+            None => Ok(()),
+        }
+    }
+
+    pub fn save_config(&self) -> anyhow::Result<()> {
+        match paths::config_file() {
+            Some(path) => self.config.save(path),
+            None => Ok(()),
         }
     }
 
@@ -42,12 +276,262 @@ impl State {
         }
     }
 
+    pub fn terminal_size(&self) -> (usize, usize) {
+        self.terminal_size
+    }
+
+    /// Whether the terminal is smaller than the layout, the simulation waits until it grows.
+    pub fn is_too_small(&self) -> bool {
+        let (width, height) = Renderer::min_size();
+        self.terminal_size.0 < width || self.terminal_size.1 < height
+    }
+
+    /// Size of the part of the sandbox that fits on screen, in sandbox cells.
+    pub fn viewport(&self) -> (usize, usize) {
+        Self::calculate_sandbox_size(self.terminal_size.0, self.terminal_size.1, self.no_braille)
+    }
+
+    /// Where the minimap is drawn, if the sandbox is too large to show whole.
+    pub fn minimap_area(&self) -> Option<Rect> {
+        Renderer::minimap_area(
+            self.terminal_size.0,
+            (self.sandbox.width, self.sandbox.height),
+            self.viewport(),
+        )
+    }
+
+    /// Moves the camera to show as much of the sandbox around (`x`, `y`) as possible.
+    fn center_camera(&mut self, x: usize, y: usize) {
+        let (width, height) = self.viewport();
+        self.camera = (
+            x.saturating_sub(width / 2)
+                .min(self.sandbox.width.saturating_sub(width)),
+            y.saturating_sub(height / 2)
+                .min(self.sandbox.height.saturating_sub(height)),
+        );
+    }
+
+    /// Lets the sandbox know what is on screen when it updates the far field less often.
+    fn update_focus(&mut self) {
+        let focus = Some((self.camera, self.viewport()));
+        if self.sandbox.config().far_field_period.is_none() || self.focus == focus {
+            return;
+        }
+        self.focus = focus;
+        let ((x, y), (width, height)) = (self.camera, self.viewport());
+        self.sandbox.queue_command(Command::Focus {
+            x,
+            y,
+            width,
+            height,
+        });
+    }
+
+    /// Jumps the camera to the part of the sandbox under a click on the minimap.
+    fn click_minimap(&mut self, e: &MouseEvent) -> bool {
+        let Some(area) = self.minimap_area() else {
+            return false;
+        };
+        let inner = area.inner(&Margin::new(1, 1));
+        let click = Rect::new(e.column, e.row, 1, 1);
+        if !inner.intersects(click) {
+            // clicks on the border don't paint under the minimap either
+            return area.intersects(click);
+        }
+        let x = (e.column - inner.x) as usize * self.sandbox.width / inner.width as usize;
+        let y = (e.row - inner.y) as usize * self.sandbox.height / inner.height as usize;
+        self.center_camera(x, y);
+        true
+    }
+
     /// Handles the tick event of the terminal.
     pub fn tick(&mut self) {
+        // the sandbox may have been resized under the camera
+        let (x, y) = self.camera;
+        let (width, height) = self.viewport();
+        self.center_camera(x + width / 2, y + height / 2);
+        self.update_focus();
+
         self.handle_mouse_down_event();
-        if !self.pause {
+        self.update_annotated_region();
+        if self.pause && self.steps == 0 {
+            self.sandbox.apply_commands();
+        } else if self.pause {
+            for _ in 0..std::mem::take(&mut self.steps) {
+                self.tick_simulation();
+                self.effects.tick(self.sandbox.events());
+            }
+            // demos unpause as they play on
+            self.pause = true;
+        } else if self.annotating && !self.slow_frame() {
+            // the commands wait for the next tick, so the annotations of the last one stay up
+        } else if self.turbo {
+            // leaves a quarter of the frame to draw it and handle the input
+            let frame = Duration::from_millis(self.launch.frame_millis());
+            let start = Instant::now();
+            while start.elapsed() < frame * 3 / 4 && !self.pause && !self.should_quit {
+                self.tick_simulation();
+                self.effects.tick(self.sandbox.events());
+            }
+        } else {
+            let frame_rate = self.launch.frame_rate();
+            self.tick_credit += self.config.tps();
+            for _ in 0..self.tick_credit / frame_rate {
+                self.tick_simulation();
+                self.effects.tick(self.sandbox.events());
+                if self.pause || self.should_quit {
+                    break;
+                }
+            }
+            self.tick_credit %= frame_rate;
+        }
+        self.end_stroke();
+        self.track_player();
+        self.update_toasts();
+        self.chord = self
+            .chord
+            .filter(|&(_, ticks)| ticks > 1)
+            .map(|(key, ticks)| (key, ticks - 1));
+        // commands carry elements by name, keep their default state so recorded runs replay
+        self.canister.extend(
+            self.sandbox
+                .take_vacuumed()
+                .iter()
+                .filter_map(|pixel| Pixel::from_name(pixel.name())),
+        );
+    }
+
+    /// Brush strokes that can be undone
+    const UNDO_STROKES: usize = 32;
+
+    /// Keeps the patch undoing the brush stroke once the mouse is up and the last commands of
+    /// the stroke were applied.
+    fn end_stroke(&mut self) {
+        if self.mouse_down_event.is_some() || self.sandbox.has_queued_commands() {
+            return;
+        }
+        if let Some(patch) = self.sandbox.take_patch() {
+            self.undo.push(patch);
+            let overflow = self.undo.len().saturating_sub(Self::UNDO_STROKES);
+            self.undo.drain(..overflow);
+            self.redo.clear();
+        }
+    }
+
+    /// Undoes the latest brush stroke when `undo` is set, redoes the latest undone one otherwise.
+    fn step_history(&mut self, undo: bool) {
+        let (from, to) = match undo {
+            true => (&mut self.undo, &mut self.redo),
+            false => (&mut self.redo, &mut self.undo),
+        };
+        let Some(patch) = from.pop() else {
+            self.notice = Some(
+                locale::text(match undo {
+                    true => "nothing_to_undo",
+                    false => "nothing_to_redo",
+                })
+                .to_string(),
+            );
+            return;
+        };
+        match self.sandbox.apply_patch(&patch) {
+            Some(inverse) => to.push(inverse),
+            // the sandbox was resized since, none of the strokes line up with it any more
+            None => {
+                from.clear();
+                to.clear();
+                self.notice = Some(locale::text("strokes_gone").to_string());
+            }
+        }
+    }
+
+    /// Width and height in sandbox cells of the region annotated around the mouse
+    pub const ANNOTATED_SIZE: usize = 16;
+    /// Frames per tick in slow motion
+    const SLOW_MOTION: u32 = 8;
+
+    /// Counts a frame in slow motion, returns whether the simulation ticks on it.
+    fn slow_frame(&mut self) -> bool {
+        self.slow_frames = (self.slow_frames + 1) % Self::SLOW_MOTION;
+        self.slow_frames == 0
+    }
+
+    /// Annotates the moves of the pixels around the mouse while annotating.
+    fn update_annotated_region(&mut self) {
+        let region = self
+            .cursor
+            .filter(|_| self.annotating)
+            .map(|(x, y)| Region {
+                x: x.saturating_sub(Self::ANNOTATED_SIZE / 2),
+                y: y.saturating_sub(Self::ANNOTATED_SIZE / 2),
+                width: Self::ANNOTATED_SIZE,
+                height: Self::ANNOTATED_SIZE,
+            });
+        self.sandbox.annotate(region);
+    }
+
+    /// How long a hint stays up, about four seconds
+    const TOAST_TICKS: u32 = 240;
+
+    /// Ages the toasts, and shows the hints the demo triggered on its last tick.
+    fn update_toasts(&mut self) {
+        self.toasts.iter_mut().for_each(|(_, ticks)| *ticks -= 1);
+        self.toasts.retain(|(_, ticks)| *ticks > 0);
+        if let Some((_, player)) = self.demo.as_mut() {
+            self.toasts.extend(
+                player
+                    .take_hints()
+                    .into_iter()
+                    .map(|hint| (hint, Self::TOAST_TICKS)),
+            );
+        }
+    }
+
+    fn tick_simulation(&mut self) {
+        if let Some(level) = self.level.as_mut() {
+            let was_won = level.progress().status == LevelStatus::Won;
+            if level.tick(&mut self.sandbox).status == LevelStatus::Won && !was_won {
+                let (ticks, time) = level.timer();
+                if self
+                    .stats
+                    .record_level_time(&level.level().name, ticks, time)
+                {
+                    self.notice = Some(locale::text("new_best_time").to_string());
+                }
+            }
+        } else if let Some((idx, player)) = self.demo.as_mut() {
+            self.pause = player.tick(&mut self.sandbox);
+            if player.is_finished() {
+                let next = *idx + 1;
+                self.load_demo(Some(next));
+            }
+        } else {
             self.sandbox.tick();
         }
+        self.stats.record_tick(&self.sandbox);
+
+        if let Some(ticks_left) = self.ticks_left.as_mut() {
+            *ticks_left = ticks_left.saturating_sub(1);
+            if *ticks_left == 0 {
+                self.quit();
+            }
+        }
+    }
+
+    /// Counts a tick event, returns whether it is skipped while in the background, see
+    /// [`Background`].
+    pub fn should_skip_tick(&mut self) -> bool {
+        if self.focused {
+            return false;
+        }
+        self.background_ticks = self.background_ticks.wrapping_add(1);
+        match self.config.background {
+            Background::Run => false,
+            Background::Slow => !self
+                .background_ticks
+                .is_multiple_of(Background::SLOW_FACTOR),
+            Background::Pause => true,
+        }
     }
 
     /// Set running to false to quit the application.
@@ -57,102 +541,981 @@ impl State {
 
     pub fn update(&mut self, event: Event) {
         match event {
+            // nothing is drawn but the size warning, so nothing happens either
+            Event::Tick | Event::Mouse(_) if self.is_too_small() => {}
             Event::Tick => self.tick(),
             Event::Key(key) => self.handle_key_event(key),
             Event::Mouse(mouse) => {
                 self.handle_mouse_event(mouse);
             }
+            Event::FocusGained => self.focused = true,
+            Event::FocusLost => {
+                self.focused = false;
+                self.background_ticks = 0;
+            }
             Event::Resize(width, height) => {
-                let (width, height) =
-                    Self::calculate_sandbox_size(width as usize, height as usize, self.no_braille);
-                self.sandbox.resize(width, height);
+                self.terminal_size = (width as usize, height as usize);
+                // levels, demos and configured sizes are fixed, and the sandbox keeps its size
+                // while the terminal is too small to show it
+                let is_fixed =
+                    self.level.is_some() || self.demo.is_some() || self.launch.size().is_some();
+                if !is_fixed && !self.is_too_small() {
+                    let (width, height) = Self::calculate_sandbox_size(
+                        width as usize,
+                        height as usize,
+                        self.no_braille,
+                    );
+                    self.sandbox
+                        .queue_command(Command::Resize { width, height });
+                }
             }
         }
     }
 
     fn handle_key_event(&mut self, e: KeyEvent) {
+        #[cfg(feature = "console")]
+        if self.show_console {
+            return self.handle_console_key_event(e);
+        }
+        if let Some(entity) = self.player {
+            let direction = match e.code {
+                KeyCode::Char('w') => Some(Direction::Up),
+                KeyCode::Char('a') => Some(Direction::Left),
+                KeyCode::Char('s') => Some(Direction::Down),
+                KeyCode::Char('d') => Some(Direction::Right),
+                _ => None,
+            };
+            if let Some(direction) = direction {
+                self.sandbox
+                    .queue_command(Command::Steer { entity, direction });
+                return;
+            }
+        }
+        if self.level_menu.is_some() {
+            return self.handle_level_menu_key_event(e);
+        }
+        if self.spawner_prompt.is_some() {
+            return self.handle_spawner_prompt_key_event(e);
+        }
+        // any other key than a completion drops the chord and does what it usually does
+        if let Some((first, _)) = self.chord.take() {
+            match e.code {
+                KeyCode::Char(c) => {
+                    if let Some(element) = catalog::by_chord(Chord(first, c)) {
+                        return self.select_pixel(element.pixel());
+                    }
+                }
+                KeyCode::Esc => return,
+                _ => {}
+            }
+        }
+
         match e.code {
             KeyCode::Char('c') if e.modifiers == KeyModifiers::CONTROL => self.quit(),
+            KeyCode::Char('u') => self.step_history(true),
+            KeyCode::Char('r') if e.modifiers == KeyModifiers::CONTROL => self.step_history(false),
             KeyCode::Char(' ') => self.pause = !self.pause,
+            KeyCode::Char('l') => self.level_menu = Some(0),
+            KeyCode::Char('a') => self.show_achievements = !self.show_achievements,
+            KeyCode::Char('e') => self.toggle_tool(Tool::Erase),
+            KeyCode::Char('f') => self.toggle_tool(Tool::Flick),
+            KeyCode::Char('v') => self.toggle_tool(Tool::Vacuum),
+            KeyCode::F(2) => self.toggle_tool(Tool::Freeze),
+            // would turn the cheap elements of a level's budget into the scarce ones
+            KeyCode::F(3) if self.level.is_some() => {
+                self.notice = Some(locale::text("no_transmute").to_string())
+            }
+            KeyCode::F(3) => self.toggle_tool(Tool::Transmute),
+            KeyCode::F(4) => self.toggle_tool(Tool::Entity(EntityKind::Ball)),
+            KeyCode::F(5) => self.toggle_tool(Tool::Entity(EntityKind::Boat)),
+            KeyCode::F(6) => self.toggle_player_mode(),
+            KeyCode::F(7) => self.show_timings = !self.show_timings,
+            #[cfg(feature = "debug")]
+            KeyCode::F(8) => {
+                self.show_inspector = !self.show_inspector;
+                if !self.show_inspector {
+                    self.sandbox.track(None);
+                }
+            }
+            KeyCode::F(9) => self.save_snapshot(),
+            KeyCode::F(10) => {
+                self.overlay = match self.overlay {
+                    Overlay::Off => Overlay::Ruler,
+                    Overlay::Ruler => Overlay::Grid,
+                    Overlay::Grid => Overlay::Off,
+                }
+            }
+            KeyCode::F(11) => {
+                self.annotating = !self.annotating;
+                if self.annotating {
+                    self.notice = Some(locale::text("slow_motion").to_string());
+                }
+            }
+            KeyCode::F(12) => self.record_fixture(),
+            #[cfg(feature = "console")]
+            KeyCode::Char('`') => self.show_console = true,
+            KeyCode::Char('d') => match self.demo {
+                Some(_) => self.load_demo(None),
+                None => self.load_demo(Some(0)),
+            },
+            KeyCode::Char('[') => self.brush.resize(-1),
+            KeyCode::Char(']') => self.brush.resize(1),
+            KeyCode::Char('<') => self.brush.thin(-10),
+            KeyCode::Char('>') => self.brush.thin(10),
+            KeyCode::Char('/') => self.brush.toggle_shape(),
+            KeyCode::Char('-') => self.change_tps(false),
+            // `+` is Shift and `=` on most layouts
+            KeyCode::Char('+' | '=') => self.change_tps(true),
+            KeyCode::Tab => self.turbo = !self.turbo,
+            KeyCode::Char('.') if self.pause => self.steps = self.steps.saturating_add(1),
+            KeyCode::Char(',') if self.pause => self.steps = self.steps.saturating_add(10),
+            KeyCode::Char(',') => self.change_ambient_temperature(-5),
+            KeyCode::Char('.') => self.change_ambient_temperature(5),
+            KeyCode::Char(c @ '1'..='9') if e.modifiers.contains(KeyModifiers::ALT) => {
+                self.bind_favorite(c as u8 - b'0')
+            }
+            KeyCode::Char(c) if Self::FAVORITE_KEYS.contains(c) => {
+                let slot = Self::FAVORITE_KEYS.find(c).unwrap() as u8 + 1;
+                self.recall_favorite(slot);
+            }
             KeyCode::Char(c) => {
-                if let Some(pixel) = Pixel::iter().find(|pixel| pixel.hotkey() == c) {
-                    self.active_pixel = pixel;
+                if let Some(pixel) = self.element_by_key(c).map(Element::pixel) {
+                    self.select_pixel(pixel);
+                }
+                // the key may also be a hotkey, which stays selected unless the chord goes on
+                if !catalog::completions(c).is_empty() {
+                    self.chord = Some((c, Self::CHORD_TICKS));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Ticks the completions of a chord stay up for, about two seconds at the default rate.
+    const CHORD_TICKS: u32 = 120;
+
+    /// Element bound to `key`, the user's bindings taking precedence over the builtin hotkeys.
+    fn element_by_key(&self, key: char) -> Option<&'static Element> {
+        match self.launch.element(key) {
+            Some(kind) => Some(catalog::element(&kind.pixel())),
+            None => catalog::by_hotkey(key),
+        }
+    }
+
+    /// Makes `pixel` the active pixel if the level allows it, spawners are configured first and
+    /// selecting a spout again changes what it pours.
+    fn select_pixel(&mut self, pixel: Pixel) {
+        match pixel {
+            Pixel::Spout(_) if self.is_pixel_allowed(pixel) => {
+                self.active_pixel = match self.active_pixel {
+                    Pixel::Spout(spout) => spout.cycle().into(),
+                    _ => pixel,
+                };
+                self.tool = Tool::Brush;
+            }
+            Pixel::Spawner(_) if self.is_pixel_allowed(pixel) => {
+                self.spawner_prompt = Some(match self.active_pixel {
+                    Pixel::Spawner(spawner) => spawner,
+                    _ => Spawner::default(),
+                })
+            }
+            _ if self.is_pixel_allowed(pixel) => {
+                self.active_pixel = pixel;
+                self.tool = Tool::Brush;
+            }
+            _ => {}
+        }
+    }
+
+    /// What Shift and a digit type on a US layout, terminals report those instead of the digit.
+    const FAVORITE_KEYS: &'static str = "!@#$%^&*(";
+    /// Keys bound to something else than an element, which registered elements can't take
+    pub const RESERVED_KEYS: &'static str = " laefvdu[]-+=/<>.,`!@#$%^&*(";
+
+    fn bind_favorite(&mut self, slot: u8) {
+        self.config.set_favorite(Favorite {
+            slot,
+            element: self.active_pixel.into(),
+            brush: self.brush,
+        });
+        self.notice = Some(locale::format(
+            "favorite_bound",
+            &[
+                ("element", &locale::element(self.active_pixel.name())),
+                ("slot", &slot),
+            ],
+        ));
+    }
+
+    fn recall_favorite(&mut self, slot: u8) {
+        match self.config.favorite(slot) {
+            Some(favorite) => {
+                self.brush = favorite.brush;
+                self.select_pixel(favorite.element.pixel());
+            }
+            None => self.notice = Some(locale::format("favorite_unbound", &[("slot", &slot)])),
+        }
+    }
+
+    /// Makes the world `delta` degrees Celsius warmer, starting from room temperature.
+    fn change_ambient_temperature(&mut self, delta: i16) {
+        let celsius = self
+            .sandbox
+            .ambient_temperature()
+            .unwrap_or(temperature::to_celsius(temperature::ROOM));
+        let celsius = (celsius + delta).clamp(-50, 150);
+        self.sandbox.set_ambient_temperature(Some(celsius));
+        self.config.sandbox.ambient = Some(celsius);
+    }
+
+    /// Moves the speed of the simulation to the next of [`Config::TPS_STEPS`], leaving turbo.
+    fn change_tps(&mut self, faster: bool) {
+        let tps = self.config.tps();
+        let next = match faster {
+            true => Config::TPS_STEPS.into_iter().find(|&step| step > tps),
+            false => Config::TPS_STEPS.into_iter().rev().find(|&step| step < tps),
+        };
+        let tps = next.unwrap_or(tps);
+        self.config.set_tps(tps);
+        self.turbo = false;
+        self.notice = Some(locale::format("tps", &[("tps", &tps)]));
+    }
+
+    /// Drops a player avatar at the top of the screen, or takes it out.
+    fn toggle_player_mode(&mut self) {
+        if self.player_mode {
+            if let Some(entity) = self.player.take() {
+                self.sandbox.queue_command(Command::RemoveEntity { entity });
+            }
+            self.player_mode = false;
+            return;
+        }
+        let (width, _) = self.viewport();
+        let (player_width, _) = EntityKind::Player.footprint();
+        self.sandbox.queue_command(Command::SpawnEntity {
+            entity: EntityKind::Player,
+            x: self.camera.0 + width.saturating_sub(player_width) / 2,
+            y: self.camera.1,
+        });
+        self.player_mode = true;
+    }
+
+    /// Picks up the avatar spawned for player mode, and leaves the mode when it is gone.
+    fn track_player(&mut self) {
+        if !self.player_mode {
+            return;
+        }
+        match self.player {
+            Some(id) if self.sandbox.entity(id).is_none() => {
+                self.notice = Some(locale::text("player_died").to_string());
+                self.player_mode = false;
+                self.player = None;
+            }
+            Some(_) => {}
+            None => {
+                // the newest player is the one just spawned
+                self.player = self
+                    .sandbox
+                    .entities()
+                    .iter()
+                    .rev()
+                    .find(|entity| entity.kind == EntityKind::Player)
+                    .map(|entity| entity.id);
+                if self.player.is_none() {
+                    self.notice = Some(locale::text("player_no_room").to_string());
+                    self.player_mode = false;
+                }
+            }
+        }
+    }
+
+    /// Switches to `tool`, or back to the brush if it is already in use.
+    fn toggle_tool(&mut self, tool: Tool) {
+        self.tool = match self.tool == tool {
+            true => Tool::Brush,
+            false => tool,
+        };
+    }
+
+    fn handle_level_menu_key_event(&mut self, e: KeyEvent) {
+        let Some(selected) = self.level_menu else {
+            return;
+        };
+
+        match e.code {
+            KeyCode::Char('c') if e.modifiers == KeyModifiers::CONTROL => self.quit(),
+            KeyCode::Esc | KeyCode::Char('l') => self.level_menu = None,
+            KeyCode::Up => self.level_menu = Some(selected.saturating_sub(1)),
+            KeyCode::Down => {
+                let last = self.levels.len() + self.scenes.len();
+                self.level_menu = Some((selected + 1).min(last));
+            }
+            KeyCode::Enter => {
+                self.level_menu = None;
+                match selected.checked_sub(1 + self.levels.len()) {
+                    Some(idx) => self.load_snapshot(idx),
+                    None => self.load_level(selected.checked_sub(1)),
                 }
             }
             _ => {}
         }
     }
 
+    fn handle_spawner_prompt_key_event(&mut self, e: KeyEvent) {
+        let Some(spawner) = self.spawner_prompt else {
+            return;
+        };
+
+        let period = |delta: i16| {
+            let period = (spawner.period() as i16 + delta).clamp(1, u8::MAX as i16);
+            Some(Spawner::new(spawner.element(), period as u8))
+        };
+        match e.code {
+            KeyCode::Char('c') if e.modifiers == KeyModifiers::CONTROL => self.quit(),
+            KeyCode::Esc => self.spawner_prompt = None,
+            KeyCode::Up => self.spawner_prompt = period(1),
+            KeyCode::Down => self.spawner_prompt = period(-1),
+            KeyCode::PageUp => self.spawner_prompt = period(10),
+            KeyCode::PageDown => self.spawner_prompt = period(-10),
+            KeyCode::Enter => {
+                self.spawner_prompt = None;
+                self.active_pixel = spawner.into();
+                self.tool = Tool::Brush;
+            }
+            KeyCode::Char(c) => {
+                if let Some(element) = self.element_by_key(c).filter(|e| e.placeable) {
+                    self.spawner_prompt = Some(Spawner::new(element.kind, spawner.period()));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[cfg(feature = "console")]
+    fn handle_console_key_event(&mut self, e: KeyEvent) {
+        match e.code {
+            KeyCode::Char('c') if e.modifiers == KeyModifiers::CONTROL => self.quit(),
+            KeyCode::Esc | KeyCode::Char('`') => self.show_console = false,
+            KeyCode::Enter => self.console.submit(&mut self.sandbox, &mut self.config),
+            KeyCode::Backspace => {
+                self.console.input.pop();
+            }
+            KeyCode::Up => self.console.recall(-1),
+            KeyCode::Down => self.console.recall(1),
+            KeyCode::Char(c) => self.console.input.push(c),
+            _ => {}
+        }
+    }
+
+    /// Width and height in sandbox cells of the level previews in the level menu
+    pub const THUMBNAIL_SIZE: (usize, usize) = (12, 6);
+
+    fn level_thumbnail(level: &Level) -> Vec<Vec<Rgb>> {
+        let sandbox = LevelSession::new(level.clone()).create_sandbox();
+        Self::scene_thumbnail(&Snapshot::capture(&sandbox))
+    }
+
+    fn scene_thumbnail(scene: &Snapshot) -> Vec<Vec<Rgb>> {
+        let (width, height) = Self::THUMBNAIL_SIZE;
+        scene.thumbnail(width, height).unwrap_or_default()
+    }
+
+    /// Starts the level at `index`, or goes back to free play when `None`.
+    fn load_level(&mut self, index: Option<usize>) {
+        match index.and_then(|idx| self.levels.get(idx)) {
+            Some(level) => {
+                let mut session = LevelSession::new(level.clone());
+                let sandbox = session.create_sandbox();
+                self.start_session(sandbox);
+                self.level = Some(session);
+                if self.tool == Tool::Transmute {
+                    self.tool = Tool::Brush;
+                }
+            }
+            None => self.load_free_play(),
+        }
+    }
+
+    /// Reads the scenes saved in [`paths::scenes_dir`] by name, leaving out the ones that can't
+    /// be read. Scenes without a name go by their file's.
+    fn load_scenes() -> Vec<Snapshot> {
+        let Some(entries) = paths::scenes_dir().and_then(|dir| fs::read_dir(dir).ok()) else {
+            return Vec::new();
+        };
+        let mut paths = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "rfs"))
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths
+            .into_iter()
+            .filter_map(|path| {
+                let mut scene = Snapshot::load(&path).ok()?;
+                if scene.scene.name.is_none() {
+                    let stem = path.file_stem()?.to_string_lossy();
+                    scene.scene.name = Some(stem.into_owned());
+                }
+                Some(scene)
+            })
+            .collect()
+    }
+
+    /// Starts playing in the saved scene at `index`, by the settings it was saved with.
+    fn load_snapshot(&mut self, index: usize) {
+        let Some(scene) = self.scenes.get(index) else {
+            return;
+        };
+        match scene.restore() {
+            Ok(sandbox) => {
+                let config = sandbox.config();
+                self.open_scene(sandbox);
+                self.sandbox.set_config(config);
+            }
+            Err(e) => self.notice = Some(locale::format("scene_failed", &[("error", &e)])),
+        }
+    }
+
+    /// Starts the demo at `index`, or goes back to free play when `None` or past the last one.
+    fn load_demo(&mut self, index: Option<usize>) {
+        match index.and_then(|idx| self.demos.get(idx).map(|demo| (idx, demo))) {
+            Some((idx, demo)) => {
+                let player = ScenarioPlayer::new(demo.clone());
+                self.start_session(player.create_sandbox());
+                self.demo = Some((idx, player));
+            }
+            None => self.load_free_play(),
+        }
+    }
+
+    fn load_free_play(&mut self) {
+        self.start_session(Self::free_play_sandbox(&self.launch, self.terminal_size));
+    }
+
+    /// An empty sandbox of the configured size and seed, fitted to a terminal of
+    /// `terminal_size` otherwise.
+    fn free_play_sandbox(
+        launch: &LaunchConfig,
+        terminal_size: (usize, usize),
+    ) -> Sandbox<SmallRng> {
+        let (width, height) = launch.size().unwrap_or_else(|| {
+            Self::calculate_sandbox_size(terminal_size.0, terminal_size.1, launch.no_braille())
+        });
+        match launch.seed {
+            Some(seed) => Sandbox::<SmallRng>::new_with_seed(width, height, seed),
+            None => Sandbox::<SmallRng>::new(width, height),
+        }
+    }
+
+    fn start_session(&mut self, mut sandbox: Sandbox<SmallRng>) {
+        sandbox.set_reactions(self.reactions.clone());
+        sandbox.set_config(self.config.sandbox);
+        self.sandbox = sandbox;
+        self.level = None;
+        self.demo = None;
+        self.pause = false;
+        self.active_pixel = Default::default();
+        self.canister.clear();
+        self.undo.clear();
+        self.redo.clear();
+        self.effects.clear();
+        self.toasts.clear();
+        self.camera = (0, 0);
+        self.focus = None;
+        self.player_mode = false;
+        self.player = None;
+        self.stats.start_session();
+    }
+
+    /// Starts playing in `sandbox` built from a scene file, whose size then sticks like one
+    /// given with `--size`.
+    pub fn open_scene(&mut self, sandbox: Sandbox<SmallRng>) {
+        (self.launch.width, self.launch.height) = (Some(sandbox.width), Some(sandbox.height));
+        self.start_session(sandbox);
+    }
+
+    /// Goes on playing in `sandbox`, restored from the scene saved by the last session.
+    pub fn restore_scene(&mut self, sandbox: Sandbox<SmallRng>) {
+        self.start_session(sandbox);
+        self.notice = Some(locale::text("restored").to_string());
+    }
+
+    /// Writes the current run as a regression fixture to the working directory.
+    fn record_fixture(&mut self) {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let path = format!("rustfall-fixture-{}.toml", secs);
+        let result = Fixture::record(&self.sandbox).and_then(|fixture| fixture.save(&path));
+        self.notice = Some(match result {
+            Ok(()) => locale::format("fixture_written", &[("path", &path)]),
+            Err(e) => locale::format("fixture_failed", &[("error", &e)]),
+        });
+    }
+
+    /// Writes the current grid as a snapshot to the working directory, see `rustfall diff`.
+    fn save_snapshot(&mut self) {
+        let secs = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let path = format!("rustfall-{}.rfs", secs);
+        self.notice = Some(match Snapshot::capture(&self.sandbox).save(&path) {
+            Ok(()) => locale::format("snapshot_written", &[("path", &path)]),
+            Err(e) => locale::format("snapshot_failed", &[("error", &e)]),
+        });
+    }
+
+    /// Whether the level, or the scenario the sandbox was built from, lets the player place
+    /// `pixel`.
+    pub fn is_pixel_allowed(&self, pixel: Pixel) -> bool {
+        let level_allows = match (&self.level, pixel) {
+            (_, Pixel::Void(_)) | (None, _) => true,
+            (Some(level), pixel) => level.level().is_allowed(pixel),
+        };
+        level_allows && self.sandbox.is_element_allowed(&pixel)
+    }
+
     fn handle_mouse_event(&mut self, e: MouseEvent) {
+        self.cursor = self.brush_area(&e).map(|(x, y, _, _)| (x, y));
         match e.kind {
-            MouseEventKind::Down(_) => {
+            MouseEventKind::Down(_) if self.click_minimap(&e) => {}
+            MouseEventKind::Down(button) => {
                 self.mouse_down_event = Some(e);
+                // levels count what is placed, undoing would hand the budget back
+                if matches!(self.tool, Tool::Brush | Tool::Erase) && self.level.is_none() {
+                    self.sandbox.record_patch();
+                }
+                let is_erasing = match self.tool {
+                    Tool::Brush => button == MouseButton::Right,
+                    tool => tool == Tool::Erase,
+                };
+                if !is_erasing {
+                    self.activate(e);
+                }
+                if let Tool::Entity(entity) = self.tool {
+                    self.spawn_entity(entity, e);
+                }
             }
             MouseEventKind::Drag(_) => {
-                self.mouse_down_event = Some(e);
-                self.handle_mouse_down_event();
+                let previous = self.mouse_down_event.replace(e);
+                match (self.tool, previous) {
+                    (Tool::Flick, Some(previous)) => self.flick(previous, e),
+                    _ => self.handle_mouse_down_event(),
+                }
             }
             MouseEventKind::Up(_) => {
                 self.mouse_down_event = None;
+                self.transmute_from = None;
             }
+            #[cfg(feature = "debug")]
+            MouseEventKind::Moved if self.show_inspector => self.inspect(e),
             _ => {}
         }
     }
 
+    /// Tracks the pixel under the mouse, which the inspector then follows as it moves.
+    #[cfg(feature = "debug")]
+    fn inspect(&mut self, e: MouseEvent) {
+        let id = self
+            .brush_area(&e)
+            .filter(|&(x, y, _, _)| self.sandbox.is_coordinate_in_bound(x, y))
+            .map(|(x, y, _, _)| self.sandbox.pixels[self.sandbox.coordinates_to_index(x, y)].id())
+            .filter(|&id| id != 0);
+        self.sandbox.track(id);
+    }
+
+    /// Sandbox area under the mouse, offset by the border.
+    fn brush_area(&self, e: &MouseEvent) -> Option<(usize, usize, usize, usize)> {
+        if e.row == 0 || e.column == 0 {
+            return None;
+        }
+        let x = e.column as usize - 1;
+        let y = e.row as usize - 1;
+
+        let (camera_x, camera_y) = self.camera;
+        Some(match self.no_braille {
+            false => (camera_x + x * 2, camera_y + y * 4, 2, 4),
+            true => (camera_x + x, camera_y + y, 1, 1),
+        })
+    }
+
     fn handle_mouse_down_event(&mut self) {
-        let Some(e) = self.mouse_down_event.as_ref() else {
+        let Some(e) = self.mouse_down_event else {
             return;
         };
-        if e.row == 0 || e.column == 0 {
+        let Some(area) = self.brush_area(&e) else {
             return;
+        };
+
+        match (self.tool, e.kind) {
+            (
+                Tool::Brush,
+                MouseEventKind::Down(MouseButton::Right) | MouseEventKind::Drag(MouseButton::Right),
+            )
+            | (Tool::Erase, _) => self.erase(area),
+            (Tool::Brush, _) => self.paint(area),
+            (
+                Tool::Vacuum,
+                MouseEventKind::Down(MouseButton::Right) | MouseEventKind::Drag(MouseButton::Right),
+            ) => self.release(area),
+            (Tool::Vacuum, _) => {
+                let (x, y, width, height) = area;
+                self.sandbox.queue_command(Command::Vacuum {
+                    x,
+                    y,
+                    width,
+                    height,
+                });
+            }
+            (
+                Tool::Freeze,
+                MouseEventKind::Down(MouseButton::Right) | MouseEventKind::Drag(MouseButton::Right),
+            ) => self.freeze(area, false),
+            (Tool::Freeze, _) => self.freeze(area, true),
+            (Tool::Transmute, _) => self.transmute(area),
+            (Tool::Entity(_), _) => {}
+            (Tool::Flick, _) => {}
         }
-        // need to offset by the border
-        let x = e.column as usize - 1;
-        let y = e.row as usize - 1;
+    }
 
-        match self.no_braille {
-            false => {
-                let x = x * 2;
-                let y = y * 4;
+    /// Sandbox cells covered by the brush centred on the terminal cell at `area`.
+    fn brush_cells(
+        &self,
+        (x, y, width, height): (usize, usize, usize, usize),
+    ) -> Vec<(usize, usize, usize, usize)> {
+        if self.brush.shape == BrushShape::Single {
+            return vec![(x + width / 2, y + height / 2, 1, 1)];
+        }
+        let size = self.brush.size as usize;
+        // the brush is centred on the cursor, measured in terminal cells
+        let radius = size as f32 / 2.0;
+        let center = (
+            x as f32 + width as f32 / 2.0,
+            y as f32 + height as f32 / 2.0,
+        );
+        let left = x.saturating_sub(size / 2 * width);
+        let top = y.saturating_sub(size / 2 * height);
+        if self.brush.shape == BrushShape::Square && self.brush.density == 100 {
+            return vec![(left, top, size * width, size * height)];
+        }
 
-                for i in 0..2 {
-                    for j in 0..4 {
-                        self.place_pixel(x + i, y + j);
-                    }
+        let mut rng = rand::thread_rng();
+        (top..top + size * height)
+            .flat_map(|y| (left..left + size * width).map(move |x| (x, y)))
+            .filter(|&(x, y)| match self.brush.shape {
+                BrushShape::Square | BrushShape::Single => true,
+                BrushShape::Circle => {
+                    let dx = (x as f32 + 0.5 - center.0) / width as f32;
+                    let dy = (y as f32 + 0.5 - center.1) / height as f32;
+                    dx * dx + dy * dy <= radius * radius
                 }
+            })
+            .filter(|_| rng.gen_range(0..100) < self.brush.density)
+            .map(|(x, y)| (x, y, 1, 1))
+            .collect()
+    }
+
+    /// Places the active pixel with the brush centred on `area`.
+    fn paint(&mut self, area: (usize, usize, usize, usize)) {
+        for cells in self.brush_cells(area) {
+            self.paint_cells(cells);
+        }
+    }
+
+    /// Empties the cells under the brush centred on `area`.
+    fn erase(&mut self, area: (usize, usize, usize, usize)) {
+        for (x, y, width, height) in self.brush_cells(area) {
+            self.sandbox.queue_command(Command::Erase {
+                x,
+                y,
+                width,
+                height,
+            });
+        }
+    }
+
+    fn paint_cells(&mut self, (x, y, width, height): (usize, usize, usize, usize)) {
+        let command = match self.active_pixel {
+            Pixel::Spawner(spawner) => Command::PlaceSpawner {
+                element: spawner.element().pixel(),
+                period: spawner.period(),
+                x,
+                y,
+                width,
+                height,
+            },
+            Pixel::Spout(spout) => Command::PlaceSpout {
+                element: spout.element().pixel(),
+                x,
+                y,
+                width,
+                height,
+            },
+            pixel => Command::PlaceBrush {
+                pixel,
+                x,
+                y,
+                width,
+                height,
+                force: matches!(pixel, Pixel::Void(_)),
+            },
+        };
+        let queued = match self.level.as_mut() {
+            // the palette shows the remaining budget, nothing else to report on failure
+            Some(level) => level.queue_command(&mut self.sandbox, command).is_ok(),
+            None => {
+                self.sandbox.queue_command(command);
+                true
             }
-            true => self.place_pixel(x, y),
+        };
+        // queued commands are applied on the next tick, so the cells are still empty here
+        if queued {
+            self.stats.record_command(&self.sandbox, &command);
         }
     }
 
-    fn place_pixel(&mut self, x: usize, y: usize) {
-        if x > self.sandbox.width - 1 || y > self.sandbox.height - 1 {
+    fn freeze(&mut self, (x, y, width, height): (usize, usize, usize, usize), frozen: bool) {
+        self.sandbox.queue_command(Command::Freeze {
+            x,
+            y,
+            width,
+            height,
+            frozen,
+        });
+    }
+
+    /// Turns the element the stroke started on into the active pixel under the brush.
+    fn transmute(&mut self, area: (usize, usize, usize, usize)) {
+        let (x, y, _, _) = area;
+        let from = match self.transmute_from {
+            Some(from) => from,
+            None if self.sandbox.is_coordinate_in_bound(x, y) => {
+                let from = self.sandbox.pixels[self.sandbox.coordinates_to_index(x, y)].pixel();
+                self.transmute_from = Some(from);
+                from
+            }
+            None => return,
+        };
+        // erasing and filling empty cells are what the brush is for
+        if matches!(from, Pixel::Void(_)) || matches!(self.active_pixel, Pixel::Void(_)) {
             return;
         }
+        for (x, y, width, height) in self.brush_cells(area) {
+            self.sandbox.queue_command(Command::Transmute {
+                from,
+                to: self.active_pixel,
+                x,
+                y,
+                width,
+                height,
+            });
+        }
+    }
+
+    /// Spawns `entity` centred on a click.
+    fn spawn_entity(&mut self, entity: EntityKind, e: MouseEvent) {
+        let Some((x, y, _, _)) = self.brush_area(&e) else {
+            return;
+        };
+        let (width, height) = entity.footprint();
+        self.sandbox.queue_command(Command::SpawnEntity {
+            entity,
+            x: x.saturating_sub(width / 2),
+            y: y.saturating_sub(height / 2),
+        });
+    }
 
-        match self.active_pixel {
-            Pixel::Void(_) => self.sandbox.place_pixel_force(self.active_pixel, x, y),
-            _ => self.sandbox.place_pixel(self.active_pixel, x, y),
+    /// Activates the switches and timers under a click.
+    fn activate(&mut self, e: MouseEvent) {
+        let Some((x, y, width, height)) = self.brush_area(&e) else {
+            return;
+        };
+        let command = Command::Activate {
+            x,
+            y,
+            width,
+            height,
+        };
+        let is_activatable = command.cells().any(|(x, y)| {
+            self.sandbox.is_coordinate_in_bound(x, y)
+                && self.sandbox.pixels[self.sandbox.coordinates_to_index(x, y)]
+                    .pixel()
+                    .is_activatable()
+        });
+        if is_activatable {
+            self.sandbox.queue_command(command);
+        }
+    }
+
+    /// Releases the last pixel from the canister into the first cell of `area`, if it is empty.
+    fn release(&mut self, (x, y, _, _): (usize, usize, usize, usize)) {
+        let Some(&pixel) = self.canister.last() else {
+            return;
+        };
+        let command = Command::PlaceBrush {
+            pixel,
+            x,
+            y,
+            width: 1,
+            height: 1,
+            force: false,
+        };
+        if self.sandbox.placement_count(&command) == 0 {
+            return;
+        }
+
+        let queued = match self.level.as_mut() {
+            Some(level) => level.queue_command(&mut self.sandbox, command).is_ok(),
+            None => {
+                self.sandbox.queue_command(command);
+                true
+            }
+        };
+        if queued {
+            self.canister.pop();
         }
     }
-}
 
-pub trait PixelHotkey {
-    fn hotkey(&self) -> char;
+    /// Throws the pixels under `from` towards `to`, faster the quicker the drag.
+    fn flick(&mut self, from: MouseEvent, to: MouseEvent) {
+        const STRENGTH: isize = 2;
+        const MAX_SPEED: isize = 12;
+
+        let (Some((x, y, width, height)), Some((to_x, to_y, _, _))) =
+            (self.brush_area(&from), self.brush_area(&to))
+        else {
+            return;
+        };
+        let speed = |from: usize, to: usize| {
+            ((to as isize - from as isize) * STRENGTH).clamp(-MAX_SPEED, MAX_SPEED) as i8
+        };
+
+        self.sandbox.queue_command(Command::Flick {
+            x,
+            y,
+            width,
+            height,
+            dx: speed(x, to_x),
+            dy: speed(y, to_y),
+        });
+    }
 }
 
-impl PixelHotkey for Pixel {
-    fn hotkey(&self) -> char {
-        match self {
-            Pixel::Sand(_) => '1',
-            Pixel::Rock(_) => '2',
-            Pixel::Water(_) => '3',
-            Pixel::Steam(_) => '4',
-            Pixel::Ice(_) => '5',
-            Pixel::Fire(_) => '6',
-            Pixel::EternalFire(_) => '7',
-            Pixel::Wood(_) => '8',
-            Pixel::Void(_) => '0',
+#[cfg(test)]
+mod test {
+    use crossterm::event::{
+        KeyCode, KeyEvent, KeyModifiers, MouseButton, MouseEvent, MouseEventKind,
+    };
+    use engine::pixel::sand::Sand;
+    use engine::pixel::Pixel;
+
+    use crate::config::{Config, LaunchConfig};
+    use crate::event::Event;
+    use crate::locale;
+    use crate::state::{State, Tool};
+
+    /// Paused state over an 8x8 sandbox, so painted pixels stay where they are.
+    fn state() -> State {
+        let mut launch = LaunchConfig::default();
+        launch.set("size", "8x8").unwrap();
+        let mut state = State::new(120, 40, Config::default(), launch);
+        state.pause = true;
+        state
+    }
+
+    fn key(state: &mut State, code: KeyCode, modifiers: KeyModifiers) {
+        state.update(Event::Key(KeyEvent::new(code, modifiers)));
+    }
+
+    /// Presses `button` over the terminal cell at `column`, `row`, then releases it, ticking in
+    /// between so the stroke is applied.
+    fn click(state: &mut State, button: MouseButton, column: u16, row: u16) {
+        for kind in [MouseEventKind::Down(button), MouseEventKind::Up(button)] {
+            state.update(Event::Mouse(MouseEvent {
+                kind,
+                column,
+                row,
+                modifiers: KeyModifiers::NONE,
+            }));
+            state.update(Event::Tick);
+        }
+    }
+
+    fn count_sand(state: &State) -> usize {
+        state
+            .sandbox
+            .pixels
+            .iter()
+            .filter(|p| matches!(p.pixel(), Pixel::Sand(_)))
+            .count()
+    }
+
+    #[test]
+    fn test_state_erase_key() {
+        let mut state = state();
+        key(&mut state, KeyCode::Char('e'), KeyModifiers::NONE);
+        assert_eq!(state.tool, Tool::Erase);
+        key(&mut state, KeyCode::Char('e'), KeyModifiers::NONE);
+        assert_eq!(state.tool, Tool::Brush);
+    }
+
+    #[test]
+    fn test_state_speed_keys() {
+        let mut state = state();
+        key(&mut state, KeyCode::Char('+'), KeyModifiers::SHIFT);
+        assert_eq!(state.config.tps(), 90);
+        key(&mut state, KeyCode::Char('-'), KeyModifiers::NONE);
+        key(&mut state, KeyCode::Char('-'), KeyModifiers::NONE);
+        assert_eq!(state.config.tps(), 45);
+
+        // the brush thins out on other keys
+        key(&mut state, KeyCode::Char('<'), KeyModifiers::SHIFT);
+        assert_eq!((state.brush.density, state.config.tps()), (90, 45));
+    }
+
+    #[test]
+    fn test_state_undo() {
+        let mut state = state();
+        state.active_pixel = Sand.into();
+        // a braille character covers 2x4 cells
+        click(&mut state, MouseButton::Left, 1, 1);
+        assert_eq!(count_sand(&state), 8);
+
+        key(&mut state, KeyCode::Char('u'), KeyModifiers::NONE);
+        assert_eq!(count_sand(&state), 0);
+        key(&mut state, KeyCode::Char('u'), KeyModifiers::NONE);
+        assert_eq!(
+            state.notice.as_deref(),
+            Some(locale::text("nothing_to_undo"))
+        );
+
+        key(&mut state, KeyCode::Char('r'), KeyModifiers::CONTROL);
+        assert_eq!(count_sand(&state), 8);
+    }
+
+    #[test]
+    fn test_state_vacuum() {
+        let mut state = state();
+        for x in 0..4 {
+            for y in 0..4 {
+                state.sandbox.place_pixel_force(Sand.into(), x, y);
+            }
         }
+        key(&mut state, KeyCode::Char('v'), KeyModifiers::NONE);
+        assert_eq!(state.tool, Tool::Vacuum);
+
+        // what is sucked up over several ticks all ends up in the canister
+        click(&mut state, MouseButton::Left, 1, 1);
+        click(&mut state, MouseButton::Left, 2, 1);
+        assert_eq!(state.canister.len(), 16);
+        assert_eq!(count_sand(&state), 0);
+
+        // released a pixel per click, into empty cells only
+        click(&mut state, MouseButton::Right, 4, 2);
+        click(&mut state, MouseButton::Right, 4, 2);
+        click(&mut state, MouseButton::Right, 3, 2);
+        assert_eq!(state.canister.len(), 14);
+        assert_eq!(count_sand(&state), 2);
     }
 }
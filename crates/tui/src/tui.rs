@@ -1,15 +1,32 @@
+use std::io::IsTerminal;
+use std::net::ToSocketAddrs;
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
 use std::{io, panic};
 
 use crossterm::{
-    event::{DisableMouseCapture, EnableMouseCapture},
+    event::{DisableFocusChange, DisableMouseCapture, EnableFocusChange, EnableMouseCapture},
     terminal::{self, EnterAlternateScreen, LeaveAlternateScreen},
 };
+use rand::rngs::SmallRng;
 use ratatui::backend::CrosstermBackend;
 use ratatui::Terminal;
 
+#[cfg(feature = "audio")]
+use crate::audio::Audio;
+use crate::config::{Config, LaunchConfig};
 use crate::event::{Event, EventHandler};
+use crate::locale;
+use crate::metrics::{MetricsSampler, MetricsServer};
+use crate::paths;
 use crate::render::Renderer;
-use crate::state::State;
+use crate::spectate::SpectatorServer;
+use crate::state::{FrameTimings, State};
+use crate::PlayOptions;
+use engine::command::Command;
+use engine::sandbox::Sandbox;
+use engine::snapshot::Snapshot;
 
 pub type CrosstermTerminal = Terminal<CrosstermBackend<io::Stderr>>;
 
@@ -24,34 +41,122 @@ pub struct Tui {
     pub events: EventHandler,
     renderer: Renderer,
     state: State,
+    #[cfg(feature = "audio")]
+    audio: Option<Audio>,
+    spectators: Option<SpectatorServer>,
+    metrics: Option<(MetricsServer, MetricsSampler)>,
+    /// Time between two autosaves and when the last one happened
+    autosave: Option<(Duration, Instant)>,
 }
 
 impl Tui {
-    /// Constructs a new instance of [`Tui`].
-    pub fn try_new(no_braille: bool) -> anyhow::Result<Self> {
+    /// Ticks between two samples of the metrics, a second at full speed
+    const METRICS_TICKS: u64 = 60;
+
+    /// Constructs a new instance of [`Tui`] from the config file and the launch settings in
+    /// effect.
+    pub fn try_new(config: Config, launch: LaunchConfig) -> anyhow::Result<Self> {
         let backend = CrosstermBackend::new(io::stderr());
 
         let terminal = Terminal::new(backend)?;
-        let events = EventHandler::new(16);
-        let renderer = Renderer::new(no_braille);
+        let events = EventHandler::new(launch.frame_millis());
+        let renderer = Renderer::new(launch.no_braille(), launch.palette()?);
+        let autosave = launch
+            .autosave
+            .map(|secs| (Duration::from_secs(secs), Instant::now()));
 
         let rect = terminal.size()?;
-        let state = State::new(rect.width as usize, rect.height as usize, no_braille);
+        let state = State::new(rect.width as usize, rect.height as usize, config, launch);
 
         Ok(Self {
             terminal,
             events,
             renderer,
             state,
+            #[cfg(feature = "audio")]
+            audio: Audio::try_new().ok(),
+            spectators: None,
+            metrics: None,
+            autosave,
         })
     }
 
+    /// Streams the sandbox to spectators attaching to `addr`.
+    pub fn serve(&mut self, addr: impl ToSocketAddrs) -> anyhow::Result<()> {
+        self.spectators = Some(SpectatorServer::bind(addr)?);
+        Ok(())
+    }
+
+    /// Serves the simulation metrics at `/metrics` on `addr`, refreshed every
+    /// [`Self::METRICS_TICKS`] ticks.
+    pub fn serve_metrics(&mut self, addr: impl ToSocketAddrs) -> anyhow::Result<()> {
+        self.metrics = Some((MetricsServer::bind(addr)?, MetricsSampler::default()));
+        Ok(())
+    }
+
+    /// Asks whether to restore the scene saved when the last session crashed, if there is one.
+    pub fn offer_recovery(&mut self) -> anyhow::Result<()> {
+        let Some(path) = paths::recovery_file().filter(|path| path.exists()) else {
+            return Ok(());
+        };
+        if !io::stdin().is_terminal() {
+            return Ok(());
+        }
+
+        eprint!("rustfall didn't exit cleanly last time, restore the scene it saved? [Y/n] ");
+        let mut answer = String::new();
+        io::stdin().read_line(&mut answer)?;
+        if !answer.trim().eq_ignore_ascii_case("n") {
+            match Snapshot::load(&path).and_then(|snapshot| snapshot.restore()) {
+                Ok(sandbox) => self.state.restore_scene(sandbox),
+                Err(e) => self.state.notice = Some(format!("{:#}", e)),
+            }
+        }
+        std::fs::remove_file(&path)?;
+        Ok(())
+    }
+
+    /// Applies the command line options before the first tick.
+    pub fn configure(&mut self, options: &PlayOptions) -> anyhow::Result<()> {
+        if let Some(path) = &options.scene {
+            self.state
+                .open_scene(Sandbox::<SmallRng>::from_scene(path)?);
+        }
+        self.state.pause = options.paused;
+        self.state.ticks_left = options.ticks;
+        for &(x, y, pixel) in &options.place {
+            self.state.sandbox.queue_command(Command::PlaceBrush {
+                pixel,
+                x,
+                y,
+                width: 1,
+                height: 1,
+                force: true,
+            });
+        }
+        Ok(())
+    }
+
     /// Initializes the terminal interface.
     ///
     /// It enables the raw mode and sets terminal properties.
     pub fn enter(&mut self) -> anyhow::Result<()> {
+        Self::setup()?;
+
+        // self.terminal.hide_cursor()?;
+        self.terminal.clear()?;
+        Ok(())
+    }
+
+    /// Enables the raw mode and installs a panic hook restoring the terminal.
+    pub(crate) fn setup() -> anyhow::Result<()> {
         terminal::enable_raw_mode()?;
-        crossterm::execute!(io::stderr(), EnterAlternateScreen, EnableMouseCapture)?;
+        crossterm::execute!(
+            io::stderr(),
+            EnterAlternateScreen,
+            EnableMouseCapture,
+            EnableFocusChange
+        )?;
 
         // Define a custom panic hook to reset the terminal properties.
         // This way, you won't have your terminal messed up if an unexpected error happens.
@@ -60,22 +165,18 @@ impl Tui {
             Self::reset().expect("failed to reset the terminal");
             panic_hook(panic);
         }));
-
-        // self.terminal.hide_cursor()?;
-        self.terminal.clear()?;
         Ok(())
     }
 
     pub fn run(&mut self) -> anyhow::Result<()> {
         while !self.state.should_quit {
             let e = self.events.next()?;
-            match e {
-                Event::Tick => {
-                    self.state.update(e);
-                    self.draw()?;
-                }
-                _ => {
-                    self.state.update(e);
+            // the panic hook already reset the terminal, save the scene before going down
+            match panic::catch_unwind(AssertUnwindSafe(|| self.handle(e))) {
+                Ok(result) => result?,
+                Err(panic) => {
+                    self.save_recovery();
+                    panic::resume_unwind(panic);
                 }
             }
         }
@@ -83,6 +184,77 @@ impl Tui {
         Ok(())
     }
 
+    fn handle(&mut self, e: Event) -> anyhow::Result<()> {
+        match e {
+            // nothing changes on screen while the terminal sleeps in the background
+            Event::Tick if self.state.should_skip_tick() => {}
+            Event::Tick => {
+                self.state.update(e);
+                #[cfg(feature = "audio")]
+                if let Some(audio) = self.audio.as_ref() {
+                    audio.play(self.state.sandbox.events());
+                }
+                if let Some(spectators) = self.spectators.as_mut() {
+                    spectators.broadcast(&self.state.sandbox);
+                }
+                if let Some((server, sampler)) = self.metrics.as_mut() {
+                    sampler.record(&self.state.sandbox);
+                    if sampler.ticks() >= Self::METRICS_TICKS {
+                        server.publish(&sampler.sample(&self.state.sandbox));
+                    }
+                }
+                if let Some((period, last)) = self.autosave.as_mut() {
+                    if last.elapsed() >= *period {
+                        *last = Instant::now();
+                        if let Err(e) = self.save_scene() {
+                            self.state.notice = Some(locale::format(
+                                "autosave_failed",
+                                &[("error", &format!("{:#}", e))],
+                            ));
+                        }
+                    }
+                }
+                // the overlay shows how long the previous frame took to render
+                let start = Instant::now();
+                self.draw()?;
+                self.state.frame_timings = FrameTimings {
+                    events: Duration::ZERO,
+                    render: start.elapsed(),
+                };
+            }
+            _ => {
+                let start = Instant::now();
+                self.state.update(e);
+                self.state.frame_timings.events += start.elapsed();
+            }
+        }
+        Ok(())
+    }
+
+    /// Saves the scene for the next launch to offer restoring it, after a panic.
+    fn save_recovery(&self) {
+        match self.save_scene() {
+            Ok(Some(path)) => eprintln!(
+                "The scene was saved to {}, restore it on the next launch",
+                path.display()
+            ),
+            Ok(None) => {}
+            Err(e) => eprintln!("Failed to save the scene: {:#}", e),
+        }
+    }
+
+    /// Saves the scene to the recovery file, returning where it went.
+    fn save_scene(&self) -> anyhow::Result<Option<PathBuf>> {
+        let Some(path) = paths::recovery_file() else {
+            return Ok(None);
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        Snapshot::capture(&self.state.sandbox).save(&path)?;
+        Ok(Some(path))
+    }
+
     /// [`Draw`] the terminal interface by [`rendering`] the widgets.
     ///
     /// [`Draw`]: tui::Terminal::draw
@@ -97,9 +269,14 @@ impl Tui {
     ///
     /// This function is also used for the panic hook to revert
     /// the terminal properties if unexpected errors occur.
-    fn reset() -> anyhow::Result<()> {
+    pub(crate) fn reset() -> anyhow::Result<()> {
         terminal::disable_raw_mode()?;
-        crossterm::execute!(io::stderr(), LeaveAlternateScreen, DisableMouseCapture)?;
+        crossterm::execute!(
+            io::stderr(),
+            LeaveAlternateScreen,
+            DisableMouseCapture,
+            DisableFocusChange
+        )?;
         Ok(())
     }
 
@@ -108,6 +285,12 @@ impl Tui {
     /// It disables the raw mode and reverts back the terminal properties.
     pub fn exit(&mut self) -> anyhow::Result<()> {
         Self::reset()?;
+        self.state.save_stats()?;
+        self.state.save_config()?;
+        // autosaves are only offered back after a session that didn't exit cleanly
+        if let Some(path) = paths::recovery_file().filter(|path| path.exists()) {
+            std::fs::remove_file(path)?;
+        }
         // self.terminal.show_cursor()?;
         Ok(())
     }
@@ -0,0 +1,163 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use anyhow::{anyhow, Context};
+use engine::catalog;
+use engine::sandbox::Sandbox;
+use engine::snapshot::Snapshot;
+use rand::Rng;
+
+const USAGE: &str = "usage: rustfall render <scene.rfs> [--ticks <n>] [--every <n>] [--out <dir>] [--scale <n>] [--fps <n>] [--video <file.mp4>]";
+
+/// Options of a headless recording of a scene.
+#[derive(Debug)]
+pub struct VideoOptions {
+    scene: PathBuf,
+    ticks: u64,
+    /// Ticks between two frames
+    every: u64,
+    /// Directory the frames are written to, created if missing
+    out: PathBuf,
+    /// Pixels of the image for every cell along each side
+    scale: usize,
+    /// Frames per second of the video
+    fps: u32,
+    /// Where ffmpeg encodes the frames to, they are only written if unset
+    video: Option<PathBuf>,
+}
+
+impl VideoOptions {
+    pub fn parse(args: &[String]) -> anyhow::Result<Self> {
+        let mut scene = None;
+        let mut options = Self {
+            scene: PathBuf::new(),
+            ticks: 600,
+            every: 1,
+            out: PathBuf::from("frames"),
+            scale: 4,
+            fps: 30,
+            video: None,
+        };
+
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            let mut value = || args.next().ok_or_else(|| anyhow!(USAGE));
+            match arg.as_str() {
+                "--ticks" => options.ticks = value()?.parse().context(USAGE)?,
+                "--every" => options.every = value()?.parse().context(USAGE)?,
+                "--out" => options.out = PathBuf::from(value()?),
+                "--scale" => options.scale = value()?.parse().context(USAGE)?,
+                "--fps" => options.fps = value()?.parse().context(USAGE)?,
+                "--video" => options.video = Some(PathBuf::from(value()?)),
+                s if !s.starts_with("--") && scene.is_none() => scene = Some(PathBuf::from(s)),
+                _ => anyhow::bail!(USAGE),
+            }
+        }
+        options.scene = scene.ok_or_else(|| anyhow!(USAGE))?;
+        if options.every == 0 || options.scale == 0 || options.fps == 0 {
+            anyhow::bail!(USAGE);
+        }
+        Ok(options)
+    }
+
+    /// Path of the frame numbered `frame`, in the pattern ffmpeg is given.
+    fn frame_path(&self, frame: u64) -> PathBuf {
+        self.out.join(format!("frame-{:05}.ppm", frame))
+    }
+
+    /// Arguments of the ffmpeg invocation encoding the frames into `video`.
+    fn ffmpeg_args(&self, video: &Path) -> Vec<String> {
+        [
+            "-y",
+            "-loglevel",
+            "error",
+            "-framerate",
+            &self.fps.to_string(),
+            "-i",
+            &self.out.join("frame-%05d.ppm").to_string_lossy(),
+            "-pix_fmt",
+            "yuv420p",
+            // the encoder wants even sizes
+            "-vf",
+            "pad=ceil(iw/2)*2:ceil(ih/2)*2",
+            &video.to_string_lossy(),
+        ]
+        .map(String::from)
+        .to_vec()
+    }
+}
+
+/// Writes the sandbox as a binary PPM image, every cell a `scale` pixels wide square in the
+/// catalog colour of its element.
+fn write_frame<R: Rng>(sandbox: &Sandbox<R>, scale: usize, path: &Path) -> anyhow::Result<()> {
+    let file =
+        File::create(path).with_context(|| format!("failed to create {}", path.display()))?;
+    let mut out = BufWriter::new(file);
+    write!(
+        out,
+        "P6\n{} {}\n255\n",
+        sandbox.width * scale,
+        sandbox.height * scale
+    )?;
+    for row in sandbox.pixels.chunks(sandbox.width) {
+        let line = row
+            .iter()
+            .flat_map(|cell| {
+                let color = catalog::element(&cell.pixel()).color;
+                [color.0, color.1, color.2].repeat(scale)
+            })
+            .collect::<Vec<_>>();
+        for _ in 0..scale {
+            out.write_all(&line)?;
+        }
+    }
+    out.flush()?;
+    Ok(())
+}
+
+/// Ticks a saved scene without a terminal, writing a frame every few ticks, and encodes them
+/// into a video with ffmpeg if asked to.
+pub fn run(options: VideoOptions) -> anyhow::Result<()> {
+    let mut sandbox = Snapshot::load(&options.scene)?.restore()?;
+    std::fs::create_dir_all(&options.out)
+        .with_context(|| format!("failed to create {}", options.out.display()))?;
+
+    let mut frames = 0;
+    write_frame(&sandbox, options.scale, &options.frame_path(frames))?;
+    for tick in 1..=options.ticks {
+        sandbox.tick();
+        if tick.is_multiple_of(options.every) {
+            frames += 1;
+            write_frame(&sandbox, options.scale, &options.frame_path(frames))?;
+        }
+    }
+    println!("{} frames written to {}", frames + 1, options.out.display());
+
+    match &options.video {
+        Some(video) => {
+            let status = Command::new("ffmpeg")
+                .args(options.ffmpeg_args(video))
+                .status()
+                .context("failed to run ffmpeg, is it installed?")?;
+            if !status.success() {
+                anyhow::bail!("ffmpeg failed with {}", status);
+            }
+            println!("encoded {}", video.display());
+        }
+        None => {
+            // quoted for the shell it is pasted into
+            let args = options
+                .ffmpeg_args(Path::new("out.mp4"))
+                .into_iter()
+                .map(|arg| match arg.contains(['(', '*', '%', ' ']) {
+                    true => format!("'{}'", arg),
+                    false => arg,
+                })
+                .collect::<Vec<_>>();
+            println!("encode them with: ffmpeg {}", args.join(" "));
+        }
+    }
+    Ok(())
+}